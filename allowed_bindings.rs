@@ -44,10 +44,17 @@ bind! {
     // ext_php_rs_php_build_id,
     // ext_php_rs_zend_object_alloc,
     // ext_php_rs_zend_object_release,
+    // ext_php_rs_zend_object_store_ctor_failed,
+    // ext_php_rs_zval_make_ref,
+    // ext_php_rs_zval_unwrap_ref,
+    // ext_php_rs_zend_get_class_constant,
+    // ext_php_rs_zend_function_deprecate,
     // ext_php_rs_zend_string_init,
     // ext_php_rs_zend_string_release,
     // ext_php_rs_is_known_valid_utf8,
     // ext_php_rs_set_known_valid_utf8,
+    // ext_php_rs_zend_string_separate,
+    // ext_php_rs_zend_array_is_list,
     object_properties_init,
     php_error_docref,
     php_info_print_table_end,
@@ -64,6 +71,7 @@ bind! {
     zend_ce_arithmetic_error,
     zend_ce_compile_error,
     zend_ce_division_by_zero_error,
+    zend_ce_error,
     zend_ce_error_exception,
     zend_ce_exception,
     zend_ce_parse_error,
@@ -81,20 +89,34 @@ bind! {
     zend_class_entry,
     zend_declare_class_constant,
     zend_declare_property,
+    zend_compare,
+    add_function,
+    sub_function,
+    mul_function,
+    div_function,
+    mod_function,
+    pow_function,
+    concat_function,
     zend_do_implement_interface,
     zend_execute_data,
     zend_function_entry,
     zend_hash_clean,
+    zend_hash_del_bucket,
     zend_hash_find_known_hash,
     _zend_hash_find_known_hash,
     zend_hash_index_del,
     zend_hash_index_find,
     zend_hash_index_update,
     zend_hash_next_index_insert,
+    zend_hash_packed_to_hash,
+    zend_hash_real_init_packed,
+    zend_hash_rehash,
+    zend_hash_sort_ex,
     zend_hash_str_del,
     zend_hash_str_find,
     zend_hash_str_update,
     zend_internal_arg_info,
+    zend_sort,
     zend_is_callable,
     zend_is_identical,
     zend_is_iterable,
@@ -114,13 +136,26 @@ bind! {
     zend_register_long_constant,
     zend_register_string_constant,
     zend_resource,
+    zend_register_list_destructors_ex,
+    zend_register_resource,
+    zend_list_close,
+    zend_list_delete,
+    rsrc_dtor_func_t,
     zend_string,
     zend_string_init_interned,
     zend_throw_exception_ex,
     zend_throw_exception_object,
     zend_type,
+    zend_update_property_ex,
     zend_value,
     zend_wrong_parameters_count_error,
+    zend_argument_type_error,
+    zend_parse_arg_bool_slow,
+    zend_parse_arg_long_slow,
+    zend_parse_arg_double_slow,
+    zend_parse_arg_str_slow,
+    zend_zval_type_name,
+    zend_zval_value_name,
     zval,
     CONST_CS,
     CONST_DEPRECATED,
@@ -141,6 +176,7 @@ bind! {
     E_RECOVERABLE_ERROR,
     E_DEPRECATED,
     E_USER_DEPRECATED,
+    HASH_FLAG_PACKED,
     HT_MIN_SIZE,
     IS_ARRAY,
     IS_ARRAY_EX,
@@ -269,6 +305,15 @@ bind! {
     sapi_globals,
     sapi_module,
     php_printf,
+    php_output_start_default,
+    php_output_get_contents,
+    php_output_discard,
+    php_var_export_ex,
+    php_var_dump,
+    zend_print_zval_r_to_str,
+    smart_str,
+    smart_str_erealloc,
+    smart_str_realloc,
     __zend_malloc,
     tsrm_get_ls_cache,
     executor_globals_offset,
@@ -311,5 +356,25 @@ bind! {
     php_module_shutdown,
     php_request_startup,
     php_request_shutdown,
-    instanceof_function_slow
+    instanceof_function_slow,
+    zend_create_fake_closure,
+    zend_set_function_arg_flags,
+    zend_is_callable_ex,
+    zend_release_fcall_info_cache,
+    zend_fcall_info_cache,
+    zend_ce_generator,
+    zend_iterator_dtor,
+    object_init_ex,
+    zend_std_get_static_method,
+    zend_std_get_static_property,
+    zend_update_static_property_ex,
+    BP_VAR_R,
+    BP_VAR_W,
+    zend_error,
+    zend_class_constant,
+    zend_read_property,
+    ZEND_ACC_ENUM,
+    ZEND_CLASS_CONST_IS_CASE,
+    zend_enum_get_case,
+    zend_argument_value_error
 }
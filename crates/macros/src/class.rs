@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use crate::STATE;
+use crate::describe::Describe;
+use crate::helpers::Deprecation;
 use anyhow::{anyhow, bail, Context, Result};
 use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Span, TokenStream};
@@ -11,18 +12,15 @@ use syn::{Attribute, AttributeArgs, Expr, Fields, FieldsNamed, ItemStruct, LitSt
 #[derive(Debug, Default)]
 pub struct Class {
     pub class_name: String,
-    pub struct_path: String,
     pub parent: Option<String>,
     pub interfaces: Vec<String>,
     pub docs: Vec<String>,
-    pub methods: Vec<crate::method::Method>,
-    pub constructor: Option<crate::method::Method>,
-    pub constants: Vec<crate::constant::Constant>,
     pub properties: HashMap<String, Property>,
     /// A function name called when creating the class entry. Given an instance
     /// of `ClassBuilder` and must return it.
     pub modifier: Option<String>,
     pub flags: Option<String>,
+    pub deprecated: Option<Deprecation>,
 }
 
 #[derive(Debug)]
@@ -39,6 +37,7 @@ pub struct AttrArgs {
     name: Option<String>,
     modifier: Option<String>,
     flags: Option<Expr>,
+    deprecated: Option<Deprecation>,
 }
 
 pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream> {
@@ -93,14 +92,7 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
                                 .ok_or_else(|| anyhow!("Only named fields can be properties."))?
                                 .to_string();
                             let prop_name = prop.rename.unwrap_or_else(|| field_name.clone());
-                            result_prop = Some((
-                                prop_name,
-                                Property::field(
-                                    field_name,
-                                    vec![],
-                                    prop.flags.map(|flags| flags.to_token_stream().to_string()),
-                                ),
-                            ));
+                            result_prop = Some((prop_name, Property::field(field_name, vec![])));
                         }
                         ParsedAttribute::Comment(doc) => docs.push(doc),
                         _ => bail!("Attribute {:?} is not valid for struct fields.", attr),
@@ -118,36 +110,145 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
 
     let ItemStruct { ident, .. } = &input;
     let class_name = args.name.unwrap_or_else(|| ident.to_string());
-    let struct_path = ident.to_string();
     let flags = args.flags.map(|flags| flags.to_token_stream().to_string());
     let class = Class {
         class_name,
-        struct_path,
         parent,
         interfaces,
         docs: comments,
         properties,
         modifier: args.modifier,
         flags,
-        ..Default::default()
+        deprecated: args.deprecated,
     };
+    let registered_class_impl = generate_registered_class_impl(ident, &class)?;
 
-    let mut state = STATE.lock();
+    Ok(quote! {
+        #input
 
-    if state.built_module {
-        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
-    }
+        ::ext_php_rs::class_derives!(#ident);
 
-    if state.startup_function.is_some() {
-        bail!("The `#[php_startup]` macro must be called after all the classes have been defined.");
-    }
+        #registered_class_impl
+    })
+}
 
-    state.classes.insert(ident.to_string(), class);
+/// Generates an implementation for `RegisteredClass` on the given class. The
+/// contents of the `#[php_impl]` block of the class, if any, are retrieved
+/// through the `PhpClassImpl` trait.
+fn generate_registered_class_impl(ident: &Ident, class: &Class) -> Result<TokenStream> {
+    let class_name = &class.class_name;
+    let prop_tuples = class
+        .properties
+        .iter()
+        .map(|(name, prop)| prop.as_prop_tuple(name, &quote! { Self }));
+    let modifier = match &class.modifier {
+        Some(modifier) => {
+            let modifier = Ident::new(modifier, Span::call_site());
+            quote! { Some(#modifier) }
+        }
+        None => quote! { None },
+    };
+    let extends = match &class.parent {
+        Some(parent) => {
+            let expr: Expr = syn::parse_str(parent)
+                .map_err(|_| anyhow!("Invalid expression given for `{}` parent", class_name))?;
+            quote! { Some(|| #expr) }
+        }
+        None => quote! { None },
+    };
+    let implements = class
+        .interfaces
+        .iter()
+        .map(|interface| {
+            let expr: Expr = syn::parse_str(interface).map_err(|_| {
+                anyhow!(
+                    "Invalid expression given for `{}` interface: `{}`",
+                    class_name,
+                    interface
+                )
+            })?;
+            Ok(quote! { || #expr })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let flags = match &class.flags {
+        Some(flags) => {
+            let expr: Expr =
+                syn::parse_str(&format!("::ext_php_rs::flags::ClassFlags::{flags}"))
+                    .map_err(|_| anyhow!("Invalid expression given for `{}` flags", class_name))?;
+            quote! { #expr }
+        }
+        None => quote! { ::ext_php_rs::flags::ClassFlags::empty() },
+    };
+    let describe = class.describe();
 
     Ok(quote! {
-        #input
+        impl ::ext_php_rs::class::RegisteredClass for #ident {
+            const CLASS_NAME: &'static str = #class_name;
+            const BUILDER_MODIFIER: ::std::option::Option<
+                fn(::ext_php_rs::builders::ClassBuilder) -> ::ext_php_rs::error::Result<::ext_php_rs::builders::ClassBuilder>
+            > = #modifier;
+            const EXTENDS: ::std::option::Option<
+                fn() -> &'static ::ext_php_rs::zend::ClassEntry
+            > = #extends;
+            const IMPLEMENTS: &'static [fn() -> &'static ::ext_php_rs::zend::ClassEntry] = &[
+                #(#implements,)*
+            ];
+            const FLAGS: ::ext_php_rs::flags::ClassFlags = #flags;
+
+            fn get_metadata() -> &'static ::ext_php_rs::class::ClassMetadata<Self> {
+                static METADATA: ::ext_php_rs::class::ClassMetadata<#ident> =
+                    ::ext_php_rs::class::ClassMetadata::new();
+                &METADATA
+            }
 
-        ::ext_php_rs::class_derives!(#ident);
+            fn get_properties<'a>() -> ::std::collections::HashMap<&'static str, ::ext_php_rs::props::Property<'a, Self>> {
+                use ::std::iter::FromIterator;
+                use ::ext_php_rs::internal::class::PhpClassImpl;
+
+                let mut properties = ::std::collections::HashMap::from_iter([
+                    #(#prop_tuples)*
+                ]);
+                properties.extend(
+                    ::ext_php_rs::internal::class::PhpClassImplCollector::<Self>::default()
+                        .get_method_props()
+                );
+                properties
+            }
+
+            fn method_builders() -> ::std::vec::Vec<(
+                ::ext_php_rs::builders::FunctionBuilder<'static>,
+                ::ext_php_rs::flags::MethodFlags,
+            )> {
+                use ::ext_php_rs::internal::class::PhpClassImpl;
+
+                ::ext_php_rs::internal::class::PhpClassImplCollector::<Self>::default().get_methods()
+            }
+
+            fn constructor() -> ::std::option::Option<::ext_php_rs::class::ConstructorMeta<Self>> {
+                use ::ext_php_rs::internal::class::PhpClassImpl;
+
+                ::ext_php_rs::internal::class::PhpClassImplCollector::<Self>::default().get_constructor()
+            }
+
+            fn constants() -> &'static [(&'static str, &'static dyn ::ext_php_rs::convert::IntoZvalDyn)] {
+                use ::ext_php_rs::internal::class::PhpClassImpl;
+
+                ::ext_php_rs::internal::class::PhpClassImplCollector::<Self>::default().get_constants()
+            }
+
+            fn deprecated_constants() -> &'static [&'static str] {
+                use ::ext_php_rs::internal::class::PhpClassImpl;
+
+                ::ext_php_rs::internal::class::PhpClassImplCollector::<Self>::default().get_deprecated_constants()
+            }
+
+            fn describe() -> ::ext_php_rs::describe::Class {
+                use ::ext_php_rs::describe::*;
+                use ::ext_php_rs::internal::class::{PhpClassImpl, PhpClassImplCollector};
+
+                #describe
+            }
+        }
     })
 }
 
@@ -155,8 +256,6 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
 pub struct Property {
     pub ty: PropertyType,
     pub docs: Vec<String>,
-    #[allow(dead_code)]
-    pub flags: Option<String>,
 }
 
 #[derive(Debug)]
@@ -205,43 +304,43 @@ impl Property {
         }
     }
 
-    pub fn field(field_name: String, docs: Vec<String>, flags: Option<String>) -> Self {
+    pub fn field(field_name: String, docs: Vec<String>) -> Self {
         Self {
             ty: PropertyType::Field { field_name },
             docs,
-            flags,
         }
     }
 
-    pub fn method(docs: Vec<String>, flags: Option<String>) -> Self {
+    pub fn method(docs: Vec<String>) -> Self {
         Self {
             ty: PropertyType::Method {
                 getter: None,
                 setter: None,
             },
             docs,
-            flags,
         }
     }
 
-    pub fn as_prop_tuple(&self, name: &str) -> TokenStream {
+    /// Returns a tuple of the property name and the property accessor, where
+    /// `self_ty` is the type of the class.
+    pub fn as_prop_tuple(&self, name: &str, self_ty: &TokenStream) -> TokenStream {
         match &self.ty {
             PropertyType::Field { field_name } => {
                 let field_name = Ident::new(field_name, Span::call_site());
                 quote! {
-                    (#name, ::ext_php_rs::props::Property::field(|obj: &mut Self| &mut obj.#field_name)),
+                    (#name, ::ext_php_rs::props::Property::field(|obj: &mut #self_ty| &mut obj.#field_name)),
                 }
             }
             PropertyType::Method { getter, setter } => {
                 let getter = if let Some(getter) = getter {
                     let ident = Ident::new(getter, Span::call_site());
-                    quote! { Some(Self::#ident) }
+                    quote! { Some(#self_ty::#ident) }
                 } else {
                     quote! { None }
                 };
                 let setter = if let Some(setter) = setter {
                     let ident = Ident::new(setter, Span::call_site());
-                    quote! { Some(Self::#ident) }
+                    quote! { Some(#self_ty::#ident) }
                 } else {
                     quote! { None }
                 };
@@ -1,53 +1,72 @@
-use crate::helpers::get_docs;
-use anyhow::{bail, Result};
-use darling::ToTokens;
-use proc_macro2::TokenStream;
+use crate::helpers::{get_docs, Deprecation};
+use anyhow::{anyhow, Result};
+use darling::FromMeta;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{Expr, ItemConst};
-
-use crate::STATE;
+use syn::{AttributeArgs, ItemConst, Path};
 
+/// A constant attached to a class.
 #[derive(Debug)]
 pub struct Constant {
     pub name: String,
     // pub visibility: Visibility,
     pub docs: Vec<String>,
-    pub value: String,
+    pub deprecated: Option<Deprecation>,
 }
 
-pub fn parser(input: ItemConst) -> Result<TokenStream> {
-    let mut state = STATE.lock();
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    namespace: Option<String>,
+    deprecated: Option<Deprecation>,
+}
 
-    if state.startup_function.is_some() {
-        bail!("Constants must be declared before you declare your startup function and module function.");
-    }
+pub fn parser(args: AttributeArgs, input: ItemConst) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
 
-    state.constants.push(Constant {
-        name: input.ident.to_string(),
-        docs: get_docs(&input.attrs),
-        value: input.expr.to_token_stream().to_string(),
+    let ItemConst { vis, ident, .. } = &input;
+    let name = ident.to_string();
+    let internal_ident = internal_ident(ident);
+    let docs = get_docs(&input.attrs);
+    let namespace = args
+        .namespace
+        .map(|namespace| quote! { .namespace(#namespace) });
+    let deprecated = args.deprecated.map(|deprecation| {
+        let message = deprecation.message();
+        quote! { .deprecated(#message) }
     });
 
     Ok(quote! {
-        #[allow(dead_code)]
         #input
+
+        /// Returns a builder for the constant, used to add it to a module.
+        #[doc(hidden)]
+        #[allow(dead_code, non_snake_case)]
+        #vis fn #internal_ident() -> ::ext_php_rs::builders::ConstantBuilder {
+            ::ext_php_rs::builders::ConstantBuilder::new(#name, #ident)
+                #namespace
+                #deprecated
+                .docs(&[#(#docs,)*])
+        }
     })
 }
 
-impl Constant {
-    pub fn val_tokens(&self) -> TokenStream {
-        let expr: Expr =
-            syn::parse_str(&self.value).expect("failed to parse previously parsed expr");
-        expr.to_token_stream()
-    }
-
-    // pub fn get_flags(&self) -> TokenStream {
-    //     let flag = match self.visibility {
-    //         Visibility::Public => quote! { Public },
-    //         Visibility::Protected => quote! { Protected },
-    //         Visibility::Private => quote! { Private },
-    //     };
-
-    //     quote! { ::ext_php_rs::flags::ConstantFlags}
-    // }
+/// Returns the identifier of the function generated for the exported constant
+/// `ident`.
+fn internal_ident(ident: &Ident) -> Ident {
+    Ident::new(&format!("_internal_php_const_{ident}"), Span::call_site())
+}
+
+/// Parses the input of the `wrap_constant!` macro, a path to an exported
+/// constant, returning the builder of the constant.
+pub fn wrap(input: Path) -> Result<TokenStream> {
+    let mut path = input;
+    let last = path
+        .segments
+        .last_mut()
+        .ok_or_else(|| anyhow!("Expected a path to a constant."))?;
+    last.ident = internal_ident(&last.ident);
+
+    Ok(quote! { #path() })
 }
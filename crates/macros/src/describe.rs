@@ -0,0 +1,198 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Type;
+
+use crate::{
+    class::{Class, Property},
+    constant::Constant,
+    function::Arg,
+    helpers::Deprecation,
+    impl_::Visibility,
+    method::{self, Method},
+};
+
+pub trait Describe {
+    fn describe(&self) -> TokenStream;
+}
+
+/// Describes an optional deprecation notice as an `abi::Option<Str>`.
+pub fn describe_deprecation(deprecated: &Option<Deprecation>) -> TokenStream {
+    match deprecated {
+        Some(deprecation) => {
+            let message = deprecation.message();
+            quote! { Some(#message.into()) }
+        }
+        None => quote! { None },
+    }
+}
+
+impl Describe for Arg {
+    fn describe(&self) -> TokenStream {
+        let Arg { name, nullable, .. } = self;
+        let ty: Type = syn::parse_str(&self.ty).expect("failed to parse previously parsed type");
+
+        let mut ty =
+            quote! { abi::Option::Some(<#ty as ::ext_php_rs::convert::FromZvalMut>::TYPE) };
+        if self.variadic {
+            ty = quote! { abi::Option::Some(::ext_php_rs::flags::DataType::Array) }
+        }
+        let default = if let Some(default) = &self.default {
+            quote! { Some(#default.into()) }
+        } else {
+            quote! { None }
+        };
+
+        quote! {
+            Parameter {
+                name: #name.into(),
+                ty: #ty,
+                nullable: #nullable,
+                default: abi::Option::#default,
+            }
+        }
+    }
+}
+
+/// Describes the class. The methods, constants and method properties of the
+/// class are declared in its `#[php_impl]` block, and are therefore retrieved
+/// through the `PhpClassImpl` trait.
+impl Describe for Class {
+    fn describe(&self) -> TokenStream {
+        let name = &self.class_name;
+        let extends = if let Some(parent) = &self.parent {
+            quote! { Some(#parent.into()) }
+        } else {
+            quote! { None }
+        };
+        let interfaces = self.interfaces.iter().map(|iface| quote! { #iface.into() });
+        let properties = self.properties.iter().map(|d| d.describe());
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+        let deprecated = describe_deprecation(&self.deprecated);
+
+        quote! {
+            let mut properties: ::std::vec::Vec<Property> = vec![#(#properties,)*];
+            properties.extend(PhpClassImplCollector::<Self>::default().describe_method_props());
+
+            Class {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                extends: abi::Option::#extends,
+                implements: vec![#(#interfaces,)*].into(),
+                properties: properties.into(),
+                methods: PhpClassImplCollector::<Self>::default().describe_methods().into(),
+                constants: PhpClassImplCollector::<Self>::default().describe_constants().into(),
+                deprecated: abi::Option::#deprecated,
+            }
+        }
+    }
+}
+
+impl Describe for (&String, &Property) {
+    fn describe(&self) -> TokenStream {
+        let name = self.0;
+        let docs = self.1.docs.iter().map(|doc| {
+            quote! {
+                #doc.into()
+            }
+        });
+
+        // TODO(david): store metadata for ty, vis, static, null, default
+        quote! {
+            Property {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                ty: abi::Option::None,
+                vis: Visibility::Public,
+                static_: false,
+                nullable: false,
+                default: abi::Option::None,
+            }
+        }
+    }
+}
+
+impl Describe for Method {
+    fn describe(&self) -> TokenStream {
+        let Method { name, _static, .. } = &self;
+        let ty = if self.name == "__construct" {
+            quote! { MethodType::Constructor }
+        } else if self._static {
+            quote! { MethodType::Static }
+        } else {
+            quote! { MethodType::Member }
+        };
+        let parameters = self.args.iter().filter_map(|arg| {
+            if let method::Arg::Typed(arg) = &arg {
+                Some(arg.describe())
+            } else {
+                None
+            }
+        });
+        let ret = if let Some((ty, null)) = &self.output {
+            let ty: Type = syn::parse_str(ty).expect("failed to parse previously parsed type");
+            quote! {
+                Some(Retval {
+                    ty: <#ty as ::ext_php_rs::convert::IntoZval>::TYPE,
+                    nullable: #null,
+                })
+            }
+        } else {
+            quote! { None }
+        };
+        let vis = self.visibility.describe();
+        let docs = self.docs.iter().map(|doc| {
+            quote! {
+                #doc.into()
+            }
+        });
+        let deprecated = describe_deprecation(&self.deprecated);
+
+        quote! {
+            Method {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                ty: #ty,
+                params: vec![#(#parameters,)*].into(),
+                retval: abi::Option::#ret,
+                _static: #_static,
+                visibility: #vis,
+                deprecated: abi::Option::#deprecated,
+            }
+        }
+    }
+}
+
+impl Describe for Visibility {
+    fn describe(&self) -> TokenStream {
+        match self {
+            Visibility::Public => quote! { Visibility::Public },
+            Visibility::Protected => quote! { Visibility::Protected },
+            Visibility::Private => quote! { Visibility::Private },
+        }
+    }
+}
+
+impl Describe for Constant {
+    fn describe(&self) -> TokenStream {
+        let name = &self.name;
+        let deprecated = describe_deprecation(&self.deprecated);
+        let docs = self.docs.iter().map(|doc| {
+            quote! {
+                #doc.into()
+            }
+        });
+
+        quote! {
+            Constant {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                value: abi::Option::None,
+                deprecated: abi::Option::#deprecated,
+            }
+        }
+    }
+}
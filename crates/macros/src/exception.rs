@@ -0,0 +1,222 @@
+use crate::helpers::namespaced_name;
+use anyhow::{anyhow, bail, Result};
+use darling::{ast, util::Flag, FromDeriveInput, FromField, FromVariant};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Attribute, DeriveInput, Expr, Index, Member, Type};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(php), supports(enum_any))]
+struct ExceptionArgs {
+    ident: Ident,
+    data: ast::Data<ExceptionVariant, ()>,
+    /// Name of the base exception class, defaults to the name of the enum.
+    name: Option<String>,
+    /// Namespace the exception classes are placed in.
+    namespace: Option<String>,
+    /// Parent of the base exception class, defaults to `Exception`.
+    extends: Option<String>,
+}
+
+#[derive(Debug, FromVariant)]
+#[darling(attributes(php), forward_attrs(doc))]
+struct ExceptionVariant {
+    ident: Ident,
+    fields: ast::Fields<ExceptionField>,
+    attrs: Vec<Attribute>,
+    /// Name of the exception class, defaults to the name of the variant
+    /// suffixed with `Exception`.
+    name: Option<String>,
+    /// Exception code of the variant.
+    code: Option<i32>,
+    /// Existing exception class to throw instead of generating a subclass.
+    ce: Option<String>,
+}
+
+#[derive(Debug, FromField)]
+#[darling(attributes(php), forward_attrs(doc))]
+struct ExceptionField {
+    ident: Option<Ident>,
+    ty: Type,
+    attrs: Vec<Attribute>,
+    /// Whether the field is left out of the exception properties.
+    skip: Flag,
+    /// Whether the field is the underlying error, thrown as the previous
+    /// exception.
+    source: Flag,
+}
+
+pub fn parser(input: DeriveInput) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        bail!("`#[derive(PhpException)]` cannot be used on generic types.");
+    }
+
+    let args = ExceptionArgs::from_derive_input(&input)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+    let variants = match &args.data {
+        ast::Data::Enum(variants) => variants,
+        ast::Data::Struct(_) => bail!("`#[derive(PhpException)]` can only be used on enums."),
+    };
+
+    let ident = &args.ident;
+    let namespaced = |name: String| namespaced_name(args.namespace.as_deref(), &name);
+    let base_ident = Ident::new(
+        &format!("_internal_php_exception_{ident}"),
+        Span::call_site(),
+    );
+    let base_name = namespaced(args.name.clone().unwrap_or_else(|| ident.to_string()));
+    let base_extends = match &args.extends {
+        Some(extends) => syn::parse_str::<Expr>(extends)
+            .map_err(|_| anyhow!("Invalid expression given for `{}` parent", base_name))?
+            .into_token_stream(),
+        None => quote! { ::ext_php_rs::zend::ce::exception() },
+    };
+
+    let mut classes = vec![];
+    let mut class_idents = vec![base_ident.clone()];
+    let mut arms = vec![];
+
+    for variant in variants.iter() {
+        let variant_ident = &variant.ident;
+        let code = variant.code.unwrap_or_default();
+        let source = source_field(ident, variant)?;
+        let (source_pat, with_source) = match &source {
+            Some(member) => (
+                quote! { #member: source, },
+                quote! {
+                    .with_previous(::ext_php_rs::exception::PhpException::from_error(&source))
+                },
+            ),
+            None => (quote! {}, quote! {}),
+        };
+
+        if let Some(ce) = &variant.ce {
+            let ce: Expr = syn::parse_str(ce).map_err(|_| {
+                anyhow!(
+                    "Invalid expression given for `{}::{}` class",
+                    ident,
+                    variant_ident
+                )
+            })?;
+            arms.push(quote! {
+                #ident::#variant_ident { #source_pat .. } => {
+                    ::ext_php_rs::exception::PhpException::new(message, #code, #ce)
+                        #with_source
+                }
+            });
+            continue;
+        }
+
+        let class_ident = Ident::new(
+            &format!("_internal_php_exception_{ident}_{variant_ident}"),
+            Span::call_site(),
+        );
+        let name = namespaced(variant.name.clone().unwrap_or_else(|| {
+            let name = variant_ident.to_string();
+            if name.ends_with("Exception") {
+                name
+            } else {
+                format!("{name}Exception")
+            }
+        }));
+        let docs = &variant.attrs;
+        let props = variant
+            .fields
+            .iter()
+            .filter(|field| !field.skip.is_present() && !field.source.is_present())
+            .map(|field| match &field.ident {
+                Some(field_ident) => Ok((field_ident, field)),
+                None => Err(syn::Error::new_spanned(
+                    &field.ty,
+                    format!(
+                        "Positional fields of `{ident}::{variant_ident}` cannot be exposed as \
+                         exception properties. Name the field, or mark it with `#[php(skip)]`."
+                    ),
+                )),
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        let prop_defs = props.iter().map(|(field_ident, field)| {
+            let ty = &field.ty;
+            let attrs = &field.attrs;
+            quote! {
+                #(#attrs)*
+                #[prop]
+                #field_ident: #ty
+            }
+        });
+        let prop_idents = props.iter().map(|(field_ident, _)| field_ident);
+        let prop_idents2 = prop_idents.clone();
+
+        classes.push(quote! {
+            #(#docs)*
+            #[::ext_php_rs::php_class(name = #name)]
+            #[extends(<#base_ident as ::ext_php_rs::class::RegisteredClass>::get_metadata().ce())]
+            #[allow(non_camel_case_types)]
+            struct #class_ident {
+                #(#prop_defs,)*
+            }
+        });
+        arms.push(quote! {
+            #ident::#variant_ident { #source_pat #(#prop_idents,)* .. } => {
+                ::ext_php_rs::exception::PhpException::from_object(
+                    message,
+                    #code,
+                    #class_ident { #(#prop_idents2,)* },
+                )
+                #with_source
+            }
+        });
+        class_idents.push(class_ident);
+    }
+
+    Ok(quote! {
+        #[::ext_php_rs::php_class(name = #base_name)]
+        #[extends(#base_extends)]
+        #[allow(non_camel_case_types, dead_code)]
+        struct #base_ident;
+
+        #(#classes)*
+
+        impl ::ext_php_rs::exception::RegisteredException for #ident {
+            fn register_classes(
+                module: ::ext_php_rs::builders::ModuleBuilder,
+            ) -> ::ext_php_rs::builders::ModuleBuilder {
+                module
+                    #(.class::<#class_idents>())*
+            }
+        }
+
+        impl ::std::convert::From<#ident> for ::ext_php_rs::exception::PhpException {
+            fn from(err: #ident) -> Self {
+                let message = ::std::string::ToString::to_string(&err);
+
+                match err {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/// Returns the member of the field of the variant marked with
+/// `#[php(source)]`, if any.
+fn source_field(ident: &Ident, variant: &ExceptionVariant) -> Result<Option<Member>> {
+    let mut sources = variant
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.source.is_present())
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        });
+    let source = sources.next();
+    if sources.next().is_some() {
+        bail!(
+            "Only one field of `{}::{}` can be marked as the source.",
+            ident,
+            variant.ident
+        );
+    }
+    Ok(source)
+}
@@ -1,23 +1,24 @@
 use std::collections::HashMap;
 
-use crate::helpers::get_docs;
-use crate::{syn_ext::DropLifetimes, STATE};
+use crate::helpers::{get_docs, Deprecation};
+use crate::syn_ext::DropLifetimes;
 use anyhow::{anyhow, bail, Result};
 use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 use syn::{
-    punctuated::Punctuated, AttributeArgs, FnArg, GenericArgument, ItemFn, Lit, PathArguments,
-    ReturnType, Signature, Token, Type, TypePath,
+    punctuated::Punctuated, AttributeArgs, FnArg, GenericArgument, ItemFn, Lit, Path,
+    PathArguments, ReturnType, Signature, Token, Type, TypePath,
 };
 
 #[derive(Default, Debug, FromMeta)]
 #[darling(default)]
 pub struct AttrArgs {
     optional: Option<String>,
-    ignore_module: bool,
     defaults: HashMap<String, Lit>,
     name: Option<String>,
+    namespace: Option<String>,
+    deprecated: Option<Deprecation>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,20 +34,23 @@ pub struct Arg {
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
+    /// Namespace the function is registered in. An empty namespace refers to
+    /// the global namespace.
+    pub namespace: Option<String>,
     pub docs: Vec<String>,
-    pub ident: String,
     pub args: Vec<Arg>,
     pub optional: Option<String>,
     pub output: Option<(String, bool)>,
+    pub deprecated: Option<Deprecation>,
 }
 
-pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Function)> {
+pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<TokenStream> {
     let attr_args = match AttrArgs::from_list(&args) {
         Ok(args) => args,
         Err(e) => bail!("Unable to parse attribute arguments: {:?}", e),
     };
 
-    let ItemFn { sig, .. } = &input;
+    let ItemFn { vis, sig, .. } = &input;
     let Signature {
         ident,
         output,
@@ -54,59 +58,93 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
         ..
     } = &sig;
 
-    let internal_ident = Ident::new(&format!("_internal_php_{ident}"), Span::call_site());
+    let internal_ident = internal_ident(ident);
     let args = build_args(inputs, &attr_args.defaults)?;
     let optional = find_optional_parameter(args.iter(), attr_args.optional);
-    let arg_definitions = build_arg_definitions(&args);
+    let fast = is_fast_parsable(args.iter());
+    let arg_definitions = if fast {
+        vec![]
+    } else {
+        build_arg_definitions(&args)
+    };
     let arg_parser = build_arg_parser(
         args.iter(),
         &optional,
         &quote! { return; },
         ParserType::Function,
     )?;
-    let arg_accessors = build_arg_accessors(&args);
+    let arg_accessors = build_arg_accessors(&args, fast);
+    let deprecation_notice = attr_args
+        .deprecated
+        .as_ref()
+        .map(|deprecation| deprecation.handler_notice(&quote! { return; }));
 
     let return_type = get_return_type(output)?;
 
-    let func = quote! {
+    let function = Function {
+        name: attr_args.name.unwrap_or_else(|| ident.to_string()),
+        namespace: attr_args.namespace,
+        docs: get_docs(&input.attrs),
+        args,
+        optional,
+        output: return_type,
+        deprecated: attr_args.deprecated,
+    };
+    let builder = function.get_builder();
+
+    Ok(quote! {
         #input
 
-        ::ext_php_rs::zend_fastcall! {
-            #[doc(hidden)]
-            pub extern fn #internal_ident(ex: &mut ::ext_php_rs::zend::ExecuteData, retval: &mut ::ext_php_rs::types::Zval) {
-                use ::ext_php_rs::convert::IntoZval;
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #vis struct #internal_ident;
+
+        impl #internal_ident {
+            ::ext_php_rs::zend_fastcall! {
+                #[doc(hidden)]
+                pub extern fn handler(ex: &mut ::ext_php_rs::zend::ExecuteData, retval: &mut ::ext_php_rs::types::Zval) {
+                    use ::ext_php_rs::convert::IntoZval;
 
-                #(#arg_definitions)*
-                #arg_parser
+                    ::ext_php_rs::internal::run_handler(|| {
+                        #deprecation_notice
+                        #(#arg_definitions)*
+                        #arg_parser
 
-                let result = #ident(#(#arg_accessors, )*);
+                        let result = #ident(#(#arg_accessors, )*);
 
-                if let Err(e) = result.set_zval(retval, false) {
-                    let e: ::ext_php_rs::exception::PhpException = e.into();
-                    e.throw().expect("Failed to throw exception");
+                        if let Err(e) = result.set_zval(retval, false) {
+                            let e: ::ext_php_rs::exception::PhpException = e.into();
+                            let _ = e.throw();
+                        }
+                    });
                 }
             }
-        }
-    };
-
-    let mut state = STATE.lock();
-
-    if state.built_module && !attr_args.ignore_module {
-        bail!("The `#[php_module]` macro must be called last to ensure functions are registered. To ignore this error, pass the `ignore_module` option into this attribute invocation: `#[php_function(ignore_module)]`");
-    }
 
-    let function = Function {
-        name: attr_args.name.unwrap_or_else(|| ident.to_string()),
-        docs: get_docs(&input.attrs),
-        ident: internal_ident.to_string(),
-        args,
-        optional,
-        output: return_type,
-    };
+            /// Returns a builder for the function, used to add it to a module.
+            pub fn function_builder() -> ::ext_php_rs::builders::FunctionBuilder<'static> {
+                #builder
+            }
+        }
+    })
+}
 
-    state.functions.push(function.clone());
+/// Returns the identifier of the type generated for the exported function
+/// `ident`.
+fn internal_ident(ident: &Ident) -> Ident {
+    Ident::new(&format!("_internal_php_{ident}"), Span::call_site())
+}
 
-    Ok((func, function))
+/// Parses the input of the `wrap_function!` macro, a path to an exported
+/// function, returning the builder of the function.
+pub fn wrap(input: Path) -> Result<TokenStream> {
+    let mut path = input;
+    let last = path
+        .segments
+        .last_mut()
+        .ok_or_else(|| anyhow!("Expected a path to a function."))?;
+    last.ident = internal_ident(&last.ident);
+
+    Ok(quote! { #path::function_builder() })
 }
 
 fn build_args(
@@ -164,6 +202,13 @@ pub fn find_optional_parameter<'a>(
     optional
 }
 
+/// Returns whether the arguments can be read in place from the call frame with
+/// a `FastArgParser`, rather than being collected by an `ArgParser`. Only
+/// variadic functions need the latter.
+pub fn is_fast_parsable<'a>(mut args: impl Iterator<Item = &'a Arg>) -> bool {
+    !args.any(|arg| arg.variadic)
+}
+
 pub enum ParserType {
     Function,
     Method,
@@ -175,10 +220,76 @@ pub fn build_arg_parser<'a>(
     optional: &Option<String>,
     ret: &TokenStream,
     ty: ParserType,
+) -> Result<TokenStream> {
+    let args = args.collect::<Vec<_>>();
+
+    if is_fast_parsable(args.iter().copied()) {
+        build_fast_arg_parser(&args, optional, ret, ty)
+    } else {
+        build_slow_arg_parser(&args, optional, ret, ty)
+    }
+}
+
+fn build_fast_arg_parser(
+    args: &[&Arg],
+    optional: &Option<String>,
+    ret: &TokenStream,
+    ty: ParserType,
+) -> Result<TokenStream> {
+    let max_num_args = args.len();
+    let min_num_args = optional
+        .as_ref()
+        .and_then(|opt| args.iter().position(|arg| arg.name == *opt))
+        .unwrap_or(max_num_args);
+
+    if let Some(arg) = args[min_num_args..]
+        .iter()
+        .find(|arg| !arg.nullable && arg.default.is_none())
+    {
+        bail!(
+            "Parameter `{}` must be a variant of `Option` or have a default value as it is optional.",
+            arg.name
+        )
+    }
+
+    let (parser, this) = match ty {
+        ParserType::Function | ParserType::StaticMethod => (
+            quote! {
+                #[allow(unused_mut)]
+                let mut parser = ex.fast_parser();
+            },
+            None,
+        ),
+        ParserType::Method => (
+            quote! {
+                #[allow(unused_mut)]
+                let (mut parser, this) = ex.fast_parser_method::<Self>();
+            },
+            Some(this_check()),
+        ),
+    };
+
+    Ok(quote! {
+        #parser
+
+        if parser.check_count(#min_num_args, #max_num_args).is_err() {
+            #ret
+        }
+
+        #this
+    })
+}
+
+fn build_slow_arg_parser(
+    args: &[&Arg],
+    optional: &Option<String>,
+    ret: &TokenStream,
+    ty: ParserType,
 ) -> Result<TokenStream> {
     let mut rest_optional = false;
 
     let args = args
+        .iter()
         .map(|arg| {
             let name = arg.get_name_ident();
             let prelude = optional.as_ref().and_then(|opt| if *opt == arg.name {
@@ -207,17 +318,7 @@ pub fn build_arg_parser<'a>(
         }
         ParserType::Method => (
             quote! { let (parser, this) = ex.parser_method::<Self>(); },
-            Some(quote! {
-                let this = match this {
-                    Some(this) => this,
-                    None => {
-                        ::ext_php_rs::exception::PhpException::default("Failed to retrieve reference to `$this`".into())
-                            .throw()
-                            .unwrap();
-                        return;
-                    },
-                };
-            }),
+            Some(this_check()),
         ),
     };
 
@@ -235,9 +336,24 @@ pub fn build_arg_parser<'a>(
     })
 }
 
-fn build_arg_accessors(args: &[Arg]) -> Vec<TokenStream> {
+/// Returns a [`TokenStream`] which ensures `this` was retrieved from the
+/// execution data.
+fn this_check() -> TokenStream {
+    quote! {
+        let this = match this {
+            Some(this) => this,
+            None => {
+                let _ = ::ext_php_rs::exception::PhpException::default("Failed to retrieve reference to `$this`".into())
+                    .throw();
+                return;
+            },
+        };
+    }
+}
+
+fn build_arg_accessors(args: &[Arg], fast: bool) -> Vec<TokenStream> {
     args.iter()
-        .map(|arg| arg.get_accessor(&quote! { return; }))
+        .map(|arg| arg.get_accessor(&quote! { return; }, fast))
         .collect()
 }
 
@@ -360,6 +476,20 @@ impl Arg {
                     is_variadic,
                 ))
             }
+            // `()` is treated the same as no type.
+            Type::Tuple(tuple) if tuple.elems.is_empty() => None,
+            Type::Tuple(_) | Type::Array(_) => {
+                let mut ty = ty.clone();
+                ty.drop_lifetimes();
+                Some(Arg::new(
+                    name,
+                    ty.to_token_stream().to_string(),
+                    false,
+                    default,
+                    false,
+                    false,
+                ))
+            }
             _ => None,
         }
     }
@@ -378,35 +508,47 @@ impl Arg {
     }
 
     /// Returns a [`TokenStream`] containing the line required to retrieve the
-    /// value from the argument.
-    pub fn get_accessor(&self, ret: &TokenStream) -> TokenStream {
-        let name = &self.name;
+    /// value from the argument. When `fast` is set, the value is read from the
+    /// `FastArgParser` rather than from the argument definition.
+    ///
+    /// Values which cannot be converted throw a `TypeError`, after which `ret`
+    /// is executed.
+    pub fn get_accessor(&self, ret: &TokenStream, fast: bool) -> TokenStream {
         let name_ident = self.get_name_ident();
+        let nullable = self.nullable;
+        let val = if fast {
+            quote! { parser.parse_next(#nullable) }
+        } else {
+            quote! { #name_ident.parse_val() }
+        };
 
         if let Some(default) = self.default.as_ref() {
             // `bool`s are not literals - need to use Ident.
-            let val = syn::parse_str::<Literal>(default)
+            let default = syn::parse_str::<Literal>(default)
                 .map(|lit| lit.to_token_stream())
                 .or_else(|_| Ident::from_string(default).map(|ident| ident.to_token_stream()))
                 .unwrap_or(quote! { Default::default() });
 
-            quote! { #name_ident.val().unwrap_or(#val.into()) }
+            quote! {
+                match #val {
+                    Ok(val) => val.unwrap_or(#default.into()),
+                    Err(_) => { #ret }
+                }
+            }
         } else if self.nullable {
-            quote! { #name_ident.val() }
+            quote! {
+                match #val {
+                    Ok(val) => val,
+                    Err(_) => { #ret }
+                }
+            }
         } else if self.variadic {
             quote! { &#name_ident.variadic_vals() }
         } else {
             quote! {
-                match #name_ident.val() {
-                    Some(val) => val,
-                    None => {
-                        ::ext_php_rs::exception::PhpException::default(
-                            concat!("Invalid value given for argument `", #name, "`.").into()
-                        )
-                        .throw()
-                        .expect(concat!("Failed to throw exception: Invalid value given for argument `", #name, "`."));
-                        #ret
-                    }
+                match #val {
+                    Ok(Some(val)) => val,
+                    _ => { #ret }
                 }
             }
         }
@@ -437,14 +579,9 @@ impl Arg {
 }
 
 impl Function {
-    #[inline]
-    pub fn get_name_ident(&self) -> Ident {
-        Ident::new(&self.ident, Span::call_site())
-    }
-
     pub fn get_builder(&self) -> TokenStream {
         let name = &self.name;
-        let name_ident = self.get_name_ident();
+        let docs = &self.docs;
         let args = self
             .args
             .iter()
@@ -468,12 +605,22 @@ impl Function {
                 .returns(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE, false, #nullable)
             }
         });
+        let namespace = self
+            .namespace
+            .as_ref()
+            .map(|namespace| quote! { .namespace(#namespace) });
+        let deprecated = self.deprecated.as_ref().map(|deprecation| {
+            let message = deprecation.message();
+            quote! { .deprecated(#message) }
+        });
 
         quote! {
-            ::ext_php_rs::builders::FunctionBuilder::new(#name, #name_ident)
+            ::ext_php_rs::builders::FunctionBuilder::new(#name, Self::handler)
+                #namespace
                 #(#args)*
                 #output
-                .build()
+                #deprecated
+                .docs(&[#(#docs,)*])
         }
     }
 }
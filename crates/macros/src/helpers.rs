@@ -1,5 +1,8 @@
 use crate::class::{parse_attribute, ParsedAttribute};
-use syn::Attribute;
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Lit, Meta, NestedMeta};
 
 /// Takes a list of attributes and returns a list of doc comments retrieved from
 /// the attributes.
@@ -14,3 +17,74 @@ pub fn get_docs(attrs: &[Attribute]) -> Vec<String> {
 
     docs
 }
+
+/// Joins a name with the namespace it is registered in, following
+/// `ext_php_rs::builders::namespaced_name`. Used for class names, which are
+/// constants and so cannot be namespaced when the extension starts up.
+pub fn namespaced_name(namespace: Option<&str>, name: &str) -> String {
+    match namespace.map(|ns| ns.trim_matches('\\')) {
+        Some(ns) if !ns.is_empty() => format!("{ns}\\{name}"),
+        _ => name.to_string(),
+    }
+}
+
+/// Deprecation notice attached to an exported item, with an optional message
+/// explaining what to use instead.
+///
+/// Parsed from either `deprecated` or `deprecated = "message"` when used as an
+/// attribute option.
+#[derive(Debug, Clone, Default)]
+pub struct Deprecation(pub Option<String>);
+
+impl Deprecation {
+    /// Returns the deprecation message, or an empty string if no message was
+    /// given.
+    pub fn message(&self) -> &str {
+        self.0.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the code emitting the deprecation notice at the start of a
+    /// handler on versions of PHP which cannot emit it themselves, running
+    /// `ret` if the notice was turned into an exception.
+    pub fn handler_notice(&self, ret: &TokenStream) -> TokenStream {
+        let message = self.message();
+        quote! {
+            if ::ext_php_rs::internal::deprecated(ex, #message) {
+                #ret
+            }
+        }
+    }
+
+    /// Parses a standalone `#[deprecated]`, `#[deprecated = "message"]` or
+    /// `#[deprecated(note = "message")]` attribute.
+    pub fn from_attribute_meta(meta: &Meta) -> Option<Self> {
+        match meta {
+            Meta::Path(_) => Some(Self(None)),
+            Meta::NameValue(nv) => match &nv.lit {
+                Lit::Str(s) => Some(Self(Some(s.value()))),
+                _ => None,
+            },
+            Meta::List(list) => match list.nested.first() {
+                None => Some(Self(None)),
+                Some(NestedMeta::Lit(Lit::Str(s))) => Some(Self(Some(s.value()))),
+                Some(NestedMeta::Meta(Meta::NameValue(nv))) if nv.path.is_ident("note") => {
+                    match &nv.lit {
+                        Lit::Str(s) => Some(Self(Some(s.value()))),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+impl FromMeta for Deprecation {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self(None))
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self(Some(value.to_string())))
+    }
+}
@@ -1,15 +1,16 @@
 use anyhow::{anyhow, bail, Result};
 use darling::{FromMeta, ToTokens};
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::collections::HashMap;
-use syn::{Attribute, AttributeArgs, ItemImpl, Lit, Meta, NestedMeta};
+use syn::{Attribute, AttributeArgs, ItemImpl, Lit, Meta, NestedMeta, Type};
 
-use crate::helpers::get_docs;
+use crate::describe::Describe;
+use crate::helpers::{get_docs, Deprecation};
 use crate::{
     class::{Property, PropertyAttr},
     constant::Constant,
-    method,
+    method::{self, Method},
 };
 
 #[derive(Debug, Clone)]
@@ -80,6 +81,7 @@ pub enum ParsedAttribute {
     Constructor,
     This,
     Abstract,
+    Deprecated(Deprecation),
 }
 
 #[derive(Default, Debug, FromMeta)]
@@ -98,37 +100,37 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
     let args = AttrArgs::from_list(&args)
         .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
 
-    let ItemImpl { self_ty, items, .. } = input;
-    let class_name = self_ty.to_token_stream().to_string();
-
     if input.trait_.is_some() {
         bail!("This macro cannot be used on trait implementations.");
     }
 
-    let mut state = crate::STATE.lock();
-
-    if state.startup_function.is_some() {
-        bail!(
-            "Impls must be declared before you declare your startup function and module function."
-        );
-    }
-
-    let class = state.classes.get_mut(&class_name).ok_or_else(|| {
-        anyhow!(
-            "You must use `#[php_class]` on the struct before using this attribute on the impl."
-        )
-    })?;
+    let ItemImpl { self_ty, items, .. } = input;
+    let mut class = ClassImpl::default();
 
     let tokens = items
         .into_iter()
         .map(|item| {
             Ok(match item {
-                syn::ImplItem::Const(constant) => {
+                syn::ImplItem::Const(mut constant) => {
+                    let mut deprecated = None;
+                    for attr in constant.attrs.iter() {
+                        if let Some(ParsedAttribute::Deprecated(deprecation)) =
+                            parse_attribute(attr)?
+                        {
+                            deprecated = Some(deprecation);
+                        }
+                    }
+                    // The constant is deprecated in PHP, not in Rust where it is
+                    // still referenced when registering the class.
+                    constant
+                        .attrs
+                        .retain(|attr| !attr.path.is_ident("deprecated"));
+
                     class.constants.push(Constant {
                         name: constant.ident.to_string(),
                         // visibility: Visibility::Public,
                         docs: get_docs(&constant.attrs),
-                        value: constant.expr.to_token_stream().to_string(),
+                        deprecated,
                     });
 
                     quote! {
@@ -146,7 +148,7 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                         let prop = class
                             .properties
                             .entry(prop)
-                            .or_insert_with(|| Property::method(vec![], None));
+                            .or_insert_with(|| Property::method(vec![]));
                         let ident = parsed_method.method.orig_ident.clone();
 
                         match ty {
@@ -169,15 +171,135 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
         })
         .collect::<Result<Vec<_>>>()?;
 
+    let collector_impl = class.generate_collector_impl(&self_ty);
     let output = quote! {
         impl #self_ty {
             #(#tokens)*
         }
+
+        #collector_impl
     };
 
     Ok(output)
 }
 
+/// The contents of a `#[php_impl]` block which are exported to PHP.
+#[derive(Debug, Default)]
+struct ClassImpl {
+    methods: Vec<Method>,
+    constructor: Option<Method>,
+    constants: Vec<Constant>,
+    properties: HashMap<String, Property>,
+}
+
+impl ClassImpl {
+    /// Generates the implementation of `PhpClassImpl`, through which the class
+    /// retrieves its methods, constants and properties when it is registered.
+    fn generate_collector_impl(&self, self_ty: &Type) -> TokenStream {
+        let methods = self.methods.iter().map(|method| {
+            let builder = method.get_builder(self_ty);
+            let flags = method.get_flags();
+            quote! { (#builder, #flags) }
+        });
+        let prop_tuples = self
+            .properties
+            .iter()
+            .map(|(name, prop)| prop.as_prop_tuple(name, &quote! { #self_ty }));
+        let constructor = if let Some(constructor) = &self.constructor {
+            let func = Ident::new(&constructor.ident, Span::call_site());
+            let args = constructor.get_arg_definitions();
+            let deprecated = constructor.deprecated.as_ref().map(|deprecation| {
+                let message = deprecation.message();
+                quote! { .deprecated(#message) }
+            });
+            quote! {
+                Some(::ext_php_rs::class::ConstructorMeta {
+                    constructor: <#self_ty>::#func,
+                    build_fn: {
+                        use ::ext_php_rs::builders::FunctionBuilder;
+                        fn build_fn(func: FunctionBuilder) -> FunctionBuilder {
+                            func
+                            #(#args)*
+                            #deprecated
+                        }
+                        build_fn
+                    }
+                })
+            }
+        } else {
+            quote! { None }
+        };
+        let constants = self.constants.iter().map(|constant| {
+            let name = &constant.name;
+            let ident = Ident::new(name, Span::call_site());
+            quote! { (#name, &<#self_ty>::#ident) }
+        });
+        let deprecated_constants = self
+            .constants
+            .iter()
+            .filter(|constant| constant.deprecated.is_some())
+            .map(|constant| &constant.name);
+        let describe_methods = self
+            .constructor
+            .iter()
+            .chain(self.methods.iter())
+            .map(Describe::describe);
+        let describe_props = self.properties.iter().map(|prop| prop.describe());
+        let describe_constants = self.constants.iter().map(Describe::describe);
+
+        quote! {
+            impl ::ext_php_rs::internal::class::PhpClassImpl<#self_ty>
+                for ::ext_php_rs::internal::class::PhpClassImplCollector<#self_ty>
+            {
+                fn get_methods(self) -> ::std::vec::Vec<(
+                    ::ext_php_rs::builders::FunctionBuilder<'static>,
+                    ::ext_php_rs::flags::MethodFlags,
+                )> {
+                    vec![#(#methods,)*]
+                }
+
+                fn get_method_props<'a>(self) -> ::std::collections::HashMap<&'static str, ::ext_php_rs::props::Property<'a, #self_ty>> {
+                    use ::std::iter::FromIterator;
+
+                    ::std::collections::HashMap::from_iter([
+                        #(#prop_tuples)*
+                    ])
+                }
+
+                fn get_constructor(self) -> ::std::option::Option<::ext_php_rs::class::ConstructorMeta<#self_ty>> {
+                    #constructor
+                }
+
+                fn get_constants(self) -> &'static [(&'static str, &'static dyn ::ext_php_rs::convert::IntoZvalDyn)] {
+                    &[#(#constants,)*]
+                }
+
+                fn get_deprecated_constants(self) -> &'static [&'static str] {
+                    &[#(#deprecated_constants,)*]
+                }
+
+                fn describe_methods(self) -> ::std::vec::Vec<::ext_php_rs::describe::Method> {
+                    use ::ext_php_rs::describe::*;
+
+                    vec![#(#describe_methods,)*]
+                }
+
+                fn describe_method_props(self) -> ::std::vec::Vec<::ext_php_rs::describe::Property> {
+                    use ::ext_php_rs::describe::*;
+
+                    vec![#(#describe_props,)*]
+                }
+
+                fn describe_constants(self) -> ::std::vec::Vec<::ext_php_rs::describe::Constant> {
+                    use ::ext_php_rs::describe::*;
+
+                    vec![#(#describe_constants,)*]
+                }
+            }
+        }
+    }
+}
+
 pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
     let name = attr.path.to_token_stream().to_string();
     let meta = attr
@@ -252,6 +374,10 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
         }
         "constructor" => ParsedAttribute::Constructor,
         "this" => ParsedAttribute::This,
+        "deprecated" => ParsedAttribute::Deprecated(
+            Deprecation::from_attribute_meta(&meta)
+                .ok_or_else(|| anyhow!("Invalid argument given for `#[deprecated]` macro."))?,
+        ),
         _ => return Ok(None),
     }))
 }
@@ -1,5 +1,7 @@
 mod class;
 mod constant;
+mod describe;
+mod exception;
 mod extern_;
 mod fastcall;
 mod function;
@@ -11,46 +13,15 @@ mod startup_function;
 mod syn_ext;
 mod zval;
 
-use std::{
-    collections::HashMap,
-    sync::{Mutex, MutexGuard},
-};
-
-use constant::Constant;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use syn::{
     parse_macro_input, AttributeArgs, DeriveInput, ItemConst, ItemFn, ItemForeignMod, ItemImpl,
-    ItemStruct,
+    ItemStruct, Path,
 };
 
 extern crate proc_macro;
 
-#[derive(Default, Debug)]
-struct State {
-    functions: Vec<function::Function>,
-    classes: HashMap<String, class::Class>,
-    constants: Vec<Constant>,
-    startup_function: Option<String>,
-    built_module: bool,
-}
-
-lazy_static::lazy_static! {
-    pub(crate) static ref STATE: StateMutex = StateMutex::new();
-}
-
-struct StateMutex(Mutex<State>);
-
-impl StateMutex {
-    pub fn new() -> Self {
-        Self(Mutex::new(Default::default()))
-    }
-
-    pub fn lock(&self) -> MutexGuard<State> {
-        self.0.lock().unwrap_or_else(|e| e.into_inner())
-    }
-}
-
 #[proc_macro_attribute]
 pub fn php_class(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
@@ -69,17 +40,18 @@ pub fn php_function(args: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemFn);
 
     match function::parser(args, input) {
-        Ok((parsed, _)) => parsed,
+        Ok(parsed) => parsed,
         Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
     }
     .into()
 }
 
 #[proc_macro_attribute]
-pub fn php_module(_: TokenStream, input: TokenStream) -> TokenStream {
+pub fn php_module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
     let input = parse_macro_input!(input as ItemFn);
 
-    match module::parser(input) {
+    match module::parser(args, input) {
         Ok(parsed) => parsed,
         Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
     }
@@ -91,7 +63,7 @@ pub fn php_startup(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
     let input = parse_macro_input!(input as ItemFn);
 
-    match startup_function::parser(Some(args), input) {
+    match startup_function::parser(args, input) {
         Ok(parsed) => parsed,
         Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
     }
@@ -111,10 +83,11 @@ pub fn php_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn php_const(_: TokenStream, input: TokenStream) -> TokenStream {
+pub fn php_const(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
     let input = parse_macro_input!(input as ItemConst);
 
-    match constant::parser(input) {
+    match constant::parser(args, input) {
         Ok(parsed) => parsed,
         Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
     }
@@ -143,6 +116,20 @@ pub fn zval_convert_derive(input: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_derive(PhpException, attributes(php))]
+pub fn php_exception_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match exception::parser(input) {
+        Ok(parsed) => parsed,
+        Err(e) => match e.downcast::<syn::Error>() {
+            Ok(e) => e.to_compile_error(),
+            Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+        },
+    }
+    .into()
+}
+
 #[proc_macro]
 pub fn zend_fastcall(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemFn);
@@ -153,3 +140,25 @@ pub fn zend_fastcall(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+#[proc_macro]
+pub fn wrap_function(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Path);
+
+    match function::wrap(input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
+#[proc_macro]
+pub fn wrap_constant(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Path);
+
+    match constant::wrap(input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
@@ -3,7 +3,7 @@ use quote::ToTokens;
 use std::collections::HashMap;
 use syn::ReturnType;
 
-use crate::helpers::get_docs;
+use crate::helpers::{get_docs, Deprecation};
 use crate::{
     function::{self, ParserType},
     impl_::{parse_attribute, ParsedAttribute, PropAttrTy, RenameRule, Visibility},
@@ -33,6 +33,7 @@ pub struct Method {
     pub _static: bool,
     pub _abstract: bool,
     pub visibility: Visibility,
+    pub deprecated: Option<Deprecation>,
 }
 
 pub struct ParsedMethod {
@@ -77,6 +78,7 @@ pub fn parser(
     let mut identifier = None;
     let mut is_abstract = false;
     let mut is_constructor = false;
+    let mut deprecated = None;
     let docs = get_docs(&input.attrs);
 
     for attr in input.attrs.iter() {
@@ -107,6 +109,7 @@ pub fn parser(
                     as_prop = Some((prop_name, ty))
                 }
                 ParsedAttribute::Constructor => is_constructor = true,
+                ParsedAttribute::Deprecated(deprecation) => deprecated = Some(deprecation),
                 _ => bail!("Invalid attribute for method."),
             }
         }
@@ -129,6 +132,9 @@ pub fn parser(
     } else {
         quote! { return; }
     };
+    let deprecation_notice = deprecated
+        .as_ref()
+        .map(|deprecation| deprecation.handler_notice(&bail));
     let internal_ident = Ident::new(&format!("_internal_php_{ident}"), Span::call_site());
     let args = build_args(struct_ty, &mut input.sig.inputs, &defaults)?;
     let optional = function::find_optional_parameter(
@@ -138,7 +144,14 @@ pub fn parser(
         }),
         optional,
     );
-    let (arg_definitions, method_type) = build_arg_definitions(&args);
+    let fast = function::is_fast_parsable(args.iter().filter_map(|arg| match arg {
+        Arg::Typed(arg) => Some(arg),
+        _ => None,
+    }));
+    let (mut arg_definitions, method_type) = build_arg_definitions(&args);
+    if fast {
+        arg_definitions.clear();
+    }
     let arg_parser = build_arg_parser(
         args.iter(),
         &optional,
@@ -148,7 +161,7 @@ pub fn parser(
             _ => ParserType::Method,
         },
     )?;
-    let arg_accessors = build_arg_accessors(&args, &bail);
+    let arg_accessors = build_arg_accessors(&args, &bail, fast);
 
     let func = if is_constructor {
         quote! {
@@ -161,6 +174,7 @@ pub fn parser(
                 use ::ext_php_rs::convert::IntoZval;
                 use ::ext_php_rs::class::ConstructorResult;
 
+                #deprecation_notice
                 #(#arg_definitions)*
                 #arg_parser
 
@@ -184,15 +198,18 @@ pub fn parser(
                 ) {
                     use ::ext_php_rs::convert::IntoZval;
 
-                    #(#arg_definitions)*
-                    #arg_parser
+                    ::ext_php_rs::internal::run_handler(|| {
+                        #deprecation_notice
+                        #(#arg_definitions)*
+                        #arg_parser
 
-                    let result = #this #ident(#(#arg_accessors,)*);
+                        let result = #this #ident(#(#arg_accessors,)*);
 
-                    if let Err(e) = result.set_zval(retval, false) {
-                        let e: ::ext_php_rs::exception::PhpException = e.into();
-                        e.throw().expect("Failed to throw exception");
-                    }
+                        if let Err(e) = result.set_zval(retval, false) {
+                            let e: ::ext_php_rs::exception::PhpException = e.into();
+                            let _ = e.throw();
+                        }
+                    });
                 }
             }
         }
@@ -209,6 +226,7 @@ pub fn parser(
         _static: matches!(method_type, MethodType::Static),
         _abstract: is_abstract,
         visibility,
+        deprecated,
     };
 
     Ok(ParsedMethod::new(func, method, as_prop, is_constructor))
@@ -377,10 +395,10 @@ fn build_arg_parser<'a>(
     )
 }
 
-fn build_arg_accessors(args: &[Arg], ret: &TokenStream) -> Vec<TokenStream> {
+fn build_arg_accessors(args: &[Arg], ret: &TokenStream, fast: bool) -> Vec<TokenStream> {
     args.iter()
         .filter_map(|arg| match arg {
-            Arg::Typed(arg) => Some(arg.get_accessor(ret)),
+            Arg::Typed(arg) => Some(arg.get_accessor(ret, fast)),
             Arg::Receiver(MethodType::ReceiverClassObject) => Some(quote! { this }),
             _ => None,
         })
@@ -410,7 +428,7 @@ impl Method {
         })
     }
 
-    pub fn get_builder(&self, class_path: &Ident) -> TokenStream {
+    pub fn get_builder(&self, self_ty: &Type) -> TokenStream {
         let name = &self.name;
         let name_ident = self.get_name_ident();
         let args = self.get_arg_definitions();
@@ -422,12 +440,16 @@ impl Method {
                 .returns(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE, false, #nullable)
             }
         });
+        let deprecated = self.deprecated.as_ref().map(|deprecation| {
+            let message = deprecation.message();
+            quote! { .deprecated(#message) }
+        });
 
         quote! {
-            ::ext_php_rs::builders::FunctionBuilder::new(#name, #class_path :: #name_ident)
+            ::ext_php_rs::builders::FunctionBuilder::new(#name, <#self_ty>::#name_ident)
                 #(#args)*
                 #output
-                .build()
+                #deprecated
         }
     }
 
@@ -114,6 +114,7 @@ pub const IS_OBJECT_EX: u32 = 776;
 pub const IS_RESOURCE_EX: u32 = 265;
 pub const IS_REFERENCE_EX: u32 = 266;
 pub const IS_CONSTANT_AST_EX: u32 = 267;
+pub const HASH_FLAG_PACKED: u32 = 4;
 pub const E_ERROR: u32 = 1;
 pub const E_WARNING: u32 = 2;
 pub const E_PARSE: u32 = 4;
@@ -157,7 +158,9 @@ pub const ZEND_ACC_RESOLVED_INTERFACES: u32 = 262144;
 pub const ZEND_ACC_UNRESOLVED_VARIANCE: u32 = 524288;
 pub const ZEND_ACC_NEARLY_LINKED: u32 = 1048576;
 pub const ZEND_ACC_NOT_SERIALIZABLE: u32 = 536870912;
+pub const ZEND_ACC_ENUM: u32 = 268435456;
 pub const ZEND_ACC_DEPRECATED: u32 = 2048;
+pub const ZEND_CLASS_CONST_IS_CASE: u32 = 64;
 pub const ZEND_ACC_RETURN_REFERENCE: u32 = 4096;
 pub const ZEND_ACC_HAS_RETURN_TYPE: u32 = 8192;
 pub const ZEND_ACC_VARIADIC: u32 = 16384;
@@ -394,6 +397,27 @@ pub struct _Bucket {
     pub key: *mut zend_string,
 }
 pub type Bucket = _Bucket;
+pub type compare_func_t = ::std::option::Option<
+    unsafe extern "C" fn(
+        arg1: *const ::std::os::raw::c_void,
+        arg2: *const ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type swap_func_t = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut ::std::os::raw::c_void),
+>;
+pub type sort_func_t = ::std::option::Option<
+    unsafe extern "C" fn(
+        arg1: *mut ::std::os::raw::c_void,
+        arg2: usize,
+        arg3: usize,
+        arg4: compare_func_t,
+        arg5: swap_func_t,
+    ),
+>;
+pub type bucket_compare_func_t = ::std::option::Option<
+    unsafe extern "C" fn(a: *mut Bucket, b: *mut Bucket) -> ::std::os::raw::c_int,
+>;
 pub type HashTable = _zend_array;
 #[repr(C)]
 pub struct _zend_array {
@@ -626,6 +650,59 @@ extern "C" {
         pData: *mut zval,
     ) -> *mut zval;
 }
+extern "C" {
+    pub fn zend_hash_real_init_packed(ht: *mut HashTable);
+}
+extern "C" {
+    pub fn zend_hash_packed_to_hash(ht: *mut HashTable);
+}
+extern "C" {
+    pub fn zend_hash_rehash(ht: *mut HashTable);
+}
+extern "C" {
+    pub fn zend_hash_del_bucket(ht: *mut HashTable, p: *mut Bucket);
+}
+extern "C" {
+    pub fn zend_hash_sort_ex(
+        ht: *mut HashTable,
+        sort_func: sort_func_t,
+        compare_func: bucket_compare_func_t,
+        renumber: bool,
+    );
+}
+extern "C" {
+    pub fn zend_sort(
+        base: *mut ::std::os::raw::c_void,
+        nmemb: usize,
+        siz: usize,
+        cmp: compare_func_t,
+        swp: swap_func_t,
+    );
+}
+extern "C" {
+    pub fn zend_compare(op1: *mut zval, op2: *mut zval) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn add_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
+extern "C" {
+    pub fn sub_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
+extern "C" {
+    pub fn mul_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
+extern "C" {
+    pub fn div_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
+extern "C" {
+    pub fn mod_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
+extern "C" {
+    pub fn pow_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
+extern "C" {
+    pub fn concat_function(result: *mut zval, op1: *mut zval, op2: *mut zval) -> zend_result;
+}
 extern "C" {
     pub fn zend_hash_index_update(ht: *mut HashTable, h: zend_ulong, pData: *mut zval)
         -> *mut zval;
@@ -915,6 +992,15 @@ pub struct _zend_inheritance_cache_entry {
     pub traits_and_interfaces: [*mut zend_class_entry; 1usize],
 }
 #[repr(C)]
+pub struct _zend_class_constant {
+    pub value: zval,
+    pub doc_comment: *mut zend_string,
+    pub attributes: *mut HashTable,
+    pub ce: *mut zend_class_entry,
+    pub type_: zend_type,
+}
+pub type zend_class_constant = _zend_class_constant;
+#[repr(C)]
 pub struct _zend_class_entry {
     pub type_: ::std::os::raw::c_char,
     pub name: *mut zend_string,
@@ -1420,6 +1506,9 @@ pub struct _zend_execute_data {
     pub run_time_cache: *mut *mut ::std::os::raw::c_void,
     pub extra_named_params: *mut zend_array,
 }
+extern "C" {
+    pub fn zend_set_function_arg_flags(func: *mut zend_function);
+}
 pub type __jmp_buf = [::std::os::raw::c_long; 8usize];
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -1698,6 +1787,16 @@ extern "C" {
         parent_ce: *mut zend_class_entry,
     ) -> *mut zend_class_entry;
 }
+extern "C" {
+    pub fn zend_is_callable_ex(
+        callable: *mut zval,
+        object: *mut zend_object,
+        check_flags: u32,
+        callable_name: *mut *mut zend_string,
+        fcc: *mut zend_fcall_info_cache,
+        error: *mut *mut ::std::os::raw::c_char,
+    ) -> bool;
+}
 extern "C" {
     pub fn zend_is_callable(
         callable: *mut zval,
@@ -1705,6 +1804,9 @@ extern "C" {
         callable_name: *mut *mut zend_string,
     ) -> bool;
 }
+extern "C" {
+    pub fn zend_release_fcall_info_cache(fcc: *mut zend_fcall_info_cache);
+}
 extern "C" {
     pub fn zend_declare_property(
         ce: *mut zend_class_entry,
@@ -1788,9 +1890,85 @@ pub type _zend_expected_type = ::std::os::raw::c_uint;
 extern "C" {
     pub fn zend_wrong_parameters_count_error(min_num_args: u32, max_num_args: u32);
 }
+extern "C" {
+    pub fn zend_argument_type_error(arg_num: u32, format: *const ::std::os::raw::c_char, ...);
+}
+extern "C" {
+    pub fn zend_argument_value_error(arg_num: u32, format: *const ::std::os::raw::c_char, ...);
+}
+extern "C" {
+    pub fn zend_parse_arg_bool_slow(arg: *const zval, dest: *mut bool, arg_num: u32) -> bool;
+}
+extern "C" {
+    pub fn zend_parse_arg_long_slow(arg: *const zval, dest: *mut zend_long, arg_num: u32) -> bool;
+}
+extern "C" {
+    pub fn zend_parse_arg_double_slow(arg: *const zval, dest: *mut f64, arg_num: u32) -> bool;
+}
+extern "C" {
+    pub fn zend_parse_arg_str_slow(
+        arg: *mut zval,
+        dest: *mut *mut zend_string,
+        arg_num: u32,
+    ) -> bool;
+}
+extern "C" {
+    pub fn zend_zval_type_name(arg: *const zval) -> *const ::std::os::raw::c_char;
+}
+pub type rsrc_dtor_func_t = ::std::option::Option<unsafe extern "C" fn(res: *mut zend_resource)>;
+extern "C" {
+    pub fn zend_list_delete(res: *mut zend_resource);
+}
+extern "C" {
+    pub fn zend_list_close(res: *mut zend_resource);
+}
+extern "C" {
+    pub fn zend_register_resource(
+        rsrc_pointer: *mut ::std::os::raw::c_void,
+        rsrc_type: ::std::os::raw::c_int,
+    ) -> *mut zend_resource;
+}
+extern "C" {
+    pub fn zend_register_list_destructors_ex(
+        ld: rsrc_dtor_func_t,
+        pld: rsrc_dtor_func_t,
+        type_name: *const ::std::os::raw::c_char,
+        module_number: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
 extern "C" {
     pub fn php_printf(format: *const ::std::os::raw::c_char, ...) -> usize;
 }
+extern "C" {
+    pub fn php_output_start_default() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn php_output_get_contents(p: *mut zval) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn php_output_discard() -> ::std::os::raw::c_int;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct smart_str {
+    pub s: *mut zend_string,
+    pub a: usize,
+}
+extern "C" {
+    pub fn smart_str_erealloc(str_: *mut smart_str, len: usize);
+}
+extern "C" {
+    pub fn smart_str_realloc(str_: *mut smart_str, len: usize);
+}
+extern "C" {
+    pub fn php_var_export_ex(struc: *mut zval, level: ::std::os::raw::c_int, buf: *mut smart_str);
+}
+extern "C" {
+    pub fn php_var_dump(struc: *mut zval, level: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn zend_print_zval_r_to_str(expr: *mut zval) -> *mut zend_string;
+}
 extern "C" {
     pub fn php_error_docref(
         docref: *const ::std::os::raw::c_char,
@@ -1799,6 +1977,9 @@ extern "C" {
         ...
     );
 }
+extern "C" {
+    pub fn zend_error(type_: ::std::os::raw::c_int, format: *const ::std::os::raw::c_char, ...);
+}
 pub type php_stream = _php_stream;
 pub type php_stream_wrapper = _php_stream_wrapper;
 pub type php_stream_context = _php_stream_context;
@@ -2476,6 +2657,9 @@ extern "C" {
 extern "C" {
     pub static mut zend_ce_exception: *mut zend_class_entry;
 }
+extern "C" {
+    pub static mut zend_ce_error: *mut zend_class_entry;
+}
 extern "C" {
     pub static mut zend_ce_error_exception: *mut zend_class_entry;
 }
@@ -2514,12 +2698,74 @@ extern "C" {
 extern "C" {
     pub fn zend_throw_exception_object(exception: *mut zval);
 }
+extern "C" {
+    pub fn zend_update_property_ex(
+        scope: *mut zend_class_entry,
+        object: *mut zend_object,
+        name: *mut zend_string,
+        value: *mut zval,
+    );
+}
 extern "C" {
     pub fn zend_do_implement_interface(ce: *mut zend_class_entry, iface: *mut zend_class_entry);
 }
 extern "C" {
     pub static mut zend_ce_traversable: *mut zend_class_entry;
 }
+extern "C" {
+    pub static mut zend_ce_generator: *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_iterator_dtor(iter: *mut zend_object_iterator);
+}
+extern "C" {
+    pub fn object_init_ex(arg: *mut zval, ce: *mut zend_class_entry) -> zend_result;
+}
+extern "C" {
+    pub fn zend_std_get_static_method(
+        ce: *mut zend_class_entry,
+        function_name_strval: *mut zend_string,
+        key: *const zval,
+    ) -> *mut zend_function;
+}
+extern "C" {
+    pub fn zend_std_get_static_property(
+        ce: *mut zend_class_entry,
+        property_name: *mut zend_string,
+        type_: ::std::os::raw::c_int,
+    ) -> *mut zval;
+}
+extern "C" {
+    pub fn zend_read_property(
+        scope: *mut zend_class_entry,
+        object: *mut zend_object,
+        name: *const ::std::os::raw::c_char,
+        name_length: usize,
+        silent: bool,
+        rv: *mut zval,
+    ) -> *mut zval;
+}
+extern "C" {
+    pub fn zend_enum_get_case(ce: *mut zend_class_entry, name: *mut zend_string) -> *mut zend_object;
+}
+extern "C" {
+    pub fn zend_update_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        value: *mut zval,
+    ) -> zend_result;
+}
+pub const BP_VAR_R: u32 = 0;
+pub const BP_VAR_W: u32 = 1;
+extern "C" {
+    pub fn zend_create_fake_closure(
+        res: *mut zval,
+        op_array: *mut zend_function,
+        scope: *mut zend_class_entry,
+        called_scope: *mut zend_class_entry,
+        this_ptr: *mut zval,
+    );
+}
 extern "C" {
     pub static mut zend_ce_aggregate: *mut zend_class_entry;
 }
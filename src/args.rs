@@ -1,6 +1,10 @@
 //! Builder and objects relating to function and method arguments.
 
-use std::{ffi::CString, ptr};
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+    slice::IterMut,
+};
 
 use crate::{
     convert::{FromZvalMut, IntoZvalDyn},
@@ -10,24 +14,27 @@ use crate::{
         _zend_expected_type_Z_EXPECTED_BOOL, _zend_expected_type_Z_EXPECTED_DOUBLE,
         _zend_expected_type_Z_EXPECTED_LONG, _zend_expected_type_Z_EXPECTED_OBJECT,
         _zend_expected_type_Z_EXPECTED_RESOURCE, _zend_expected_type_Z_EXPECTED_STRING,
-        zend_internal_arg_info, zend_wrong_parameters_count_error,
+        zend_argument_type_error, zend_argument_value_error, zend_internal_arg_info,
+        zend_parse_arg_bool_slow, zend_parse_arg_double_slow, zend_parse_arg_long_slow,
+        zend_parse_arg_str_slow, zend_wrong_parameters_count_error,
     },
     flags::DataType,
     types::Zval,
-    zend::ZendType,
+    zend::{ExecutorGlobals, ZendType, FORMAT_STR},
 };
 
 /// Represents an argument to a function.
 #[derive(Debug)]
 pub struct Arg<'a> {
-    name: String,
-    _type: DataType,
+    pub(crate) name: String,
+    pub(crate) _type: DataType,
     as_ref: bool,
-    allow_null: bool,
-    variadic: bool,
-    default_value: Option<String>,
+    pub(crate) allow_null: bool,
+    pub(crate) variadic: bool,
+    pub(crate) default_value: Option<String>,
     zval: Option<&'a mut Zval>,
     variadic_zvals: Vec<Option<&'a mut Zval>>,
+    arg_num: usize,
 }
 
 impl<'a> Arg<'a> {
@@ -47,6 +54,7 @@ impl<'a> Arg<'a> {
             default_value: None,
             zval: None,
             variadic_zvals: vec![],
+            arg_num: 0,
         }
     }
 
@@ -105,6 +113,28 @@ impl<'a> Arg<'a> {
             .and_then(|zv| T::from_zval_mut(zv.dereference_mut()))
     }
 
+    /// Attempts to retrieve the value of the argument, throwing the
+    /// `TypeError` PHP throws for internal functions if the argument was given
+    /// but could not be converted into `T`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(T))` - The converted value.
+    /// * `Ok(None)` - The argument was not given, or was `null` and the
+    ///   argument is nullable.
+    /// * `Err(Error)` - The argument could not be converted. A `TypeError` has
+    ///   been thrown, so you should break execution after seeing an error.
+    pub fn parse_val<T>(&'a mut self) -> Result<Option<T>>
+    where
+        T: FromZvalMut<'a>,
+    {
+        let nullable = self.allow_null;
+        match self.zval.as_mut() {
+            Some(zv) => parse_arg(self.arg_num, zv.dereference_mut(), nullable),
+            None => Ok(None),
+        }
+    }
+
     /// Retrice all the variadic values for this Rust argument.
     pub fn variadic_vals<T>(&'a mut self) -> Vec<T>
     where
@@ -117,6 +147,30 @@ impl<'a> Arg<'a> {
             .collect()
     }
 
+    /// Consumes a variadic argument, returning the values passed to it.
+    pub(crate) fn into_variadic_zvals(self) -> Vec<&'a mut Zval> {
+        self.variadic_zvals
+            .into_iter()
+            .flatten()
+            .map(|zv| zv.dereference_mut())
+            .collect()
+    }
+
+    /// Returns a copy of the argument as declared, without any value.
+    pub(crate) fn declaration<'b>(&self) -> Arg<'b> {
+        Arg {
+            name: self.name.clone(),
+            _type: self._type,
+            as_ref: self.as_ref,
+            allow_null: self.allow_null,
+            variadic: self.variadic,
+            default_value: self.default_value.clone(),
+            zval: None,
+            variadic_zvals: vec![],
+            arg_num: 0,
+        }
+    }
+
     /// Attempts to return a reference to the arguments internal Zval.
     ///
     /// # Returns
@@ -256,9 +310,15 @@ impl<'a, 'b> ArgParser<'a, 'b> {
                 None => self.args.last_mut().filter(|arg| arg.variadic),
             };
             if let Some(arg) = arg {
+                let mut arg_zval = arg_zval;
+                if let Some(zv) = arg_zval.as_deref_mut() {
+                    coerce_arg(zv, arg._type, arg.allow_null, i + 1);
+                }
+
                 if arg.variadic {
                     arg.variadic_zvals.push(arg_zval);
                 } else {
+                    arg.arg_num = i + 1;
                     arg.zval = arg_zval;
                 }
             }
@@ -267,3 +327,323 @@ impl<'a, 'b> ArgParser<'a, 'b> {
         Ok(())
     }
 }
+
+/// Parses the arguments of a function in place, reading them directly from the
+/// call frame without any heap allocation. This is the equivalent of the fast
+/// parameter parsing API of the engine (`ZEND_PARSE_PARAMETERS_START`).
+///
+/// Used by the functions generated by the [`php_function`] and [`php_impl`]
+/// macros, unless the function takes variadic arguments, in which case the
+/// [`ArgParser`] is used.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{types::Zval, zend::ExecuteData};
+///
+/// #[no_mangle]
+/// pub extern "C" fn example_fn(ex: &mut ExecuteData, retval: &mut Zval) {
+///     let mut parser = ex.fast_parser();
+///
+///     // Takes one required and one optional argument.
+///     if parser.check_count(1, 2).is_err() {
+///         return;
+///     }
+///
+///     let a: Option<i64> = parser.next_arg();
+///     let b: Option<&str> = parser.next_arg();
+///
+///     dbg!(a, b);
+/// }
+/// ```
+///
+/// [`php_function`]: crate::php_function
+/// [`php_impl`]: crate::php_impl
+pub struct FastArgParser<'a> {
+    args: IterMut<'a, Zval>,
+    num_args: usize,
+}
+
+impl<'a> FastArgParser<'a> {
+    /// Creates a parser over the arguments passed to a function.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments passed to the function.
+    pub fn new(args: &'a mut [Zval]) -> Self {
+        Self {
+            num_args: args.len(),
+            args: args.iter_mut(),
+        }
+    }
+
+    /// Returns the number of arguments passed to the function.
+    pub fn num_args(&self) -> usize {
+        self.num_args
+    }
+
+    /// Checks that the number of arguments passed to the function is within
+    /// the given bounds.
+    ///
+    /// # Parameters
+    ///
+    /// * `min_num_args` - The number of required arguments.
+    /// * `max_num_args` - The total number of arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] type if there were too many or too little arguments
+    /// passed to the function. The user has already been notified so you
+    /// should break execution after seeing an error type.
+    pub fn check_count(&self, min_num_args: usize, max_num_args: usize) -> Result<()> {
+        if self.num_args < min_num_args || self.num_args > max_num_args {
+            // SAFETY: Exported C function is safe, return value is unused and parameters
+            // are copied.
+            unsafe { zend_wrong_parameters_count_error(min_num_args as _, max_num_args as _) };
+            return Err(Error::IncorrectArguments(self.num_args, min_num_args));
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves the next argument, converting it into `T`.
+    ///
+    /// Returns [`None`] if the argument was not passed to the function or could
+    /// not be converted into `T`. In both cases the parser moves on to the
+    /// next argument.
+    pub fn next_arg<T>(&mut self) -> Option<T>
+    where
+        T: FromZvalMut<'a>,
+    {
+        self.next_zval(T::TYPE, true)
+            .and_then(|(_, zv)| T::from_zval_mut(zv))
+    }
+
+    /// Retrieves the next argument, converting it into `T`, and throws the
+    /// `TypeError` PHP throws for internal functions if the argument was given
+    /// but could not be converted.
+    ///
+    /// # Parameters
+    ///
+    /// * `nullable` - Whether the argument accepts `null`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(T))` - The converted value.
+    /// * `Ok(None)` - The argument was not passed, or was `null` and
+    ///   `nullable` is set.
+    /// * `Err(Error)` - The argument could not be converted. A `TypeError` has
+    ///   been thrown, so you should break execution after seeing an error.
+    pub fn parse_next<T>(&mut self, nullable: bool) -> Result<Option<T>>
+    where
+        T: FromZvalMut<'a>,
+    {
+        match self.next_zval(T::TYPE, nullable) {
+            Some((arg_num, zv)) => parse_arg(arg_num, zv, nullable),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieves the next argument along with its position, coerced into `ty`.
+    fn next_zval(&mut self, ty: DataType, nullable: bool) -> Option<(usize, &'a mut Zval)> {
+        let arg_num = self.num_args - self.args.len() + 1;
+        let zv = self.args.next()?;
+        coerce_arg(zv, ty, nullable, arg_num);
+        Some((arg_num, zv.dereference_mut()))
+    }
+}
+
+/// Converts an argument into `T`, throwing a `TypeError` on failure.
+fn parse_arg<'a, T>(arg_num: usize, zv: &'a mut Zval, nullable: bool) -> Result<Option<T>>
+where
+    T: FromZvalMut<'a>,
+{
+    if nullable && zv.is_null() {
+        return Ok(None);
+    }
+
+    let ptr: *mut Zval = zv;
+    // SAFETY: The argument is only read again if the conversion failed, in which
+    // case nothing borrows it.
+    match T::from_zval_mut(unsafe { &mut *ptr }) {
+        Some(val) => Ok(Some(val)),
+        None => {
+            let zv = unsafe { &*ptr };
+            match value_error_message::<T>(zv) {
+                Some(message) => throw_value_error(arg_num, &message),
+                None => throw_type_error(arg_num, T::TYPE, nullable, zv),
+            }
+            Err(Error::ZvalConversion(zv.get_type()))
+        }
+    }
+}
+
+/// Converts a scalar argument in place into the type `ty` following PHP's
+/// weak typing rules, like the engine does for the arguments of internal
+/// functions. Numeric strings, integers and floats, booleans and objects
+/// implementing `__toString()` are converted.
+///
+/// `null` given for an argument which is not nullable is converted too, which
+/// PHP deprecated in 8.1.
+///
+/// Nothing is converted if the caller declared `strict_types=1`, except for
+/// integers given for a float, which PHP allows in strict mode too. References
+/// are never converted.
+fn coerce_arg(zv: &mut Zval, ty: DataType, nullable: bool, arg_num: usize) {
+    if (nullable && zv.is_null()) || zv.is_reference() {
+        return;
+    }
+
+    let arg_num = arg_num as u32;
+    let ptr: *mut Zval = zv;
+
+    // The position of the argument is only used for deprecation notices, which
+    // were added in PHP 8.1.
+    macro_rules! parse_slow {
+        ($func: ident, $dest: expr) => {{
+            #[cfg(php81)]
+            let res = $func(ptr, $dest, arg_num);
+            #[cfg(not(php81))]
+            let res = $func(ptr, $dest);
+            res
+        }};
+    }
+
+    // SAFETY: The `_slow` functions check the `strict_types` flag of the caller
+    // themselves, and only write to `dest`, except for strings which are
+    // converted in place.
+    unsafe {
+        match ty {
+            DataType::Long if !zv.is_long() => {
+                let mut dest = 0;
+                if parse_slow!(zend_parse_arg_long_slow, &mut dest) {
+                    zv.set_long(dest);
+                }
+            }
+            DataType::Double if !zv.is_double() => {
+                let mut dest = 0.0;
+                if parse_slow!(zend_parse_arg_double_slow, &mut dest) {
+                    zv.set_double(dest);
+                }
+            }
+            DataType::Bool | DataType::True | DataType::False if !zv.is_bool() => {
+                let mut dest = false;
+                if parse_slow!(zend_parse_arg_bool_slow, &mut dest) {
+                    zv.set_bool(dest);
+                }
+            }
+            DataType::String if !zv.is_string() => {
+                let mut dest = ptr::null_mut();
+                parse_slow!(zend_parse_arg_str_slow, &mut dest);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Throws the `TypeError` PHP throws when an argument given to an internal
+/// function has the wrong type, for example `foo(): Argument #1 ($bar) must
+/// be of type int, string given`.
+///
+/// Nothing is thrown if an exception is already pending, for example one
+/// thrown by `__toString()` while converting the argument.
+///
+/// # Parameters
+///
+/// * `arg_num` - The position of the argument, starting from 1.
+/// * `expected` - The type of the argument.
+/// * `nullable` - Whether the argument accepts `null`.
+/// * `given` - The value given for the argument.
+pub fn throw_type_error(arg_num: usize, expected: DataType, nullable: bool, given: &Zval) {
+    if ExecutorGlobals::has_exception() {
+        return;
+    }
+
+    let message = format!(
+        "must be of type {}{}, {} given",
+        if nullable { "?" } else { "" },
+        type_name(expected),
+        given_name(given).to_string_lossy()
+    );
+    let message = CString::new(message).unwrap_or_default();
+    // SAFETY: The format string consumes exactly one string argument.
+    unsafe {
+        zend_argument_type_error(arg_num as u32, FORMAT_STR.as_ptr().cast(), message.as_ptr())
+    };
+}
+
+/// Throws the `ValueError` PHP throws when an argument given to an internal
+/// function has the right type but an invalid value, for example `foo():
+/// Argument #1 ($bar) must be between 0 and 255`.
+fn throw_value_error(arg_num: usize, message: &str) {
+    if ExecutorGlobals::has_exception() {
+        return;
+    }
+
+    let message = CString::new(message).unwrap_or_default();
+    // SAFETY: The format string consumes exactly one string argument.
+    unsafe {
+        zend_argument_value_error(arg_num as u32, FORMAT_STR.as_ptr().cast(), message.as_ptr())
+    };
+}
+
+/// Returns the reason a value which has the type of `T` could not be converted
+/// into `T`, or [`None`] if the value has another type. Types which describe
+/// the reason themselves through [`FromZvalMut::value_error`] may reject values
+/// of any type.
+fn value_error_message<'a, T: FromZvalMut<'a>>(zv: &Zval) -> Option<String> {
+    if let Some(message) = T::value_error(zv) {
+        return Some(message);
+    }
+
+    let same_type = match T::TYPE {
+        DataType::Long => zv.is_long(),
+        DataType::Double => zv.is_double(),
+        DataType::String => zv.is_string(),
+        DataType::Array => zv.is_array(),
+        _ => false,
+    };
+    if !same_type {
+        return None;
+    }
+
+    Some(T::value_constraint().unwrap_or_else(|| match T::TYPE {
+        DataType::String => "must be a valid UTF-8 string".into(),
+        DataType::Array => "must only contain keys and values of the expected types".into(),
+        ty => format!("must be a valid {}", type_name(ty)),
+    }))
+}
+
+/// Returns the name PHP uses for a type in error messages.
+pub(crate) fn type_name(ty: DataType) -> &'static str {
+    match ty {
+        DataType::Null => "null",
+        DataType::False => "false",
+        DataType::True => "true",
+        DataType::Bool => "bool",
+        DataType::Long => "int",
+        DataType::Double => "float",
+        DataType::String => "string",
+        DataType::Array => "array",
+        DataType::Object(Some(class)) => class,
+        DataType::Object(None) => "object",
+        DataType::Resource => "resource",
+        DataType::Callable => "callable",
+        DataType::Iterable => "iterable",
+        DataType::Void => "void",
+        _ => "mixed",
+    }
+}
+
+/// Returns the name PHP uses for a given value in error messages.
+fn given_name(zv: &Zval) -> &CStr {
+    // SAFETY: Both functions return a static string or the name of the class of
+    // the value, which lives at least as long as the value.
+    unsafe {
+        #[cfg(php83)]
+        let name = crate::ffi::zend_zval_value_name(zv);
+        #[cfg(not(php83))]
+        let name = crate::ffi::zend_zval_type_name(zv);
+        CStr::from_ptr(name)
+    }
+}
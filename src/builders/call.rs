@@ -0,0 +1,271 @@
+use std::{fmt::Debug, marker::PhantomData, mem, ptr};
+
+use crate::{
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{
+        ext_php_rs_zval_make_ref, ext_php_rs_zval_unwrap_ref, zend_call_known_function,
+        zend_class_entry, zend_fcall_info_cache, zend_function, zend_object,
+        zend_release_fcall_info_cache,
+    },
+    types::{ZendHashTable, Zval},
+    zend::{call_php, ExecutorGlobals},
+};
+
+/// An argument given to a [`CallBuilder`].
+enum CallArg<'a> {
+    /// An argument passed by value.
+    Value(Zval),
+    /// An argument passed by reference, written back to once the call
+    /// returns.
+    Ref(&'a mut Zval),
+}
+
+impl<'a> CallArg<'a> {
+    /// Returns the zval to pass to PHP. Arguments passed by reference are
+    /// turned into PHP references in place, and pushed onto `refs` to be
+    /// unwrapped once the call returns.
+    fn into_zval(self, refs: &mut Vec<&'a mut Zval>) -> Zval {
+        match self {
+            Self::Value(zv) => zv,
+            Self::Ref(zv) => {
+                if zv.is_reference() {
+                    zv.shallow_clone()
+                } else {
+                    // SAFETY: The zval is a valid, owned zval.
+                    unsafe { ext_php_rs_zval_make_ref(zv) };
+                    let arg = zv.shallow_clone();
+                    refs.push(zv);
+                    arg
+                }
+            }
+        }
+    }
+}
+
+/// Builds a call to a PHP function or method, passing arguments by value, by
+/// reference or by name.
+///
+/// Created through [`Function::call`], [`ZendObject::call_method`],
+/// [`ClassEntry::call_static`] or [`Zval::call`]. Errors from converting
+/// arguments are deferred until the call is made with [`invoke`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{types::Zval, zend::Function};
+///
+/// let preg_match = Function::try_from_function("preg_match").unwrap();
+/// let mut matches = Zval::new();
+/// let result = preg_match
+///     .call()
+///     .arg("/(\\d+)/")
+///     .arg("abc 123")
+///     .by_ref(&mut matches)
+///     .named("flags", 256) // PREG_OFFSET_CAPTURE
+///     .invoke()
+///     .unwrap();
+/// assert_eq!(result.long(), Some(1));
+/// assert!(matches.is_array());
+/// ```
+///
+/// [`Function::call`]: crate::zend::Function::call
+/// [`ZendObject::call_method`]: crate::types::ZendObject::call_method
+/// [`ClassEntry::call_static`]: crate::zend::ClassEntry::call_static
+/// [`invoke`]: #method.invoke
+pub struct CallBuilder<'a> {
+    function: *mut zend_function,
+    object: *mut zend_object,
+    called_scope: *mut zend_class_entry,
+    /// The resolved trampoline (`__call` or `__callStatic`), released if the
+    /// call is never made.
+    trampoline: Option<zend_fcall_info_cache>,
+    args: Vec<CallArg<'a>>,
+    named: Vec<(String, CallArg<'a>)>,
+    error: Option<Error>,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> CallBuilder<'a> {
+    /// Creates a new call builder for a function.
+    ///
+    /// # Parameters
+    ///
+    /// * `function` - The function to call.
+    /// * `object` - The object to call the function on, or null.
+    /// * `called_scope` - The class the function is called on, or null.
+    pub(crate) fn new(
+        function: *mut zend_function,
+        object: *mut zend_object,
+        called_scope: *mut zend_class_entry,
+    ) -> Self {
+        Self {
+            function,
+            object,
+            called_scope,
+            trampoline: None,
+            args: vec![],
+            named: vec![],
+            error: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a new call builder from a resolved callable, taking ownership
+    /// of the function if it is a trampoline.
+    pub(crate) fn from_fcc(fcc: zend_fcall_info_cache, trampoline: bool) -> Self {
+        let mut builder = Self::new(fcc.function_handler, fcc.object, fcc.called_scope);
+        if trampoline {
+            builder.trampoline = Some(fcc);
+        }
+        builder
+    }
+
+    /// Converts a value into a zval, storing the first error that occurs.
+    fn convert<T: IntoZval>(&mut self, val: T) -> Zval {
+        match val.into_zval(false) {
+            Ok(zv) => zv,
+            Err(e) => {
+                self.error.get_or_insert(e);
+                Zval::new()
+            }
+        }
+    }
+
+    /// Adds an argument passed by value.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value of the argument.
+    pub fn arg<T: IntoZval>(mut self, val: T) -> Self {
+        let zv = self.convert(val);
+        self.args.push(CallArg::Value(zv));
+        self
+    }
+
+    /// Adds an argument passed by reference. Any changes made to it by the
+    /// function are visible in `val` once the call returns.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The zval to pass by reference.
+    pub fn by_ref(mut self, val: &'a mut Zval) -> Self {
+        self.args.push(CallArg::Ref(val));
+        self
+    }
+
+    /// Adds a named argument passed by value.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the parameter.
+    /// * `val` - The value of the argument.
+    pub fn named<T: IntoZval>(mut self, name: &str, val: T) -> Self {
+        let zv = self.convert(val);
+        self.named.push((name.into(), CallArg::Value(zv)));
+        self
+    }
+
+    /// Adds a named argument passed by reference. Any changes made to it by
+    /// the function are visible in `val` once the call returns.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the parameter.
+    /// * `val` - The zval to pass by reference.
+    pub fn named_by_ref(mut self, name: &str, val: &'a mut Zval) -> Self {
+        self.named.push((name.into(), CallArg::Ref(val)));
+        self
+    }
+
+    /// Calls the function with the given arguments.
+    ///
+    /// # Returns
+    ///
+    /// Returns the return value of the function upon success. If converting
+    /// an argument failed, the function bailed out or an exception was
+    /// thrown, an [`Err`] is returned.
+    pub fn invoke(mut self) -> Result<Zval> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        let mut refs = vec![];
+        let mut args = mem::take(&mut self.args)
+            .into_iter()
+            .map(|arg| arg.into_zval(&mut refs))
+            .collect::<Vec<_>>();
+        let mut named = if self.named.is_empty() {
+            None
+        } else {
+            let mut ht = ZendHashTable::with_capacity(self.named.len() as u32);
+            for (name, arg) in mem::take(&mut self.named) {
+                if let Err(e) = ht.insert(&name, arg.into_zval(&mut refs)) {
+                    self.error.get_or_insert(e);
+                }
+            }
+            Some(ht)
+        };
+
+        let result = if let Some(e) = self.error.take() {
+            Err(e)
+        } else {
+            // PHP releases the trampoline once it has been called.
+            self.trampoline = None;
+
+            let mut retval = Zval::new();
+            call_php(|| unsafe {
+                zend_call_known_function(
+                    self.function,
+                    self.object,
+                    self.called_scope,
+                    &mut retval,
+                    args.len() as _,
+                    args.as_mut_ptr(),
+                    named
+                        .as_deref_mut()
+                        .map_or(ptr::null_mut(), |ht| ht as *mut _),
+                )
+            })
+            .map(|_| retval)
+        };
+
+        // Release our side of the references before unwrapping them, so that
+        // they can be unwrapped in place.
+        drop(args);
+        drop(named);
+        for zv in refs {
+            // SAFETY: The zval was turned into a reference by us.
+            unsafe { ext_php_rs_zval_unwrap_ref(zv) };
+        }
+
+        let retval = result?;
+        ExecutorGlobals::check_exception()?;
+        Ok(retval)
+    }
+}
+
+impl Debug for CallBuilder<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallBuilder")
+            .field("function", &self.function)
+            .field("object", &self.object)
+            .field("called_scope", &self.called_scope)
+            .field("args", &self.args.len())
+            .field(
+                "named",
+                &self.named.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Drop for CallBuilder<'_> {
+    fn drop(&mut self) {
+        if let Some(mut fcc) = self.trampoline.take() {
+            // SAFETY: The trampoline was allocated by PHP when resolving the
+            // callable, and has not been called.
+            unsafe { zend_release_fcall_info_cache(&mut fcc) };
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use std::{ffi::CString, mem::MaybeUninit};
 
 use crate::{
-    builders::FunctionBuilder,
+    builders::{function::attach_deprecation, FunctionBuilder},
     class::{ConstructorMeta, ConstructorResult, RegisteredClass},
-    convert::IntoZval,
+    convert::{IntoZval, IntoZvalDyn},
     error::{Error, Result},
     exception::PhpException,
     ffi::{
@@ -11,6 +11,7 @@ use crate::{
         zend_register_internal_class_ex,
     },
     flags::{ClassFlags, MethodFlags, PropertyFlags},
+    internal::run_handler,
     types::{ZendClassObject, ZendObject, ZendStr, Zval},
     zend::{ClassEntry, ExecuteData, FunctionEntry},
     zend_fastcall,
@@ -26,6 +27,8 @@ pub struct ClassBuilder {
     object_override: Option<unsafe extern "C" fn(class_type: *mut ClassEntry) -> *mut ZendObject>,
     properties: Vec<(String, Zval, PropertyFlags)>,
     constants: Vec<(String, Zval)>,
+    deprecated_constants: Vec<String>,
+    deprecated_methods: Vec<(String, &'static str)>,
 }
 
 impl ClassBuilder {
@@ -47,6 +50,8 @@ impl ClassBuilder {
             object_override: None,
             properties: vec![],
             constants: vec![],
+            deprecated_constants: vec![],
+            deprecated_methods: vec![],
         }
     }
 
@@ -136,6 +141,40 @@ impl ClassBuilder {
         Ok(self)
     }
 
+    /// Marks a constant of the class as deprecated. On PHP 8.4 and later, PHP
+    /// emits an `E_DEPRECATED` notice whenever the constant is accessed.
+    /// Earlier versions have no notion of deprecated class constants, and the
+    /// constant is left untouched.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant to deprecate.
+    pub fn deprecate_constant<T: Into<String>>(mut self, name: T) -> Self {
+        self.deprecated_constants.push(name.into());
+        self
+    }
+
+    /// Adds a constant to the class from a dynamically typed value. The type of
+    /// the constant is defined by the type of the value.
+    ///
+    /// Returns a result containing the class builder if the constant was
+    /// successfully added.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant to add to the class.
+    /// * `value` - The value of the constant.
+    pub fn dyn_constant<T: Into<String>>(
+        mut self,
+        name: T,
+        value: &'static dyn IntoZvalDyn,
+    ) -> Result<Self> {
+        let value = value.as_zval(true)?;
+
+        self.constants.push((name.into(), value));
+        Ok(self)
+    }
+
     /// Sets the flags for the class.
     ///
     /// # Parameters
@@ -170,35 +209,34 @@ impl ClassBuilder {
 
         zend_fastcall! {
             extern fn constructor<T: RegisteredClass>(ex: &mut ExecuteData, _: &mut Zval) {
-                let ConstructorMeta { constructor, .. } = match T::CONSTRUCTOR {
-                    Some(c) => c,
-                    None => {
-                        PhpException::default("You cannot instantiate this class from PHP.".into())
-                            .throw()
-                            .expect("Failed to throw exception when constructing class");
-                        return;
-                    }
-                };
-
-                let this = match constructor(ex) {
-                    ConstructorResult::Ok(this) => this,
-                    ConstructorResult::Exception(e) => {
-                        e.throw()
-                            .expect("Failed to throw exception while constructing class");
-                        return;
-                    }
-                    ConstructorResult::ArgError => return,
-                };
-                let this_obj = match ex.get_object::<T>() {
-                    Some(obj) => obj,
-                    None => {
-                        PhpException::default("Failed to retrieve reference to `this` object.".into())
-                            .throw()
-                            .expect("Failed to throw exception while constructing class");
-                        return;
-                    }
-                };
-                this_obj.initialize(this);
+                run_handler(|| {
+                    let ConstructorMeta { constructor, .. } = match T::constructor() {
+                        Some(c) => c,
+                        None => {
+                            let _ = PhpException::default("You cannot instantiate this class from PHP.".into())
+                                .throw();
+                            return;
+                        }
+                    };
+
+                    let this = match constructor(ex) {
+                        ConstructorResult::Ok(this) => this,
+                        ConstructorResult::Exception(e) => {
+                            let _ = e.throw();
+                            return;
+                        }
+                        ConstructorResult::ArgError => return,
+                    };
+                    let this_obj = match ex.get_object::<T>() {
+                        Some(obj) => obj,
+                        None => {
+                            let _ = PhpException::default("Failed to retrieve reference to `this` object.".into())
+                                .throw();
+                            return;
+                        }
+                    };
+                    this_obj.initialize(this);
+                });
             }
         }
 
@@ -208,18 +246,59 @@ impl ClassBuilder {
             "Class name in builder does not match class name in `impl RegisteredClass`."
         );
         self.object_override = Some(create_object::<T>);
+
+        let mut func = FunctionBuilder::new("__construct", constructor::<T>);
+        if let Some(ConstructorMeta { build_fn, .. }) = T::constructor() {
+            func = build_fn(func);
+        }
+        if let Some(deprecation) = func.deprecation() {
+            self.deprecated_methods.push(deprecation);
+        }
         self.method(
-            {
-                let mut func = FunctionBuilder::new("__construct", constructor::<T>);
-                if let Some(ConstructorMeta { build_fn, .. }) = T::CONSTRUCTOR {
-                    func = build_fn(func);
-                }
-                func.build().expect("Failed to build constructor function")
-            },
+            func.build().expect("Failed to build constructor function"),
             MethodFlags::Public,
         )
     }
 
+    /// Builds and registers the class exported from the Rust type `T`, storing
+    /// the class entry in the class metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] variant if the class could not be registered.
+    pub(crate) fn register<T: RegisteredClass>() -> Result<()> {
+        let mut builder = Self::new(T::CLASS_NAME);
+
+        for (method, flags) in T::method_builders() {
+            if let Some(deprecation) = method.deprecation() {
+                builder.deprecated_methods.push(deprecation);
+            }
+            builder = builder.method(method.build()?, flags);
+        }
+        for (name, value) in T::constants() {
+            builder = builder.dyn_constant(*name, *value)?;
+        }
+        for name in T::deprecated_constants() {
+            builder = builder.deprecate_constant(*name);
+        }
+        for interface in T::IMPLEMENTS {
+            builder = builder.implements(interface());
+        }
+        if let Some(parent) = T::EXTENDS {
+            builder = builder.extends(parent());
+        }
+        builder = builder.flags(T::FLAGS);
+        if !T::FLAGS.contains(ClassFlags::Interface) {
+            builder = builder.object_override::<T>();
+        }
+        if let Some(modifier) = T::BUILDER_MODIFIER {
+            builder = modifier(builder)?;
+        }
+
+        T::get_metadata().set_ce(builder.build()?);
+        Ok(())
+    }
+
     /// Builds the class, returning a reference to the class entry.
     ///
     /// # Errors
@@ -290,6 +369,35 @@ impl ClassBuilder {
             };
         }
 
+        cfg_if::cfg_if! {
+            if #[cfg(php84)] {
+                for name in self.deprecated_constants {
+                    // SAFETY: The constants table of a class holds pointers to
+                    // its constants, whose flags are stored in their value.
+                    unsafe {
+                        let constant = crate::ffi::zend_hash_str_find(
+                            &class.constants_table,
+                            name.as_ptr().cast(),
+                            name.len(),
+                        );
+                        if let Some(constant) = constant.as_ref() {
+                            let constant =
+                                constant.value.ptr as *mut crate::ffi::zend_class_constant;
+                            (*constant).value.u2.constant_flags |=
+                                MethodFlags::Deprecated.bits();
+                        }
+                    }
+                }
+            } else {
+                // Class constants cannot be deprecated before PHP 8.4.
+                let _ = self.deprecated_constants;
+            }
+        }
+
+        for (name, message) in self.deprecated_methods {
+            attach_deprecation(Some(&*class), &name, message);
+        }
+
         if let Some(object_override) = self.object_override {
             class.__bindgen_anon_2.create_object = Some(object_override);
         }
@@ -0,0 +1,107 @@
+use crate::{
+    constant::IntoConst, describe::DocComments, error::Result, flags::GlobalConstantFlags,
+};
+
+/// Builder for registering a global constant in PHP. Constants are added to a
+/// module with [`ModuleBuilder::constant`], and registered when the extension
+/// starts up.
+///
+/// ```
+/// use ext_php_rs::builders::{ConstantBuilder, ModuleBuilder};
+///
+/// let module = ModuleBuilder::new("ext-name", "ext-version")
+///     .constant(ConstantBuilder::new("MAX_SIZE", 1024).namespace("Acme\\Util"));
+/// ```
+///
+/// [`ModuleBuilder::constant`]: crate::builders::ModuleBuilder::constant
+pub struct ConstantBuilder {
+    pub(crate) name: String,
+    pub(crate) namespace: Option<String>,
+    value: Box<dyn IntoConst + Send>,
+    pub(crate) docs: DocComments,
+    pub(crate) deprecated: Option<&'static str>,
+}
+
+impl ConstantBuilder {
+    /// Creates a new constant builder.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant.
+    /// * `value` - The value of the constant.
+    pub fn new<N: Into<String>, T: IntoConst + Send + 'static>(name: N, value: T) -> Self {
+        Self {
+            name: name.into(),
+            namespace: None,
+            value: Box::new(value),
+            docs: &[],
+            deprecated: None,
+        }
+    }
+
+    /// Sets the namespace the constant is registered in. An empty namespace
+    /// registers the constant in the global namespace.
+    ///
+    /// # Parameters
+    ///
+    /// * `namespace` - The namespace of the constant, e.g. `Acme\Util`.
+    pub fn namespace<T: Into<String>>(mut self, namespace: T) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Marks the constant as deprecated. PHP will emit an `E_DEPRECATED`
+    /// notice whenever the constant is accessed.
+    ///
+    /// # Parameters
+    ///
+    /// * `message` - The deprecation message, added to the generated stubs.
+    ///   May be empty.
+    pub fn deprecated(mut self, message: &'static str) -> Self {
+        self.deprecated = Some(message);
+        self
+    }
+
+    /// Sets the documentation comments of the constant, used when generating
+    /// stubs.
+    ///
+    /// # Parameters
+    ///
+    /// * `docs` - The lines of the documentation comment.
+    pub fn docs(mut self, docs: DocComments) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    /// Returns the name of the constant, including its namespace.
+    pub(crate) fn php_name(&self) -> String {
+        super::namespaced_name(self.namespace.as_deref(), &self.name)
+    }
+
+    /// Registers the constant with PHP. Must be called from the module
+    /// startup function.
+    ///
+    /// # Parameters
+    ///
+    /// * `module_number` - The module number the constant is registered
+    ///   under.
+    pub(crate) fn register(&self, module_number: i32) -> Result<()> {
+        let mut flags = GlobalConstantFlags::CaseSensitive | GlobalConstantFlags::Persistent;
+        if self.deprecated.is_some() {
+            flags |= GlobalConstantFlags::Deprecated;
+        }
+
+        self.value
+            .register_constant_flags(&self.php_name(), module_number, flags)
+    }
+}
+
+impl std::fmt::Debug for ConstantBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConstantBuilder")
+            .field("name", &self.name)
+            .field("namespace", &self.namespace)
+            .field("deprecated", &self.deprecated)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,9 +1,11 @@
 use crate::{
     args::{Arg, ArgInfo},
+    describe::DocComments,
     error::{Error, Result},
+    ffi::ext_php_rs_zend_function_deprecate,
     flags::{DataType, MethodFlags},
     types::Zval,
-    zend::{ExecuteData, FunctionEntry, ZendType},
+    zend::{ClassEntry, ExecuteData, FunctionEntry, ZendType},
 };
 use std::{ffi::CString, mem, ptr};
 
@@ -24,13 +26,16 @@ type FunctionPointerHandler =
 /// Builder for registering a function in PHP.
 #[derive(Debug)]
 pub struct FunctionBuilder<'a> {
-    name: String,
+    pub(crate) name: String,
+    pub(crate) namespace: Option<String>,
     function: FunctionEntry,
-    args: Vec<Arg<'a>>,
+    pub(crate) args: Vec<Arg<'a>>,
     n_req: Option<usize>,
-    retval: Option<DataType>,
+    pub(crate) retval: Option<DataType>,
     ret_as_ref: bool,
-    ret_as_null: bool,
+    pub(crate) ret_as_null: bool,
+    pub(crate) docs: DocComments,
+    pub(crate) deprecated: Option<&'static str>,
 }
 
 impl<'a> FunctionBuilder<'a> {
@@ -45,6 +50,7 @@ impl<'a> FunctionBuilder<'a> {
     pub fn new<T: Into<String>>(name: T, handler: FunctionHandler) -> Self {
         Self {
             name: name.into(),
+            namespace: None,
             function: FunctionEntry {
                 fname: ptr::null(),
                 // SAFETY: `*mut T` and `&mut T` have the same ABI as long as `*mut T` is non-null,
@@ -65,6 +71,8 @@ impl<'a> FunctionBuilder<'a> {
             retval: None,
             ret_as_ref: false,
             ret_as_null: false,
+            docs: &[],
+            deprecated: None,
         }
     }
 
@@ -77,6 +85,7 @@ impl<'a> FunctionBuilder<'a> {
     pub fn new_abstract<T: Into<String>>(name: T) -> Self {
         Self {
             name: name.into(),
+            namespace: None,
             function: FunctionEntry {
                 fname: ptr::null(),
                 handler: None,
@@ -93,6 +102,8 @@ impl<'a> FunctionBuilder<'a> {
             retval: None,
             ret_as_ref: false,
             ret_as_null: false,
+            docs: &[],
+            deprecated: None,
         }
     }
 
@@ -107,6 +118,17 @@ impl<'a> FunctionBuilder<'a> {
         Self::new("__construct", handler)
     }
 
+    /// Sets the namespace the function is registered in. An empty namespace
+    /// registers the function in the global namespace.
+    ///
+    /// # Parameters
+    ///
+    /// * `namespace` - The namespace of the function, e.g. `Acme\Util`.
+    pub fn namespace<T: Into<String>>(mut self, namespace: T) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
     /// Adds an argument to the function.
     ///
     /// # Parameters
@@ -128,6 +150,43 @@ impl<'a> FunctionBuilder<'a> {
         self
     }
 
+    /// Marks the function as deprecated, adding the message to the generated
+    /// stubs.
+    ///
+    /// On PHP 8.4 and later, the function is flagged as deprecated and the
+    /// message is attached as a `#[\Deprecated]` attribute, so PHP emits an
+    /// `E_DEPRECATED` notice including the message whenever the function is
+    /// called. Earlier versions have no way of attaching a message to the
+    /// function, so the handler must emit the notice itself with
+    /// [`ExecuteData::trigger_deprecation`]. The handlers generated by the
+    /// [`macro@php_function`] and [`macro@php_impl`] macros do so.
+    ///
+    /// # Parameters
+    ///
+    /// * `message` - The deprecation message, added to the notice and the
+    ///   generated stubs. May be empty.
+    ///
+    /// [`ExecuteData::trigger_deprecation`]: crate::zend::ExecuteData::trigger_deprecation
+    pub fn deprecated(mut self, message: &'static str) -> Self {
+        #[cfg(php84)]
+        {
+            self.function.flags |= MethodFlags::Deprecated.bits();
+        }
+        self.deprecated = Some(message);
+        self
+    }
+
+    /// Sets the documentation comments of the function, used when generating
+    /// stubs.
+    ///
+    /// # Parameters
+    ///
+    /// * `docs` - The lines of the documentation comment.
+    pub fn docs(mut self, docs: DocComments) -> Self {
+        self.docs = docs;
+        self
+    }
+
     /// Sets the return value of the function.
     ///
     /// # Parameters
@@ -169,10 +228,38 @@ impl<'a> FunctionBuilder<'a> {
                 .collect::<Result<Vec<_>>>()?,
         );
 
-        self.function.fname = CString::new(self.name)?.into_raw();
+        self.function.fname = CString::new(self.php_name())?.into_raw();
         self.function.num_args = (args.len() - 1) as u32;
         self.function.arg_info = Box::into_raw(args.into_boxed_slice()) as *const ArgInfo;
 
         Ok(self.function)
     }
+
+    /// Returns the name of the function, including its namespace.
+    pub(crate) fn php_name(&self) -> String {
+        super::namespaced_name(self.namespace.as_deref(), &self.name)
+    }
+
+    /// Returns the name and deprecation message of the function if it was
+    /// deprecated with a message, which has to be attached to the function
+    /// once it is registered.
+    pub(crate) fn deprecation(&self) -> Option<(String, &'static str)> {
+        self.deprecated
+            .filter(|message| !message.is_empty())
+            .map(|message| (self.php_name(), message))
+    }
+}
+
+/// Attaches the deprecation message to a registered function, or a method of
+/// the given class.
+pub(crate) fn attach_deprecation(ce: Option<&ClassEntry>, name: &str, message: &str) {
+    unsafe {
+        ext_php_rs_zend_function_deprecate(
+            ce.map_or(ptr::null_mut(), |ce| ce as *const _ as *mut _),
+            name.as_ptr().cast(),
+            name.len(),
+            message.as_ptr().cast(),
+            message.len(),
+        )
+    };
 }
@@ -1,14 +1,28 @@
 //! Structures that are used to construct other, more complicated types.
 //! Generally zero-cost abstractions.
 
+mod call;
 mod class;
+mod constant;
 mod function;
 mod module;
 #[cfg(feature = "embed")]
 mod sapi;
 
+pub use call::CallBuilder;
 pub use class::ClassBuilder;
+pub use constant::ConstantBuilder;
 pub use function::FunctionBuilder;
-pub use module::ModuleBuilder;
+pub use module::{ModuleBuilder, ModuleStartup, StartupFunction};
 #[cfg(feature = "embed")]
 pub use sapi::SapiBuilder;
+
+/// Joins a name with the namespace it is registered in. Leading and trailing
+/// backslashes are trimmed from the namespace, and an empty namespace refers to
+/// the global namespace.
+pub(crate) fn namespaced_name(namespace: Option<&str>, name: &str) -> String {
+    match namespace.map(|ns| ns.trim_matches('\\')) {
+        Some(ns) if !ns.is_empty() => format!("{ns}\\{name}"),
+        _ => name.to_string(),
+    }
+}
@@ -1,6 +1,15 @@
 use crate::{
+    builders::{function::attach_deprecation, ClassBuilder, ConstantBuilder, FunctionBuilder},
+    class::RegisteredClass,
+    describe::Class,
     error::Result,
-    ffi::{ext_php_rs_php_build_id, ZEND_MODULE_API_NO},
+    exception::RegisteredException,
+    ffi::{
+        ext_php_rs_php_build_id, ZEND_MODULE_API_NO, ZEND_RESULT_CODE_FAILURE,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    internal::startup_warning,
+    types::Resource,
     zend::{FunctionEntry, ModuleEntry},
     PHP_DEBUG, PHP_ZTS,
 };
@@ -11,12 +20,22 @@ use std::{ffi::CString, mem, ptr};
 /// from within an external function called `get_module`, returning a mutable
 /// pointer to a `ModuleEntry`.
 ///
+/// Functions, classes, constants and resource types are registered with the
+/// module through [`function`], [`class`], [`constant`] and [`resource`].
+/// Classes, constants and resource types are registered with PHP when the
+/// extension starts up, through the [`ModuleStartup`] returned by [`build`].
+/// The [`php_module`] macro does this for you.
+///
 /// ```
 /// use ext_php_rs::{
-///     builders::ModuleBuilder,
+///     builders::{ModuleBuilder, ModuleStartup},
+///     ffi::ZEND_RESULT_CODE_FAILURE,
 ///     zend::ModuleEntry,
 ///     info_table_start, info_table_end, info_table_row
 /// };
+/// use std::sync::Mutex;
+///
+/// static STARTUP: Mutex<Option<ModuleStartup>> = Mutex::new(None);
 ///
 /// #[no_mangle]
 /// pub extern "C" fn php_module_info(_module: *mut ModuleEntry) {
@@ -25,21 +44,42 @@ use std::{ffi::CString, mem, ptr};
 ///     info_table_end!();
 /// }
 ///
+/// extern "C" fn module_startup(ty: i32, module_number: i32) -> i32 {
+///     match STARTUP.lock().unwrap().take() {
+///         Some(startup) => startup.startup(ty, module_number),
+///         None => ZEND_RESULT_CODE_FAILURE,
+///     }
+/// }
+///
 /// #[no_mangle]
 /// pub extern "C" fn get_module() -> *mut ModuleEntry {
-///     ModuleBuilder::new("ext-name", "ext-version")
+///     let (mut module, startup) = ModuleBuilder::new("ext-name", "ext-version")
 ///         .info_function(php_module_info)
 ///         .build()
-///         .unwrap()
-///         .into_raw()
+///         .unwrap();
+///     module.module_startup_func = Some(module_startup);
+///     *STARTUP.lock().unwrap() = Some(startup);
+///     module.into_raw()
 /// }
 /// ```
-#[derive(Debug, Clone)]
+///
+/// [`function`]: ModuleBuilder::function
+/// [`class`]: ModuleBuilder::class
+/// [`constant`]: ModuleBuilder::constant
+/// [`resource`]: ModuleBuilder::resource
+/// [`build`]: ModuleBuilder::build
+/// [`php_module`]: crate::php_module
+#[derive(Debug)]
 pub struct ModuleBuilder {
-    name: String,
+    pub(crate) name: String,
     version: String,
     module: ModuleEntry,
-    functions: Vec<FunctionEntry>,
+    namespace: Option<String>,
+    pub(crate) functions: Vec<FunctionBuilder<'static>>,
+    pub(crate) classes: Vec<ClassRegistration>,
+    pub(crate) constants: Vec<ConstantBuilder>,
+    resources: Vec<ResourceRegistration>,
+    startup_func: Option<StartupFunction>,
 }
 
 impl ModuleBuilder {
@@ -82,17 +122,33 @@ impl ModuleBuilder {
                 module_number: 0,
                 build_id: unsafe { ext_php_rs_php_build_id() },
             },
+            namespace: None,
             functions: vec![],
+            classes: vec![],
+            constants: vec![],
+            resources: vec![],
+            startup_func: None,
         }
     }
 
-    /// Sets the startup function for the extension.
+    /// Sets the startup function for the extension, called by
+    /// [`ModuleStartup::startup`].
+    ///
+    /// Startup functions defined with the [`php_startup`] macro are called
+    /// after the classes, constants and resource types of the module have been
+    /// registered, or before when given the `before` option. Other functions
+    /// are called before, and must be cast to a [`StartupShutdownFunc`], e.g.
+    /// `startup_function(startup as StartupShutdownFunc)`.
+    ///
+    /// If the startup function fails, the extension fails to start.
     ///
     /// # Arguments
     ///
     /// * `func` - The function to be called on startup.
-    pub fn startup_function(mut self, func: StartupShutdownFunc) -> Self {
-        self.module.module_startup_func = Some(func);
+    ///
+    /// [`php_startup`]: crate::php_startup
+    pub fn startup_function<T: Into<StartupFunction>>(mut self, func: T) -> Self {
+        self.startup_func = Some(func.into());
         self
     }
 
@@ -151,27 +207,283 @@ impl ModuleBuilder {
         self
     }
 
-    /// Adds a function to the extension.
+    /// Sets the default namespace of the functions and constants of the
+    /// extension. Functions and constants given their own namespace are not
+    /// affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace, e.g. `Acme\Util`.
+    pub fn namespace<T: Into<String>>(mut self, namespace: T) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Adds a function to the extension. Functions exported with the
+    /// [`php_function`] macro are retrieved with the [`wrap_function`] macro.
     ///
     /// # Arguments
     ///
     /// * `func` - The function to be added to the extension.
-    pub fn function(mut self, func: FunctionEntry) -> Self {
+    ///
+    /// [`php_function`]: crate::php_function
+    /// [`wrap_function`]: crate::wrap_function
+    pub fn function(mut self, func: FunctionBuilder<'static>) -> Self {
         self.functions.push(func);
         self
     }
 
-    /// Builds the extension and returns a `ModuleEntry`.
+    /// Adds a class to the extension. The class is registered when the
+    /// extension starts up.
+    ///
+    /// # Type parameters
+    ///
+    /// * `T` - The Rust type exported as the class, usually through the
+    ///   [`php_class`] macro.
+    ///
+    /// [`php_class`]: crate::php_class
+    pub fn class<T: RegisteredClass>(mut self) -> Self {
+        self.classes.push(ClassRegistration {
+            register: ClassBuilder::register::<T>,
+            describe: T::describe,
+        });
+        self
+    }
+
+    /// Adds the exception classes of a Rust error type to the extension. The
+    /// classes are registered when the extension starts up.
+    ///
+    /// # Type parameters
+    ///
+    /// * `T` - The Rust error type, usually deriving the [`PhpException`]
+    ///   macro.
+    ///
+    /// [`PhpException`]: crate::PhpException
+    pub fn exception<T: RegisteredException>(self) -> Self {
+        T::register_classes(self)
+    }
+
+    /// Adds a resource type to the extension, allowing [`Resource<T>`] to be
+    /// created and used as a parameter. The resource type is registered when
+    /// the extension starts up.
+    ///
+    /// # Type parameters
+    ///
+    /// * `T` - The Rust type held by the resources.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the resource type, returned by
+    ///   `get_resource_type()`.
+    ///
+    /// [`Resource<T>`]: crate::types::Resource
+    pub fn resource<T: 'static>(mut self, name: &str) -> Self {
+        self.resources.push(ResourceRegistration {
+            name: name.into(),
+            register: Resource::<T>::register,
+        });
+        self
+    }
+
+    /// Adds a constant to the extension. The constant is registered when the
+    /// extension starts up. Constants exported with the [`php_const`] macro
+    /// are retrieved with the [`wrap_constant`] macro.
+    ///
+    /// # Arguments
+    ///
+    /// * `constant` - The constant to be added to the extension.
+    ///
+    /// [`php_const`]: crate::php_const
+    /// [`wrap_constant`]: crate::wrap_constant
+    pub fn constant(mut self, constant: ConstantBuilder) -> Self {
+        self.constants.push(constant);
+        self
+    }
+
+    /// Applies the default namespace of the module to the functions and
+    /// constants without a namespace.
+    pub(crate) fn resolve_namespaces(mut self) -> Self {
+        if let Some(namespace) = &self.namespace {
+            for func in &mut self.functions {
+                func.namespace.get_or_insert_with(|| namespace.clone());
+            }
+            for constant in &mut self.constants {
+                constant.namespace.get_or_insert_with(|| namespace.clone());
+            }
+        }
+        self
+    }
+
+    /// Builds the extension and returns a `ModuleEntry`, along with the
+    /// [`ModuleStartup`] registering the rest of the module with PHP.
+    ///
+    /// The startup function of the entry is left unset. It must be set to a
+    /// function calling [`ModuleStartup::startup`], as done by the
+    /// [`php_module`] macro.
     ///
     /// Returns a result containing the module entry if successful.
-    pub fn build(mut self) -> Result<ModuleEntry> {
-        self.functions.push(FunctionEntry::end());
-        self.module.functions =
-            Box::into_raw(self.functions.into_boxed_slice()) as *const FunctionEntry;
-        self.module.name = CString::new(self.name)?.into_raw();
-        self.module.version = CString::new(self.version)?.into_raw();
-
-        Ok(self.module)
+    ///
+    /// [`php_module`]: crate::php_module
+    pub fn build(self) -> Result<(ModuleEntry, ModuleStartup)> {
+        let Self {
+            name,
+            version,
+            mut module,
+            functions,
+            classes,
+            constants,
+            resources,
+            startup_func,
+            ..
+        } = self.resolve_namespaces();
+
+        let deprecations = functions
+            .iter()
+            .filter_map(FunctionBuilder::deprecation)
+            .collect();
+        let mut functions = functions
+            .into_iter()
+            .map(FunctionBuilder::build)
+            .collect::<Result<Vec<_>>>()?;
+        functions.push(FunctionEntry::end());
+        module.functions = Box::into_raw(functions.into_boxed_slice()) as *const FunctionEntry;
+        module.name = CString::new(name)?.into_raw();
+        module.version = CString::new(version)?.into_raw();
+
+        let startup = ModuleStartup {
+            startup_func,
+            items: ModuleItems {
+                deprecations,
+                classes,
+                constants,
+                resources,
+            },
+        };
+
+        Ok((module, startup))
+    }
+}
+
+/// A startup function of a module, see [`ModuleBuilder::startup_function`].
+/// Functions defined with the [`php_startup`] macro are of this type.
+///
+/// [`php_startup`]: crate::php_startup
+#[derive(Debug, Clone, Copy)]
+pub struct StartupFunction {
+    func: StartupShutdownFunc,
+    before: bool,
+}
+
+impl StartupFunction {
+    /// Creates a startup function.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on startup.
+    /// * `before` - Whether the function is called before the classes,
+    ///   constants and resource types of the module are registered.
+    pub const fn new(func: StartupShutdownFunc, before: bool) -> Self {
+        Self { func, before }
+    }
+}
+
+impl From<StartupShutdownFunc> for StartupFunction {
+    fn from(func: StartupShutdownFunc) -> Self {
+        Self::new(func, true)
+    }
+}
+
+/// The rest of a module built by [`ModuleBuilder::build`], registered with
+/// PHP when the extension starts up.
+#[derive(Debug)]
+pub struct ModuleStartup {
+    startup_func: Option<StartupFunction>,
+    items: ModuleItems,
+}
+
+impl ModuleStartup {
+    /// Registers the classes, constants and resource types of the module with
+    /// PHP and calls the startup function of the module, in the order given by
+    /// the startup function. Must be called from the startup function of the
+    /// module entry.
+    ///
+    /// Returns `SUCCESS`, or `FAILURE` after raising an `E_CORE_WARNING` with
+    /// the error if the registration failed, or if the startup function
+    /// failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ty` - The type of the module, passed to the startup function.
+    /// * `module_number` - The module number, passed to the startup function.
+    pub fn startup(self, ty: i32, module_number: i32) -> i32 {
+        let Self {
+            startup_func,
+            items,
+        } = self;
+        let (before, after) = match startup_func {
+            Some(func) if func.before => (Some(func.func), None),
+            Some(func) => (None, Some(func.func)),
+            None => (None, None),
+        };
+
+        if let Some(func) = before {
+            if func(ty, module_number) != ZEND_RESULT_CODE_SUCCESS {
+                return ZEND_RESULT_CODE_FAILURE;
+            }
+        }
+        if let Err(e) = items.register(module_number) {
+            startup_warning(e);
+            return ZEND_RESULT_CODE_FAILURE;
+        }
+        match after {
+            Some(func) => func(ty, module_number),
+            None => ZEND_RESULT_CODE_SUCCESS,
+        }
+    }
+}
+
+/// A class added to a module, registered when the extension starts up.
+#[derive(Debug)]
+pub(crate) struct ClassRegistration {
+    register: fn() -> Result<()>,
+    pub(crate) describe: fn() -> Class,
+}
+
+/// A resource type added to a module, registered when the extension starts up.
+#[derive(Debug)]
+struct ResourceRegistration {
+    name: String,
+    register: fn(&str, i32) -> Result<()>,
+}
+
+/// The classes, constants and resource types to register when the extension
+/// starts up, and the deprecation messages to attach to the functions of the
+/// module once PHP has registered them.
+#[derive(Debug)]
+struct ModuleItems {
+    deprecations: Vec<(String, &'static str)>,
+    classes: Vec<ClassRegistration>,
+    constants: Vec<ConstantBuilder>,
+    resources: Vec<ResourceRegistration>,
+}
+
+impl ModuleItems {
+    fn register(self, module_number: i32) -> Result<()> {
+        #[cfg(feature = "closure")]
+        ClassBuilder::register::<crate::closure::RustClosure>()?;
+        for (name, message) in self.deprecations {
+            attach_deprecation(None, &name, message);
+        }
+        for resource in self.resources {
+            (resource.register)(&resource.name, module_number)?;
+        }
+        for class in self.classes {
+            (class.register)()?;
+        }
+        for constant in self.constants {
+            constant.register(module_number)?;
+        }
+        Ok(())
     }
 }
 
@@ -9,21 +9,47 @@ use std::{
 use once_cell::sync::OnceCell;
 
 use crate::{
-    builders::FunctionBuilder,
+    builders::{ClassBuilder, FunctionBuilder},
+    convert::IntoZvalDyn,
+    describe::{abi, Class, DocBlock},
+    error::Result,
     exception::PhpException,
+    flags::{ClassFlags, MethodFlags},
     props::Property,
     zend::{ClassEntry, ExecuteData, ZendObjectHandlers},
 };
 
 /// Implemented on Rust types which are exported to PHP. Allows users to get and
 /// set PHP properties on the object.
+///
+/// Classes are registered with PHP by adding them to the module with
+/// [`ModuleBuilder::class`].
+///
+/// [`ModuleBuilder::class`]: crate::builders::ModuleBuilder::class
 pub trait RegisteredClass: Sized + 'static {
     /// PHP class name of the registered class.
     const CLASS_NAME: &'static str;
 
-    /// Optional class constructor.
+    /// Optional class constructor. Classes exported with the [`php_impl`]
+    /// macro provide their constructor through [`constructor`] instead.
+    ///
+    /// [`php_impl`]: crate::php_impl
+    /// [`constructor`]: RegisteredClass::constructor
     const CONSTRUCTOR: Option<ConstructorMeta<Self>> = None;
 
+    /// Function called with the class builder before the class is registered,
+    /// returning the modified builder.
+    const BUILDER_MODIFIER: Option<fn(ClassBuilder) -> Result<ClassBuilder>> = None;
+
+    /// Function returning the parent class of the class.
+    const EXTENDS: Option<fn() -> &'static ClassEntry> = None;
+
+    /// Functions returning the interfaces implemented by the class.
+    const IMPLEMENTS: &'static [fn() -> &'static ClassEntry] = &[];
+
+    /// Flags the class is registered with.
+    const FLAGS: ClassFlags = ClassFlags::empty();
+
     /// Returns a reference to the class metadata, which stores the class entry
     /// and handlers.
     ///
@@ -43,6 +69,43 @@ pub trait RegisteredClass: Sized + 'static {
     /// through the [`ClassMetadata::get_properties`] function, which builds the
     /// hashmap one and stores it in memory.
     fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>>;
+
+    /// Returns the methods of the class, along with their flags.
+    fn method_builders() -> Vec<(FunctionBuilder<'static>, MethodFlags)> {
+        Vec::new()
+    }
+
+    /// Returns the class constructor, if any. Defaults to [`CONSTRUCTOR`].
+    ///
+    /// [`CONSTRUCTOR`]: RegisteredClass::CONSTRUCTOR
+    fn constructor() -> Option<ConstructorMeta<Self>> {
+        Self::CONSTRUCTOR
+    }
+
+    /// Returns the constants of the class.
+    fn constants() -> &'static [(&'static str, &'static dyn IntoZvalDyn)] {
+        &[]
+    }
+
+    /// Returns the names of the constants of the class which are deprecated.
+    fn deprecated_constants() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Returns a description of the class, used by the `cargo-php` CLI to
+    /// generate stubs. Only the name of the class is described by default.
+    fn describe() -> Class {
+        Class {
+            name: Self::CLASS_NAME.into(),
+            docs: DocBlock(Vec::new().into()),
+            extends: abi::Option::None,
+            implements: Vec::new().into(),
+            properties: Vec::new().into(),
+            methods: Vec::new().into(),
+            constants: Vec::new().into(),
+            deprecated: abi::Option::None,
+        }
+    }
 }
 
 /// Stores metadata about a classes Rust constructor, including the function
@@ -1,51 +1,79 @@
 //! Types and functions used for exporting Rust closures to PHP.
 
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{c_void, CStr, CString},
+    mem,
+    os::raw::c_char,
+    ptr, slice,
+};
 
 use crate::{
-    args::{Arg, ArgParser},
-    builders::{ClassBuilder, FunctionBuilder},
+    args::{Arg, ArgInfo, ArgParser},
+    boxed::ZBox,
+    builders::FunctionBuilder,
     class::{ClassMetadata, RegisteredClass},
     convert::{FromZval, IntoZval},
+    error::Result,
     exception::PhpException,
-    flags::{DataType, MethodFlags},
+    ffi::{
+        zend_create_fake_closure, zend_function, zend_set_function_arg_flags,
+        ZEND_ACC_HAS_RETURN_TYPE, ZEND_ACC_HAS_TYPE_HINTS, ZEND_ACC_PUBLIC, ZEND_ACC_VARIADIC,
+        ZEND_INTERNAL_FUNCTION, _ZEND_IS_VARIADIC_BIT, _ZEND_SEND_MODE_SHIFT,
+    },
+    flags::{ClassFlags, DataType},
+    internal::run_handler,
     props::Property,
-    types::Zval,
-    zend::ExecuteData,
+    types::{ZendClassObject, ZendStr, Zval},
+    zend::{ExecuteData, FunctionEntry},
     zend_fastcall,
 };
 
-/// Class entry and handlers for Rust closures.
-static CLOSURE_META: ClassMetadata<Closure> = ClassMetadata::new();
+/// Name of the functions PHP closures are created from.
+const CLOSURE_NAME: &str = "{closure}";
+
+/// Class entry and handlers of the objects holding Rust closures.
+static CLOSURE_META: ClassMetadata<RustClosure> = ClassMetadata::new();
 
 /// Wrapper around a Rust closure, which can be exported to PHP.
 ///
-/// Closures can have up to 8 parameters, all must implement [`FromZval`], and
-/// can return anything that implements [`IntoZval`]. Closures must have a
-/// static lifetime, and therefore cannot modify any `self` references.
+/// Closures wrapped with [`Closure::wrap`] or [`Closure::wrap_once`] can have
+/// up to 12 parameters, all must implement [`FromZval`], and can return
+/// anything that implements [`IntoZval`]. Closures taking any number of
+/// parameters can be wrapped with [`Closure::wrap_args`] or
+/// [`Closure::wrap_variadic`], which hand them the arguments as a slice.
+/// Closures must have a static lifetime, and therefore cannot modify any
+/// `self` references.
 ///
-/// Internally, closures are implemented as a PHP class. A class `RustClosure`
-/// is registered with an `__invoke` method:
+/// When returned to PHP, the closure becomes an instance of the built-in
+/// `\Closure` class, created from an internal function named `{closure}`
+/// whose parameters and return type are those of the Rust closure:
 ///
 /// ```php
 /// <?php
 ///
-/// class RustClosure {
-///     public function __invoke(...$args): mixed {
-///         // ...    
-///     }
-/// }
+/// $closure = function (string $a, ?int $b): string {
+///     // ...
+/// };
 /// ```
 ///
-/// The Rust closure is then double boxed, firstly as a `Box<dyn Fn(...) ->
+/// The closure can therefore be passed to parameters typed as `\Closure` or
+/// `callable`, and inspected through reflection. As for closures created from
+/// methods (e.g. `$object->method(...)`), the closure is bound to an object,
+/// of the internal `RustClosure` class, which holds the Rust closure. It can
+/// be copied with `Closure::bind()` and `bindTo()`, as long as it stays bound
+/// to the same object and scope, and cannot be bound to another object or
+/// scope, e.g. through `Closure::call()`.
+///
+/// Internally, the Rust closure is double boxed, firstly as a `Box<dyn Fn(...) ->
 /// ...>` (depending on the signature of the closure) and then finally boxed as
 /// a `Box<dyn PhpClosure>`. This is a workaround, as `PhpClosure` is not
 /// generically implementable on types that implement `Fn(T, ...) -> Ret`. Make
 /// a suggestion issue if you have a better idea of implementing this!.
 ///
-/// When the `__invoke` method is called from PHP, the `invoke` method is called
-/// on the `dyn PhpClosure`\ trait object, and from there everything is
-/// basically the same as a regular PHP function.
+/// The Rust closure is dropped along with the object holding it, once the PHP
+/// closure and the closures bound from it have been freed.
 pub struct Closure(Box<dyn PhpClosure>);
 
 unsafe impl Send for Closure {}
@@ -55,9 +83,10 @@ impl Closure {
     /// Wraps a [`Fn`] or [`FnMut`] Rust closure into a type which can be
     /// returned to PHP.
     ///
-    /// The closure can accept up to 8 arguments which implement [`IntoZval`],
-    /// and can return any type which implements [`FromZval`]. The closure
-    /// must have a static lifetime, so cannot reference `self`.
+    /// The closure can accept up to 12 arguments which implement [`FromZval`],
+    /// and can return any type which implements [`IntoZval`]. The closure
+    /// must have a static lifetime, so cannot reference `self`. Closures
+    /// taking more arguments must be wrapped with [`Closure::wrap_args`].
     ///
     /// # Parameters
     ///
@@ -84,8 +113,8 @@ impl Closure {
     /// PHP. If the closure is called more than once from PHP, an exception
     /// is thrown.
     ///
-    /// The closure can accept up to 8 arguments which implement [`IntoZval`],
-    /// and can return any type which implements [`FromZval`]. The closure
+    /// The closure can accept up to 12 arguments which implement [`FromZval`],
+    /// and can return any type which implements [`IntoZval`]. The closure
     /// must have a static lifetime, so cannot reference `self`.
     ///
     /// # Parameters
@@ -110,47 +139,183 @@ impl Closure {
         func.into_closure()
     }
 
-    /// Builds the class entry for [`Closure`], registering it with PHP. This
-    /// function should only be called once inside your module startup
-    /// function.
+    /// Wraps a Rust closure taking the given parameters into a type which can
+    /// be returned to PHP. The closure is given the arguments it was called
+    /// with, in order, once their number has been checked against the
+    /// parameters.
+    ///
+    /// The parameters following the first one which has a default value or is
+    /// variadic are optional. The arguments given to a variadic parameter are
+    /// handed to the closure after the others.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The parameters of the closure.
+    /// * `func` - The closure to wrap.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ext_php_rs::{args::Arg, closure::Closure, flags::DataType, types::Zval};
+    ///
+    /// let closure = Closure::wrap_args(
+    ///     vec![
+    ///         Arg::new("separator", DataType::String),
+    ///         Arg::new("parts", DataType::String).is_variadic(),
+    ///     ],
+    ///     |args: &[&Zval]| {
+    ///         let parts: Vec<_> = args[1..].iter().filter_map(|part| part.str()).collect();
+    ///         parts.join(args[0].str().unwrap_or_default())
+    ///     },
+    /// );
+    /// ```
+    pub fn wrap_args<F, R>(args: Vec<Arg<'static>>, func: F) -> Self
+    where
+        F: FnMut(&[&Zval]) -> R + 'static,
+        R: IntoZval,
+    {
+        Self::wrap(Declared { args, func })
+    }
+
+    /// Wraps a Rust closure taking any number of arguments into a type which
+    /// can be returned to PHP. The closure is given the arguments it was
+    /// called with, and is seen by PHP as taking a variadic `mixed ...$args`
+    /// parameter.
+    ///
+    /// # Parameters
     ///
-    /// # Panics
+    /// * `func` - The closure to wrap.
     ///
-    /// Panics if the function is called more than once.
-    pub fn build() {
-        if CLOSURE_META.has_ce() {
-            panic!("Closure has already been built.");
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ext_php_rs::{closure::Closure, types::Zval};
+    ///
+    /// let closure = Closure::wrap_variadic(|args: &[&Zval]| args.len() as i64);
+    /// ```
+    pub fn wrap_variadic<F, R>(func: F) -> Self
+    where
+        F: FnMut(&[&Zval]) -> R + 'static,
+        R: IntoZval,
+    {
+        Self::wrap_args(vec![Arg::new("args", DataType::Mixed).is_variadic()], func)
+    }
+
+    /// Creates the PHP closure, bound to a new object holding the Rust
+    /// closure.
+    ///
+    /// The closure is created from an internal function built from the
+    /// signature of the Rust closure, as PHP does for closures created from
+    /// methods. PHP copies the function into the closure and into every
+    /// closure bound from it, along with a reference to the object, which is
+    /// given to [`Closure::invoke`] as `$this`.
+    fn create(self, zv: &mut Zval) -> Result<()> {
+        let entry = self
+            .0
+            .signature(FunctionBuilder::new(CLOSURE_NAME, Self::invoke))
+            .build()?;
+        let handler = entry.handler;
+        // SAFETY: The entry was built by the function builder.
+        let (ret, args) = unsafe { signature_arg_info(entry) }
+            .split_first()
+            .expect("Function entries start with the return type");
+
+        let mut name = ZendStr::new(CLOSURE_NAME, false);
+        let ce = RustClosure::get_metadata().ce() as *const _ as *mut _;
+        // SAFETY: An all-zero internal function is a valid value, whose fields are
+        // set below as `zend_register_functions()` does.
+        let mut func: zend_function = unsafe { mem::zeroed() };
+        let internal = unsafe { &mut func.internal_function };
+        internal.type_ = ZEND_INTERNAL_FUNCTION as _;
+        internal.fn_flags = ZEND_ACC_PUBLIC;
+        internal.function_name = &mut *name;
+        internal.scope = ce;
+        internal.handler = handler;
+        internal.arg_info = args.as_ptr() as *mut ArgInfo;
+        internal.num_args = args.len() as _;
+        // The name of the return type holds the number of required arguments.
+        internal.required_num_args = ret.name as usize as _;
+        if args
+            .last()
+            .is_some_and(|arg| arg.type_.type_mask & _ZEND_IS_VARIADIC_BIT != 0)
+        {
+            internal.fn_flags |= ZEND_ACC_VARIADIC;
+            internal.num_args -= 1;
+        }
+        if is_type_set(ret) {
+            internal.fn_flags |= ZEND_ACC_HAS_RETURN_TYPE;
+        }
+        if args.iter().any(is_type_set) {
+            internal.fn_flags |= ZEND_ACC_HAS_TYPE_HINTS;
         }
 
-        let ce = ClassBuilder::new("RustClosure")
-            .method(
-                FunctionBuilder::new("__invoke", Self::invoke)
-                    .not_required()
-                    .arg(Arg::new("args", DataType::Mixed).is_variadic())
-                    .returns(DataType::Mixed, false, true)
-                    .build()
-                    .expect("Failed to build `RustClosure` PHP class."),
-                MethodFlags::Public,
-            )
-            .object_override::<Self>()
-            .build()
-            .expect("Failed to build `RustClosure` PHP class.");
-        CLOSURE_META.set_ce(ce);
+        let mut holder = ZendClassObject::new(RustClosure {
+            closure: self.0,
+            name,
+        });
+        let mut this = Zval::new();
+        this.set_object(&mut holder.std);
+
+        zv.set_null();
+        // SAFETY: PHP copies the function into the closure, taking a reference to its
+        // name and to the object.
+        unsafe {
+            zend_set_function_arg_flags(&mut func);
+            zend_create_fake_closure(zv, &mut func, ce, ce, &mut this);
+        }
+
+        Ok(())
     }
 
     zend_fastcall! {
         /// External function used by the Zend interpreter to call the closure.
         extern "C" fn invoke(ex: &mut ExecuteData, ret: &mut Zval) {
-            let (parser, this) = ex.parser_method::<Self>();
-            let this = this.expect("Internal closure function called on non-closure class");
-
-            this.0.invoke(parser, ret)
+            run_handler(|| {
+                // SAFETY: PHP calls the handler with the function being called.
+                let name = unsafe { (*ex.func).common.function_name };
+                let (parser, this) = ex.parser_object();
+
+                // A closure may have been bound to the object of another Rust closure, which
+                // does not share the name of its function.
+                let holder = this
+                    .and_then(ZendClassObject::<RustClosure>::from_zend_obj_mut)
+                    .and_then(|this| this.obj.as_mut())
+                    .filter(|holder| ptr::eq(&*holder.name, name));
+
+                match holder {
+                    Some(holder) => holder.closure.invoke(parser, ret),
+                    None => {
+                        let _ = PhpException::default(
+                            "Rust closures cannot be called once bound to another `RustClosure` object.".into(),
+                        )
+                        .throw();
+                    }
+                }
+            });
         }
     }
 }
 
-impl RegisteredClass for Closure {
+impl IntoZval for Closure {
+    const TYPE: DataType = DataType::Object(Some("Closure"));
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        self.create(zv)
+    }
+}
+
+/// The object a PHP closure created from a Rust closure is bound to, which
+/// holds the Rust closure until the object is freed.
+pub(crate) struct RustClosure {
+    closure: Box<dyn PhpClosure>,
+    /// The name of the function of the PHP closure, which PHP copies into
+    /// every closure bound from it.
+    name: ZBox<ZendStr>,
+}
+
+impl RegisteredClass for RustClosure {
     const CLASS_NAME: &'static str = "RustClosure";
+    const FLAGS: ClassFlags = ClassFlags::Final;
 
     fn get_metadata() -> &'static ClassMetadata<Self> {
         &CLOSURE_META
@@ -161,7 +326,82 @@ impl RegisteredClass for Closure {
     }
 }
 
-class_derives!(Closure);
+/// A parameter or the return type of a closure, see [`signature_arg_info`].
+#[derive(PartialEq, Eq, Hash)]
+struct ArgKey {
+    name: Option<CString>,
+    type_mask: u32,
+    class: Option<CString>,
+    default: Option<CString>,
+}
+
+thread_local! {
+    /// The argument information of the PHP closures, by signature.
+    static SIGNATURES: RefCell<HashMap<(usize, Vec<ArgKey>), &'static [ArgInfo]>> =
+        RefCell::default();
+}
+
+/// Returns the argument information of the function built from the signature
+/// of a Rust closure, starting with the return type.
+///
+/// PHP copies the function of a closure when binding it, and a copy may be
+/// bound to the object of another Rust closure, outliving the object of the
+/// Rust closure it was created from. The argument information is therefore
+/// shared by the closures with the same signature and kept by the thread. As
+/// when PHP registers functions, class names are turned into Zend strings.
+///
+/// # Safety
+///
+/// The entry must have been returned by [`FunctionBuilder::build`].
+unsafe fn signature_arg_info(entry: FunctionEntry) -> &'static [ArgInfo] {
+    unsafe fn c_str(ptr: *const c_char) -> Option<CString> {
+        (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_owned())
+    }
+
+    let arg_info =
+        slice::from_raw_parts_mut(entry.arg_info as *mut ArgInfo, entry.num_args as usize + 1);
+    let args = arg_info.iter().enumerate().map(|(i, info)| ArgKey {
+        // The name of the return type holds the number of required arguments.
+        name: if i > 0 { c_str(info.name) } else { None },
+        type_mask: info.type_.type_mask,
+        class: c_str(info.type_.ptr as *const c_char),
+        default: c_str(info.default_value),
+    });
+    let key = (arg_info[0].name as usize, args.collect());
+
+    SIGNATURES.with(|signatures| {
+        let mut signatures = signatures.borrow_mut();
+        if let Some(arg_info) = signatures.get(&key) {
+            entry.free();
+            return *arg_info;
+        }
+
+        drop(CString::from_raw(entry.fname as *mut c_char));
+        for info in arg_info.iter_mut() {
+            // Only class types point to a (class name) string.
+            if info.type_.ptr.is_null() {
+                continue;
+            }
+            let class = CString::from_raw(info.type_.ptr as *mut c_char);
+            info.type_.ptr =
+                ZendStr::new(class.as_bytes(), true).into_raw() as *mut ZendStr as *mut c_void;
+            #[cfg(php83)]
+            {
+                info.type_.type_mask &= !crate::ffi::_ZEND_TYPE_LITERAL_NAME_BIT;
+                info.type_.type_mask |= crate::ffi::_ZEND_TYPE_NAME_BIT;
+            }
+        }
+        let arg_info: &'static [ArgInfo] = arg_info;
+        signatures.insert(key, arg_info);
+        arg_info
+    })
+}
+
+/// Returns whether a parameter or return type has a type, i.e. the
+/// `ZEND_TYPE_IS_SET` macro.
+fn is_type_set(info: &ArgInfo) -> bool {
+    info.type_.type_mask & ((1 << _ZEND_SEND_MODE_SHIFT) - 1) != 0
+}
 
 /// Implemented on types which can be used as PHP closures.
 ///
@@ -169,12 +409,26 @@ class_derives!(Closure);
 /// closure is called from PHP. Arguments must be parsed from the
 /// [`ExecuteData`] and the return value is returned through the [`Zval`].
 ///
-/// This trait is automatically implemented on functions with up to 8
-/// parameters.
+/// This trait is automatically implemented on functions with up to 12
+/// parameters. Functions with more parameters can be wrapped with
+/// [`Closure::wrap_args`].
 #[allow(clippy::missing_safety_doc)]
 pub unsafe trait PhpClosure {
     /// Invokes the closure.
     fn invoke<'a>(&'a mut self, parser: ArgParser<'a, '_>, ret: &mut Zval);
+
+    /// Adds the parameters and return type of the closure to the function the
+    /// PHP closure is created from. By default, the closure takes any number
+    /// of arguments of any type, and returns `mixed`.
+    ///
+    /// # Parameters
+    ///
+    /// * `func` - The function the PHP closure is created from.
+    fn signature<'a>(&self, func: FunctionBuilder<'a>) -> FunctionBuilder<'a> {
+        func.not_required()
+            .arg(Arg::new("args", DataType::Mixed).is_variadic())
+            .returns(DataType::Mixed, false, true)
+    }
 }
 
 /// Implemented on [`FnOnce`] types which can be used as PHP closures. See
@@ -188,15 +442,100 @@ pub trait PhpOnceClosure {
     fn into_closure(self) -> Closure;
 }
 
+/// Returns the argument of a closure for a parameter of type `T`.
+fn closure_arg<'a, T>(name: &str) -> Arg<'a>
+where
+    for<'b> T: FromZval<'b>,
+{
+    let arg = Arg::new(name, T::TYPE);
+    if T::NULLABLE {
+        arg.allow_null()
+    } else {
+        arg
+    }
+}
+
+/// Returns the result of a closure to PHP.
+fn set_result<R: IntoZval>(result: R, ret: &mut Zval) {
+    if let Err(e) = result.set_zval(ret, false) {
+        let _ =
+            PhpException::default(format!("Failed to return closure result to PHP: {}", e)).throw();
+    }
+}
+
+/// Returns the number of required parameters of a closure wrapped with
+/// [`Closure::wrap_args`].
+fn required_args(args: &[Arg]) -> usize {
+    args.iter()
+        .position(|arg| arg.variadic || arg.default_value.is_some())
+        .unwrap_or(args.len())
+}
+
+/// A closure taking the given parameters. See [`Closure::wrap_args`].
+struct Declared<F> {
+    args: Vec<Arg<'static>>,
+    func: F,
+}
+
+unsafe impl<F, R> PhpClosure for Declared<F>
+where
+    F: FnMut(&[&Zval]) -> R,
+    R: IntoZval,
+{
+    fn invoke(&mut self, parser: ArgParser, ret: &mut Zval) {
+        let mut args: Vec<Arg> = self.args.iter().map(Arg::declaration).collect();
+        let required = required_args(&args);
+        let mut parser = parser;
+        for (i, arg) in args.iter_mut().enumerate() {
+            if i == required {
+                parser = parser.not_required();
+            }
+            parser = parser.arg(arg);
+        }
+        if parser.parse().is_err() {
+            return;
+        }
+
+        let variadic = match args.last() {
+            Some(arg) if arg.variadic => args.pop().map(Arg::into_variadic_zvals),
+            _ => None,
+        }
+        .unwrap_or_default();
+        let mut values: Vec<&Zval> = args
+            .iter_mut()
+            .filter_map(|arg| arg.zval())
+            .map(|zv| zv.dereference())
+            .collect();
+        values.extend(variadic.iter().map(|zv| &**zv));
+        set_result((self.func)(&values), ret);
+    }
+
+    fn signature<'a>(&self, mut func: FunctionBuilder<'a>) -> FunctionBuilder<'a> {
+        let required = required_args(&self.args);
+        for (i, arg) in self.args.iter().enumerate() {
+            if i == required {
+                func = func.not_required();
+            }
+            func = func.arg(arg.declaration());
+        }
+        func.returns(R::TYPE, false, R::NULLABLE)
+    }
+}
+
 unsafe impl<R> PhpClosure for Box<dyn Fn() -> R>
 where
     R: IntoZval,
 {
-    fn invoke(&mut self, _: ArgParser, ret: &mut Zval) {
-        if let Err(e) = self().set_zval(ret, false) {
-            let _ = PhpException::default(format!("Failed to return closure result to PHP: {}", e))
-                .throw();
+    fn invoke(&mut self, parser: ArgParser, ret: &mut Zval) {
+        if parser.parse().is_err() {
+            return;
         }
+
+        set_result(self(), ret);
+    }
+
+    fn signature<'a>(&self, func: FunctionBuilder<'a>) -> FunctionBuilder<'a> {
+        func.returns(R::TYPE, false, R::NULLABLE)
     }
 }
 
@@ -204,11 +543,16 @@ unsafe impl<R> PhpClosure for Box<dyn FnMut() -> R>
 where
     R: IntoZval,
 {
-    fn invoke(&mut self, _: ArgParser, ret: &mut Zval) {
-        if let Err(e) = self().set_zval(ret, false) {
-            let _ = PhpException::default(format!("Failed to return closure result to PHP: {}", e))
-                .throw();
+    fn invoke(&mut self, parser: ArgParser, ret: &mut Zval) {
+        if parser.parse().is_err() {
+            return;
         }
+
+        set_result(self(), ret);
+    }
+
+    fn signature<'a>(&self, func: FunctionBuilder<'a>) -> FunctionBuilder<'a> {
+        func.returns(R::TYPE, false, R::NULLABLE)
     }
 }
 
@@ -237,9 +581,9 @@ where
 }
 
 macro_rules! php_closure_impl {
-    ($($gen: ident),*) => {
-        php_closure_impl!(Fn; $($gen),*);
-        php_closure_impl!(FnMut; $($gen),*);
+    ($($gen: ident $arg: ident),*) => {
+        php_closure_impl!(Fn; $($gen $arg),*);
+        php_closure_impl!(FnMut; $($gen $arg),*);
 
         impl<$($gen),*, Ret> PhpOnceClosure for Box<dyn FnOnce($($gen),*) -> Ret>
         where
@@ -249,7 +593,7 @@ macro_rules! php_closure_impl {
             fn into_closure(self) -> Closure {
                 let mut this = Some(self);
 
-                Closure::wrap(Box::new(move |$($gen),*| {
+                Closure::wrap(Box::new(move |$($arg),*| {
                     let this = match this.take() {
                         Some(this) => this,
                         None => {
@@ -261,13 +605,13 @@ macro_rules! php_closure_impl {
                         }
                     };
 
-                    Some(this($($gen),*))
+                    Some(this($($arg),*))
                 }) as Box<dyn FnMut($($gen),*) -> Option<Ret>>)
             }
         }
     };
 
-    ($fnty: ident; $($gen: ident),*) => {
+    ($fnty: ident; $($gen: ident $arg: ident),*) => {
         unsafe impl<$($gen),*, Ret> PhpClosure for Box<dyn $fnty($($gen),*) -> Ret>
         where
             $(for<'a> $gen: FromZval<'a>,)*
@@ -275,11 +619,11 @@ macro_rules! php_closure_impl {
         {
             fn invoke(&mut self, parser: ArgParser, ret: &mut Zval) {
                 $(
-                    let mut $gen = Arg::new(stringify!($gen), $gen::TYPE);
+                    let mut $arg = closure_arg::<$gen>(stringify!($arg));
                 )*
 
                 let parser = parser
-                    $(.arg(&mut $gen))*
+                    $(.arg(&mut $arg))*
                     .parse();
 
                 if parser.is_err() {
@@ -288,29 +632,37 @@ macro_rules! php_closure_impl {
 
                 let result = self(
                     $(
-                        match $gen.consume() {
+                        match $arg.consume() {
                             Ok(val) => val,
                             _ => {
-                                let _ = PhpException::default(concat!("Invalid parameter type for `", stringify!($gen), "`.").into()).throw();
+                                let _ = PhpException::default(concat!("Invalid parameter type for `", stringify!($arg), "`.").into()).throw();
                                 return;
                             }
                         }
                     ),*
                 );
 
-                if let Err(e) = result.set_zval(ret, false) {
-                    let _ = PhpException::default(format!("Failed to return closure result to PHP: {}", e)).throw();
-                }
+                set_result(result, ret);
+            }
+
+            fn signature<'a>(&self, func: FunctionBuilder<'a>) -> FunctionBuilder<'a> {
+                func
+                    $(.arg(closure_arg::<$gen>(stringify!($arg))))*
+                    .returns(Ret::TYPE, false, Ret::NULLABLE)
             }
         }
     };
 }
 
-php_closure_impl!(A);
-php_closure_impl!(A, B);
-php_closure_impl!(A, B, C);
-php_closure_impl!(A, B, C, D);
-php_closure_impl!(A, B, C, D, E);
-php_closure_impl!(A, B, C, D, E, F);
-php_closure_impl!(A, B, C, D, E, F, G);
-php_closure_impl!(A, B, C, D, E, F, G, H);
+php_closure_impl!(A a);
+php_closure_impl!(A a, B b);
+php_closure_impl!(A a, B b, C c);
+php_closure_impl!(A a, B b, C c, D d);
+php_closure_impl!(A a, B b, C c, D d, E e);
+php_closure_impl!(A a, B b, C c, D d, E e, F f);
+php_closure_impl!(A a, B b, C c, D d, E e, F f, G g);
+php_closure_impl!(A a, B b, C c, D d, E e, F f, G g, H h);
+php_closure_impl!(A a, B b, C c, D d, E e, F f, G g, H h, I i);
+php_closure_impl!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j);
+php_closure_impl!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k);
+php_closure_impl!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k, L l);
@@ -1,5 +1,7 @@
 //! Traits used to convert between Zend/PHP and Rust types.
 
+use std::{rc::Rc, sync::Arc};
+
 use crate::{
     boxed::ZBox,
     error::Result,
@@ -14,6 +16,9 @@ pub trait FromZval<'a>: Sized {
     /// The corresponding type of the implemented value in PHP.
     const TYPE: DataType;
 
+    /// Whether `null` is accepted in place of the type, i.e. for [`Option`].
+    const NULLABLE: bool = false;
+
     /// Attempts to retrieve an instance of `Self` from a reference to a
     /// [`Zval`].
     ///
@@ -21,6 +26,38 @@ pub trait FromZval<'a>: Sized {
     ///
     /// * `zval` - Zval to get value from.
     fn from_zval(zval: &'a Zval) -> Option<Self>;
+
+    /// Describes the values of type [`TYPE`](Self::TYPE) which can be
+    /// converted, e.g. `must be between 0 and 255`. It is the message of the
+    /// `ValueError` thrown when a function is given an argument of the right
+    /// type which cannot be converted.
+    fn value_constraint() -> Option<String> {
+        None
+    }
+
+    /// Describes why a value could not be converted, for types whose
+    /// conversion fails for reasons which depend on the value rather than its
+    /// type, e.g. deserializing it. It takes precedence over
+    /// [`value_constraint`](Self::value_constraint) as the message of the
+    /// `ValueError` thrown when a function is given such an argument.
+    ///
+    /// # Parameters
+    ///
+    /// * `zval` - The value which could not be converted.
+    fn value_error(zval: &Zval) -> Option<String> {
+        let _ = zval;
+        None
+    }
+}
+
+/// Ignores the value, e.g. for the return value of a callable.
+impl FromZval<'_> for () {
+    const TYPE: DataType = DataType::Void;
+
+    #[inline]
+    fn from_zval(_: &Zval) -> Option<Self> {
+        Some(())
+    }
 }
 
 impl<'a, T> FromZval<'a> for Option<T>
@@ -28,10 +65,79 @@ where
     T: FromZval<'a>,
 {
     const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = true;
 
     fn from_zval(zval: &'a Zval) -> Option<Self> {
         Some(T::from_zval(zval))
     }
+
+    fn value_constraint() -> Option<String> {
+        T::value_constraint()
+    }
+
+    fn value_error(zval: &Zval) -> Option<String> {
+        T::value_error(zval)
+    }
+}
+
+impl<'a, T> FromZval<'a> for Box<T>
+where
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        T::from_zval(zval).map(Box::new)
+    }
+
+    fn value_constraint() -> Option<String> {
+        T::value_constraint()
+    }
+
+    fn value_error(zval: &Zval) -> Option<String> {
+        T::value_error(zval)
+    }
+}
+
+impl<'a, T> FromZval<'a> for Rc<T>
+where
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        T::from_zval(zval).map(Rc::new)
+    }
+
+    fn value_constraint() -> Option<String> {
+        T::value_constraint()
+    }
+
+    fn value_error(zval: &Zval) -> Option<String> {
+        T::value_error(zval)
+    }
+}
+
+impl<'a, T> FromZval<'a> for Arc<T>
+where
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        T::from_zval(zval).map(Arc::new)
+    }
+
+    fn value_constraint() -> Option<String> {
+        T::value_constraint()
+    }
+
+    fn value_error(zval: &Zval) -> Option<String> {
+        T::value_error(zval)
+    }
 }
 
 /// Allows mutable zvals to be converted into Rust types in a fallible way.
@@ -50,6 +156,19 @@ pub trait FromZvalMut<'a>: Sized {
     ///
     /// * `zval` - Zval to get value from.
     fn from_zval_mut(zval: &'a mut Zval) -> Option<Self>;
+
+    /// Describes the values of type [`TYPE`](Self::TYPE) which can be
+    /// converted. See [`FromZval::value_constraint`].
+    fn value_constraint() -> Option<String> {
+        None
+    }
+
+    /// Describes why a value could not be converted. See
+    /// [`FromZval::value_error`].
+    fn value_error(zval: &Zval) -> Option<String> {
+        let _ = zval;
+        None
+    }
 }
 
 impl<'a, T> FromZvalMut<'a> for T
@@ -62,6 +181,14 @@ where
     fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
         Self::from_zval(zval)
     }
+
+    fn value_constraint() -> Option<String> {
+        <T as FromZval>::value_constraint()
+    }
+
+    fn value_error(zval: &Zval) -> Option<String> {
+        <T as FromZval>::value_error(zval)
+    }
 }
 
 /// `FromZendObject` is implemented by types which can be extracted from a Zend
@@ -119,6 +246,9 @@ pub trait IntoZval: Sized {
     /// The corresponding type of the implemented value in PHP.
     const TYPE: DataType;
 
+    /// Whether the value can be converted into `null`, i.e. for [`Option`].
+    const NULLABLE: bool = false;
+
     /// Converts a Rust primitive type into a Zval. Returns a result containing
     /// the Zval if successful.
     ///
@@ -158,6 +288,7 @@ where
     T: IntoZval,
 {
     const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = true;
 
     #[inline]
     fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
@@ -177,6 +308,7 @@ where
     E: Into<PhpException>,
 {
     const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
 
     fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
         match self {
@@ -189,6 +321,51 @@ where
     }
 }
 
+impl<T> IntoZval for Box<T>
+where
+    T: IntoZval,
+{
+    const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        (*self).set_zval(zv, persistent)
+    }
+}
+
+/// Clones the value if it is shared with other `Rc`s.
+impl<T> IntoZval for Rc<T>
+where
+    T: IntoZval + Clone,
+{
+    const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        Rc::try_unwrap(self)
+            .unwrap_or_else(|rc| (*rc).clone())
+            .set_zval(zv, persistent)
+    }
+}
+
+/// Clones the value if it is shared with other `Arc`s.
+impl<T> IntoZval for Arc<T>
+where
+    T: IntoZval + Clone,
+{
+    const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        Arc::try_unwrap(self)
+            .unwrap_or_else(|arc| (*arc).clone())
+            .set_zval(zv, persistent)
+    }
+}
+
 /// An object-safe version of the [`IntoZval`] trait.
 ///
 /// This trait is automatically implemented on any type that implements both
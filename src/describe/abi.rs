@@ -73,6 +73,16 @@ impl From<&'static str> for Str {
     }
 }
 
+impl From<String> for Str {
+    /// Leaks the string to create a [`Str`]. Descriptions are only created
+    /// once, by the `cargo-php` CLI, and live for the remainder of the
+    /// program.
+    fn from(val: String) -> Self {
+        let val: &'static str = Box::leak(val.into_boxed_str());
+        val.into()
+    }
+}
+
 impl AsRef<str> for Str {
     fn as_ref(&self) -> &str {
         self.str()
@@ -91,3 +101,12 @@ pub enum Option<T> {
     Some(T),
     None,
 }
+
+impl<T> From<std::option::Option<T>> for Option<T> {
+    fn from(val: std::option::Option<T>) -> Self {
+        match val {
+            Some(val) => Self::Some(val),
+            None => Self::None,
+        }
+    }
+}
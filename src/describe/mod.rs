@@ -4,11 +4,19 @@
 pub mod abi;
 mod stub;
 
-use crate::flags::DataType;
+use crate::{
+    args::Arg,
+    builders::{ConstantBuilder, FunctionBuilder, ModuleBuilder},
+    flags::DataType,
+};
 use abi::*;
+use std::vec::Vec as StdVec;
 
 pub use stub::ToStub;
 
+/// Lines of a documentation comment attached to an export.
+pub type DocComments = &'static [&'static str];
+
 #[repr(C)]
 pub struct Description {
     /// Extension description.
@@ -40,10 +48,49 @@ pub struct Module {
     pub constants: Vec<Constant>,
 }
 
+impl From<ModuleBuilder> for Module {
+    fn from(builder: ModuleBuilder) -> Self {
+        let builder = builder.resolve_namespaces();
+
+        Self {
+            name: builder.name.into(),
+            functions: builder
+                .functions
+                .into_iter()
+                .map(Function::from)
+                .collect::<StdVec<_>>()
+                .into(),
+            classes: builder
+                .classes
+                .iter()
+                .map(|class| (class.describe)())
+                .collect::<StdVec<_>>()
+                .into(),
+            constants: builder
+                .constants
+                .into_iter()
+                .map(Constant::from)
+                .collect::<StdVec<_>>()
+                .into(),
+        }
+    }
+}
+
 /// Represents a set of comments on an export.
 #[repr(C)]
 pub struct DocBlock(pub Vec<Str>);
 
+impl From<DocComments> for DocBlock {
+    fn from(docs: DocComments) -> Self {
+        Self(
+            docs.iter()
+                .map(|doc| (*doc).into())
+                .collect::<StdVec<_>>()
+                .into(),
+        )
+    }
+}
+
 /// Represents an exported function.
 #[repr(C)]
 pub struct Function {
@@ -51,6 +98,31 @@ pub struct Function {
     pub docs: DocBlock,
     pub ret: Option<Retval>,
     pub params: Vec<Parameter>,
+    /// Deprecation message, empty if deprecated without a message.
+    pub deprecated: Option<Str>,
+}
+
+impl From<FunctionBuilder<'_>> for Function {
+    fn from(builder: FunctionBuilder<'_>) -> Self {
+        Self {
+            name: builder.php_name().into(),
+            docs: builder.docs.into(),
+            ret: match builder.retval {
+                Some(ty) => Option::Some(Retval {
+                    ty,
+                    nullable: builder.ret_as_null,
+                }),
+                None => Option::None,
+            },
+            params: builder
+                .args
+                .iter()
+                .map(Parameter::from)
+                .collect::<StdVec<_>>()
+                .into(),
+            deprecated: builder.deprecated.map(Str::from).into(),
+        }
+    }
 }
 
 /// Represents a parameter attached to an exported function or method.
@@ -62,6 +134,21 @@ pub struct Parameter {
     pub default: Option<Str>,
 }
 
+impl From<&Arg<'_>> for Parameter {
+    fn from(arg: &Arg<'_>) -> Self {
+        Self {
+            name: arg.name.clone().into(),
+            ty: Option::Some(if arg.variadic {
+                DataType::Array
+            } else {
+                arg._type
+            }),
+            nullable: arg.allow_null,
+            default: arg.default_value.clone().map(Str::from).into(),
+        }
+    }
+}
+
 /// Represents an exported class.
 #[repr(C)]
 pub struct Class {
@@ -72,6 +159,8 @@ pub struct Class {
     pub properties: Vec<Property>,
     pub methods: Vec<Method>,
     pub constants: Vec<Constant>,
+    /// Deprecation message, empty if deprecated without a message.
+    pub deprecated: Option<Str>,
 }
 
 /// Represents a property attached to an exported class.
@@ -96,6 +185,8 @@ pub struct Method {
     pub retval: Option<Retval>,
     pub _static: bool,
     pub visibility: Visibility,
+    /// Deprecation message, empty if deprecated without a message.
+    pub deprecated: Option<Str>,
 }
 
 /// Represents a value returned from a function or method.
@@ -130,4 +221,17 @@ pub struct Constant {
     pub name: Str,
     pub docs: DocBlock,
     pub value: Option<Str>,
+    /// Deprecation message, empty if deprecated without a message.
+    pub deprecated: Option<Str>,
+}
+
+impl From<ConstantBuilder> for Constant {
+    fn from(builder: ConstantBuilder) -> Self {
+        Self {
+            name: builder.php_name().into(),
+            docs: builder.docs.into(),
+            value: Option::None,
+            deprecated: builder.deprecated.map(Str::from).into(),
+        }
+    }
 }
@@ -110,7 +110,7 @@ impl ToStub for Module {
 
 impl ToStub for Function {
     fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+        fmt_doc_block(&self.docs, &self.deprecated, buf)?;
 
         let (_, name) = split_namespace(self.name.as_ref());
         write!(
@@ -194,7 +194,7 @@ impl ToStub for DocBlock {
 
 impl ToStub for Class {
     fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+        fmt_doc_block(&self.docs, &self.deprecated, buf)?;
 
         let (_, name) = split_namespace(self.name.as_ref());
         write!(buf, "class {name} ")?;
@@ -272,7 +272,7 @@ impl ToStub for Visibility {
 
 impl ToStub for Method {
     fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+        fmt_doc_block(&self.docs, &self.deprecated, buf)?;
         self.visibility.fmt_stub(buf)?;
 
         write!(buf, " ")?;
@@ -308,9 +308,10 @@ impl ToStub for Method {
 
 impl ToStub for Constant {
     fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+        fmt_doc_block(&self.docs, &self.deprecated, buf)?;
 
-        write!(buf, "const {} = ", self.name)?;
+        let (_, name) = split_namespace(self.name.as_ref());
+        write!(buf, "const {name} = ")?;
         if let Option::Some(value) = &self.value {
             write!(buf, "{value}")?;
         } else {
@@ -320,6 +321,29 @@ impl ToStub for Constant {
     }
 }
 
+/// Writes the doc block of an exported item, adding a `@deprecated` tag if the
+/// item has been deprecated.
+fn fmt_doc_block(docs: &DocBlock, deprecated: &Option<Str>, buf: &mut String) -> FmtResult {
+    let deprecated = match deprecated {
+        Option::Some(deprecated) => deprecated.str(),
+        Option::None => return docs.fmt_stub(buf),
+    };
+
+    writeln!(buf, "/**")?;
+    for comment in docs.0.iter() {
+        writeln!(buf, " *{comment}")?;
+    }
+    if !docs.0.is_empty() {
+        writeln!(buf, " *")?;
+    }
+    if deprecated.is_empty() {
+        writeln!(buf, " * @deprecated")?;
+    } else {
+        writeln!(buf, " * @deprecated {deprecated}")?;
+    }
+    writeln!(buf, " */")
+}
+
 #[cfg(windows)]
 const NEW_LINE_SEPARATOR: &str = "\r\n";
 #[cfg(not(windows))]
@@ -371,7 +395,8 @@ fn indent(s: &str, depth: usize) -> String {
 
 #[cfg(test)]
 mod test {
-    use super::split_namespace;
+    use super::{split_namespace, ToStub};
+    use crate::describe::{abi::Option, Constant, DocBlock, Function};
 
     #[test]
     pub fn test_split_ns() {
@@ -380,6 +405,37 @@ mod test {
         assert_eq!(split_namespace("simple\\ns"), (Some("simple"), "ns"));
     }
 
+    #[test]
+    pub fn test_deprecated_function() {
+        let func = Function {
+            name: "foo".into(),
+            docs: DocBlock(vec![" Does foo things.".into()].into()),
+            ret: Option::None,
+            params: vec![].into(),
+            deprecated: Option::Some("use bar() instead".into()),
+        };
+
+        assert_eq!(
+            func.to_stub().expect("failed to generate stub"),
+            "/**\n * Does foo things.\n *\n * @deprecated use bar() instead\n */\nfunction foo() {}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_namespaced_constant() {
+        let constant = Constant {
+            name: "Acme\\Util\\MAX_SIZE".into(),
+            docs: DocBlock(vec![].into()),
+            value: Option::Some("1024".into()),
+            deprecated: Option::None,
+        };
+
+        assert_eq!(
+            constant.to_stub().expect("failed to generate stub"),
+            "const MAX_SIZE = 1024;\n"
+        );
+    }
+
     #[test]
     #[cfg(not(windows))]
     #[allow(clippy::uninlined_format_args)]
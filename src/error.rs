@@ -8,7 +8,8 @@ use std::{
 
 use crate::{
     boxed::ZBox,
-    exception::PhpException,
+    convert::IntoZval,
+    exception::{PhpException, PhpThrowable},
     ffi::php_error_docref,
     flags::{ClassFlags, DataType, ErrorType, ZvalTypeFlags},
     types::ZendObject,
@@ -59,12 +60,29 @@ pub enum Error {
     Callable,
     /// An object was expected.
     Object,
+    /// The given class does not exist.
+    UnknownClass(String),
+    /// The given class is abstract, an interface or a trait, and cannot be
+    /// instantiated.
+    AbstractClass(String),
+    /// The resource type of a Rust type has not been registered with the
+    /// module.
+    UnregisteredResource,
     /// An invalid exception type was thrown.
     InvalidException(ClassFlags),
     /// Converting integer arguments resulted in an overflow.
     IntegerOverflow,
     /// An exception was thrown in a function.
     Exception(ZBox<ZendObject>),
+    /// PHP bailed out (e.g. on a fatal error) while being called from Rust.
+    /// The bailout is resumed once control returns to PHP.
+    Bailout,
+    /// A value could not be serialized into or deserialized from a zval.
+    #[cfg(feature = "serde")]
+    Serde(String),
+    /// The output of PHP could not be captured, for example because it was
+    /// called outside of a request.
+    OutputCapture,
     /// A failure occurred while registering the stream wrapper
     StreamWrapperRegistrationFailure,
     /// A failure occurred while unregistering the stream wrapper
@@ -96,13 +114,23 @@ impl Display for Error {
             Error::InvalidUtf8 => write!(f, "Invalid Utf8 byte sequence."),
             Error::Callable => write!(f, "Could not call given function."),
             Error::Object => write!(f, "An object was expected."),
+            Error::UnknownClass(name) => write!(f, "Class {name} does not exist."),
+            Error::AbstractClass(name) => write!(f, "Class {name} cannot be instantiated."),
+            Error::UnregisteredResource => write!(f, "Resource type has not been registered."),
             Error::InvalidException(flags) => {
                 write!(f, "Invalid exception type was thrown: {flags:?}")
             }
             Error::IntegerOverflow => {
                 write!(f, "Converting integer arguments resulted in an overflow.")
             }
-            Error::Exception(e) => write!(f, "Exception was thrown: {e:?}"),
+            Error::Exception(e) => match PhpThrowable::from_object(e) {
+                Some(ex) => write!(f, "Exception was thrown: {ex}"),
+                None => write!(f, "Exception was thrown: {e:?}"),
+            },
+            Error::Bailout => write!(f, "PHP bailed out while being called from Rust."),
+            #[cfg(feature = "serde")]
+            Error::Serde(msg) => write!(f, "{msg}"),
+            Error::OutputCapture => write!(f, "Failed to capture the output of PHP."),
             Error::StreamWrapperRegistrationFailure => {
                 write!(f, "A failure occurred while registering the stream wrapper")
             }
@@ -116,6 +144,17 @@ impl Display for Error {
     }
 }
 
+impl Error {
+    /// Reads the exception carried by an [`Error::Exception`], including the
+    /// chain of previous exceptions. Returns [`None`] for any other error.
+    pub fn exception(&self) -> Option<PhpThrowable> {
+        match self {
+            Error::Exception(e) => PhpThrowable::from_object(e),
+            _ => None,
+        }
+    }
+}
+
 impl ErrorTrait for Error {}
 
 impl From<NulError> for Error {
@@ -126,7 +165,16 @@ impl From<NulError> for Error {
 
 impl From<Error> for PhpException {
     fn from(err: Error) -> Self {
-        Self::default(err.to_string())
+        let ex = Self::default(err.to_string());
+        match err {
+            // The exception thrown in PHP is kept as the previous exception.
+            Error::Exception(e) => {
+                let mut previous = Self::default(String::new());
+                previous.set_object(e.into_zval(false).ok());
+                ex.with_previous(previous)
+            }
+            _ => ex,
+        }
     }
 }
 
@@ -1,15 +1,31 @@
 //! Types and functions used for throwing exceptions from Rust to PHP.
 
-use std::{ffi::CString, fmt::Debug};
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::RefCell,
+    error::Error as ErrorTrait,
+    ffi::CString,
+    fmt::{Debug, Display},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, Ordering},
+        Once,
+    },
+};
 
 use crate::{
+    builders::ModuleBuilder,
     class::RegisteredClass,
+    convert::IntoZval,
     error::{Error, Result},
+    ffi::zend_read_property,
     ffi::zend_throw_exception_ex,
     ffi::zend_throw_exception_object,
     flags::ClassFlags,
-    types::Zval,
-    zend::{ce, ClassEntry},
+    types::{ZendClassObject, ZendLong, ZendObject, Zval},
+    zend::{bailout_pending, ce, ClassEntry},
 };
 
 /// Result type with the error variant as a [`PhpException`].
@@ -22,12 +38,18 @@ pub type PhpResult<T = ()> = std::result::Result<T, PhpException>;
 /// There are default [`From`] implementations for any type that implements
 /// [`ToString`], so these can also be returned from these functions. You can
 /// also implement [`From<T>`] for your custom error type.
+///
+/// An exception can carry a previous exception, which becomes the
+/// `getPrevious()` of the thrown PHP exception. [`PhpException::from_error`]
+/// maps the [`source`](std::error::Error::source) chain of a Rust error to
+/// previous exceptions.
 #[derive(Debug)]
 pub struct PhpException {
     message: String,
     code: i32,
     ex: &'static ClassEntry,
     object: Option<Zval>,
+    previous: Option<Box<PhpException>>,
 }
 
 impl PhpException {
@@ -44,6 +66,7 @@ impl PhpException {
             code,
             ex,
             object: None,
+            previous: None,
         }
     }
 
@@ -67,6 +90,74 @@ impl PhpException {
         Self::new(message, 0, T::get_metadata().ce())
     }
 
+    /// Creates an instance of an exception from an instance of a PHP class
+    /// type, which must extend `Exception`. The message and code are written
+    /// to the `message` and `code` properties of the object.
+    ///
+    /// This allows the exception to carry extra properties, declared on the
+    /// Rust type with the `#[prop]` attribute.
+    ///
+    /// # Parameters
+    ///
+    /// * `message` - Message to contain in the exception.
+    /// * `code` - Integer code to go inside the exception.
+    /// * `object` - The object to throw.
+    pub fn from_object<T: RegisteredClass>(message: String, code: i32, object: T) -> Self {
+        let ex = T::get_metadata().ce();
+        let mut obj = ZendClassObject::new(object);
+
+        // The properties of `Exception` are protected, so they are written from the
+        // scope of the exception class.
+        let _ = obj.std.update_property(ex, "message", message.as_str());
+        let _ = obj.std.update_property(ex, "code", code);
+
+        Self {
+            message,
+            code,
+            ex,
+            object: obj.into_zval(false).ok(),
+            previous: None,
+        }
+    }
+
+    /// Creates a default exception from a Rust error. Each error in the
+    /// [`source`](std::error::Error::source) chain of the error becomes the
+    /// previous exception of the one before it, so the root cause is available
+    /// in PHP through `getPrevious()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `err` - The error to create the exception from.
+    pub fn from_error(err: &(dyn ErrorTrait + 'static)) -> Self {
+        let ex = Self::default(err.to_string());
+        match err.source() {
+            Some(source) => ex.with_previous(Self::from_error(source)),
+            None => ex,
+        }
+    }
+
+    /// Sets the previous exception of the exception, replacing any previous
+    /// exception already set. The previous exception is thrown along with the
+    /// exception, and returned by `getPrevious()` in PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous` - The exception which caused this exception.
+    pub fn with_previous(mut self, previous: impl Into<PhpException>) -> Self {
+        self.previous = Some(Box::new(previous.into()));
+        self
+    }
+
+    /// Returns the previous exception of the exception, if any.
+    pub fn previous(&self) -> Option<&PhpException> {
+        self.previous.as_deref()
+    }
+
+    /// Returns the message of the exception.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
     /// Set the Zval object for the exception.
     ///
     /// Exceptions can be based of instantiated Zval objects when you are
@@ -81,7 +172,23 @@ impl PhpException {
 
     /// Throws the exception, returning nothing inside a result if successful
     /// and an error otherwise.
+    ///
+    /// The chain of previous exceptions is thrown first, starting from the
+    /// root cause. PHP sets the exception that is currently being thrown as
+    /// the previous exception of the next one thrown.
+    ///
+    /// Returns [`Error::Bailout`] without throwing while a bailout is pending,
+    /// as PHP is unwinding from it and exceptions can't be thrown until it has
+    /// been resumed.
     pub fn throw(self) -> Result<()> {
+        if bailout_pending() {
+            return Err(Error::Bailout);
+        }
+
+        if let Some(previous) = self.previous {
+            previous.throw()?;
+        }
+
         match self.object {
             Some(object) => throw_object(object),
             None => throw_with_code(self.ex, self.code, &self.message),
@@ -104,7 +211,221 @@ impl From<&str> for PhpException {
 #[cfg(feature = "anyhow")]
 impl From<anyhow::Error> for PhpException {
     fn from(err: anyhow::Error) -> Self {
-        Self::new(format!("{:#}", err), 0, crate::zend::ce::exception())
+        // Each context of the error is thrown as a separate exception.
+        Self::from_error(err.as_ref())
+    }
+}
+
+/// An exception thrown in PHP, read from a `Throwable` object. Returned by
+/// [`Error::exception`] to inspect exceptions caught from PHP code called by
+/// Rust, including the chain of previous exceptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhpThrowable {
+    /// Name of the class of the exception.
+    pub class_name: String,
+    /// Message of the exception.
+    pub message: String,
+    /// Code of the exception.
+    pub code: ZendLong,
+    /// File the exception was created in.
+    pub file: String,
+    /// Line the exception was created on.
+    pub line: ZendLong,
+    /// The previous exception, returned by `getPrevious()`.
+    pub previous: Option<Box<PhpThrowable>>,
+}
+
+impl PhpThrowable {
+    /// Reads an exception from a PHP object. Returns [`None`] if the object
+    /// does not implement `Throwable`.
+    ///
+    /// The properties of the exception are read directly, like PHP does when
+    /// reporting an uncaught exception, so that no PHP code is run.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The exception object.
+    pub fn from_object(obj: &ZendObject) -> Option<Self> {
+        if !obj.instance_of(ce::throwable()) {
+            return None;
+        }
+
+        // The properties are declared by `Exception` and `Error`, and are only
+        // accessible from their scope.
+        let scope = if obj.instance_of(ce::exception()) {
+            ce::exception()
+        } else {
+            ce::error()
+        };
+        let read = |name: &str| {
+            let mut rv = Zval::new();
+            // SAFETY: The object is a valid exception object, and reading its
+            // declared properties does not call into PHP code.
+            let zv = unsafe {
+                zend_read_property(
+                    scope as *const _ as *mut _,
+                    obj as *const _ as *mut _,
+                    name.as_ptr().cast(),
+                    name.len(),
+                    true,
+                    &mut rv,
+                )
+                .as_ref()
+            }?;
+            Some(zv.dereference().shallow_clone())
+        };
+        let previous = read("previous").and_then(|prev| {
+            let prev = Self::from_object(prev.object()?)?;
+            Some(Box::new(prev))
+        });
+
+        Some(Self {
+            class_name: obj.get_class_name().ok()?,
+            message: read("message")
+                .and_then(|zv| zv.string())
+                .unwrap_or_default(),
+            code: read("code").and_then(|zv| zv.long()).unwrap_or_default(),
+            file: read("file").and_then(|zv| zv.string()).unwrap_or_default(),
+            line: read("line").and_then(|zv| zv.long()).unwrap_or_default(),
+            previous,
+        })
+    }
+
+    /// Returns an iterator over the exception and its previous exceptions,
+    /// ending with the root cause.
+    pub fn chain(&self) -> impl Iterator<Item = &PhpThrowable> {
+        std::iter::successors(Some(self), |ex| ex.previous.as_deref())
+    }
+}
+
+impl Display for PhpThrowable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.class_name, self.message)
+    }
+}
+
+impl ErrorTrait for PhpThrowable {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        self.previous.as_deref().map(|ex| ex as _)
+    }
+}
+
+/// Implemented on Rust error types which are thrown as a hierarchy of PHP
+/// exception classes, usually through the [`PhpException`](crate::PhpException)
+/// derive macro.
+///
+/// The exception classes are added to a module with
+/// [`ModuleBuilder::exception`].
+pub trait RegisteredException {
+    /// Adds the exception classes of the type to the given module.
+    ///
+    /// # Parameters
+    ///
+    /// * `module` - The module to add the classes to.
+    fn register_classes(module: ModuleBuilder) -> ModuleBuilder;
+}
+
+/// Class of the exception thrown when a panic is caught, `Error` when null.
+static PANIC_EXCEPTION: AtomicPtr<ClassEntry> = AtomicPtr::new(ptr::null_mut());
+
+/// Whether a backtrace is added to the message of caught panics.
+static PANIC_BACKTRACE: AtomicBool = AtomicBool::new(false);
+
+/// Installs the panic hook capturing backtraces.
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    /// Backtrace of the last panic on the thread, captured by the panic hook.
+    static LAST_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+/// Sets the class of the exception thrown in place of a Rust panic caught by
+/// [`catch_panic`]. Defaults to `Error`.
+///
+/// # Parameters
+///
+/// * `ex` - The exception class, which must implement `Throwable`.
+pub fn set_panic_exception(ex: &'static ClassEntry) {
+    PANIC_EXCEPTION.store(ex as *const _ as *mut _, Ordering::Relaxed);
+}
+
+/// Sets whether the Rust backtrace of a panic caught by [`catch_panic`] is
+/// added to the message of the exception. Disabled by default.
+///
+/// Enabling backtraces installs a panic hook, which captures the backtrace
+/// before calling the previously installed hook.
+///
+/// # Parameters
+///
+/// * `enabled` - Whether to add backtraces to the exception message.
+pub fn set_panic_backtrace(enabled: bool) {
+    PANIC_BACKTRACE.store(enabled, Ordering::Relaxed);
+
+    if enabled {
+        PANIC_HOOK.call_once(|| {
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if PANIC_BACKTRACE.load(Ordering::Relaxed) {
+                    let backtrace = Backtrace::force_capture();
+                    LAST_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+                }
+                hook(info)
+            }));
+        });
+    }
+}
+
+/// Calls the given function, catching any panic and converting it into an
+/// exception rather than unwinding into PHP. Used by the function, method and
+/// object handlers of the library.
+///
+/// The exception is of the class given to [`set_panic_exception`], with the
+/// panic message as its message.
+///
+/// # Parameters
+///
+/// * `f` - The function to call.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ext_php_rs::exception::catch_panic;
+///
+/// let result = catch_panic(|| {
+///     let items: Vec<i32> = vec![];
+///     items[1]
+/// });
+/// assert!(result.is_err());
+/// ```
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> PhpResult<R> {
+    reset_panic_backtrace();
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let mut message = format!("Rust panic: {}", panic_message(&*payload));
+        if let Some(backtrace) = LAST_BACKTRACE.with(|last| last.borrow_mut().take()) {
+            message.push_str(&format!("\n\nRust backtrace:\n{backtrace}"));
+        }
+
+        // SAFETY: The pointer is either null or was set from a static reference.
+        let ex = unsafe { PANIC_EXCEPTION.load(Ordering::Relaxed).as_ref() };
+        PhpException::new(message, 0, ex.unwrap_or_else(ce::error))
+    })
+}
+
+/// Discards the backtrace of a previous panic on the thread, which was caught
+/// without being read. Called before catching panics, so that the backtrace
+/// read once a panic is caught is the backtrace of that panic.
+pub(crate) fn reset_panic_backtrace() {
+    LAST_BACKTRACE.with(|last| last.borrow_mut().take());
+}
+
+/// Returns the message of a panic from its payload.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
     }
 }
 
@@ -199,7 +520,7 @@ pub fn throw_with_code(ex: &ClassEntry, code: i32, message: &str) -> Result<()>
 ///
 /// #[php_module]
 /// pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
-///     module
+///     module.class::<JsException>()
 /// }
 ///
 /// let error = JsException { message: "A JS error occurred.".to_string(), code: 100, file: "index.js".to_string() };
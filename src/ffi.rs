@@ -21,10 +21,26 @@ extern "C" {
     pub fn ext_php_rs_zend_string_release(zs: *mut zend_string);
     pub fn ext_php_rs_is_known_valid_utf8(zs: *const zend_string) -> bool;
     pub fn ext_php_rs_set_known_valid_utf8(zs: *mut zend_string);
+    pub fn ext_php_rs_zend_string_separate(zs: *mut zend_string) -> *mut zend_string;
+    pub fn ext_php_rs_zend_array_is_list(arr: *mut zend_array) -> bool;
 
     pub fn ext_php_rs_php_build_id() -> *const c_char;
     pub fn ext_php_rs_zend_object_alloc(obj_size: usize, ce: *mut zend_class_entry) -> *mut c_void;
     pub fn ext_php_rs_zend_object_release(obj: *mut zend_object);
+    pub fn ext_php_rs_zend_object_store_ctor_failed(obj: *mut zend_object);
+    pub fn ext_php_rs_zval_make_ref(zv: *mut zval);
+    pub fn ext_php_rs_zval_unwrap_ref(zv: *mut zval);
+    pub fn ext_php_rs_zend_get_class_constant(
+        ce: *mut zend_class_entry,
+        name: *mut zend_string,
+    ) -> *mut zval;
+    pub fn ext_php_rs_zend_function_deprecate(
+        ce: *mut zend_class_entry,
+        name: *const c_char,
+        name_len: usize,
+        message: *const c_char,
+        len: usize,
+    );
     pub fn ext_php_rs_executor_globals() -> *mut zend_executor_globals;
     pub fn ext_php_rs_process_globals() -> *mut php_core_globals;
     pub fn ext_php_rs_sapi_globals() -> *mut sapi_globals_struct;
@@ -93,6 +93,8 @@ bitflags! {
 
         #[cfg(php81)]
         const NotSerializable = crate::ffi::ZEND_ACC_NOT_SERIALIZABLE;
+        #[cfg(php81)]
+        const Enum = crate::ffi::ZEND_ACC_ENUM;
     }
 }
 
@@ -0,0 +1,105 @@
+//! Traits used by the [`php_class`] and [`php_impl`] macros to join the
+//! contents of a class, which are spread across the two macros.
+//!
+//! The [`php_class`] macro implements [`RegisteredClass`] by calling the
+//! methods of [`PhpClassImpl`] on a [`PhpClassImplCollector`]. The
+//! [`php_impl`] macro implements [`PhpClassImpl`] on the collector of the
+//! class, which takes precedence over the empty implementation on a
+//! reference to the collector when the methods are called on a value.
+//!
+//! [`php_class`]: crate::php_class
+//! [`php_impl`]: crate::php_impl
+
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::{
+    builders::FunctionBuilder,
+    class::{ConstructorMeta, RegisteredClass},
+    convert::IntoZvalDyn,
+    describe::{Constant, Method, Property as PropertyDescription},
+    flags::MethodFlags,
+    props::Property,
+};
+
+/// Collects the contents of the `impl` block of a class. See the [module
+/// level documentation](self).
+pub struct PhpClassImplCollector<T: RegisteredClass>(PhantomData<T>);
+
+impl<T: RegisteredClass> Default for PhpClassImplCollector<T> {
+    #[inline]
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Implemented by the [`php_impl`](crate::php_impl) macro on the
+/// [`PhpClassImplCollector`] of a class.
+pub trait PhpClassImpl<T: RegisteredClass> {
+    /// Returns the methods of the class, along with their flags.
+    fn get_methods(self) -> Vec<(FunctionBuilder<'static>, MethodFlags)>;
+
+    /// Returns the properties of the class backed by getters and setters.
+    fn get_method_props<'a>(self) -> HashMap<&'static str, Property<'a, T>>;
+
+    /// Returns the constructor of the class, if any.
+    fn get_constructor(self) -> Option<ConstructorMeta<T>>;
+
+    /// Returns the constants of the class.
+    fn get_constants(self) -> &'static [(&'static str, &'static dyn IntoZvalDyn)];
+
+    /// Returns the names of the deprecated constants of the class.
+    fn get_deprecated_constants(self) -> &'static [&'static str];
+
+    /// Describes the methods of the class, including the constructor.
+    fn describe_methods(self) -> Vec<Method>;
+
+    /// Describes the properties of the class backed by getters and setters.
+    fn describe_method_props(self) -> Vec<PropertyDescription>;
+
+    /// Describes the constants of the class.
+    fn describe_constants(self) -> Vec<Constant>;
+}
+
+/// Used when a class has no `impl` block annotated with the
+/// [`php_impl`](crate::php_impl) macro.
+impl<T: RegisteredClass> PhpClassImpl<T> for &'_ PhpClassImplCollector<T> {
+    #[inline]
+    fn get_methods(self) -> Vec<(FunctionBuilder<'static>, MethodFlags)> {
+        Vec::new()
+    }
+
+    #[inline]
+    fn get_method_props<'a>(self) -> HashMap<&'static str, Property<'a, T>> {
+        HashMap::new()
+    }
+
+    #[inline]
+    fn get_constructor(self) -> Option<ConstructorMeta<T>> {
+        None
+    }
+
+    #[inline]
+    fn get_constants(self) -> &'static [(&'static str, &'static dyn IntoZvalDyn)] {
+        &[]
+    }
+
+    #[inline]
+    fn get_deprecated_constants(self) -> &'static [&'static str] {
+        &[]
+    }
+
+    #[inline]
+    fn describe_methods(self) -> Vec<Method> {
+        Vec::new()
+    }
+
+    #[inline]
+    fn describe_method_props(self) -> Vec<PropertyDescription> {
+        Vec::new()
+    }
+
+    #[inline]
+    fn describe_constants(self) -> Vec<Constant> {
+        Vec::new()
+    }
+}
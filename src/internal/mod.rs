@@ -0,0 +1,94 @@
+//! Internal, public functions that are called from downstream extensions.
+
+use std::{ffi::CString, fmt::Display};
+
+use crate::{
+    exception::catch_panic,
+    ffi::{zend_error, E_CORE_WARNING, ZEND_RESULT_CODE_FAILURE, ZEND_RESULT_CODE_SUCCESS},
+    zend::{handler_scope, resume_bailout, ExecuteData, FORMAT_STR},
+};
+
+pub mod class;
+
+/// Runs the body of a handler called by PHP. A panic in the body is thrown as
+/// an exception, and a bailout caught while the body called into PHP is
+/// resumed once the body has returned and dropped its values.
+#[inline(always)]
+pub fn run_handler(body: impl FnOnce()) {
+    handler_scope(|| {
+        if let Err(e) = catch_panic(body) {
+            let _ = e.throw();
+        }
+    });
+
+    // SAFETY: The body has returned, so there are no values left to drop before
+    // returning to PHP.
+    unsafe { resume_bailout() };
+}
+
+/// Emits the deprecation notice of a deprecated function or method from its
+/// handler, before PHP 8.4. Later versions flag the function as deprecated
+/// and emit the notice themselves, including the message.
+///
+/// Returns `true` if the notice was turned into an exception, in which case
+/// the handler must return without calling the function.
+#[inline(always)]
+pub fn deprecated(ex: &ExecuteData, message: &str) -> bool {
+    #[cfg(not(php84))]
+    {
+        ex.trigger_deprecation(message);
+        crate::zend::ExecutorGlobals::has_exception()
+    }
+    #[cfg(php84)]
+    {
+        let _ = (ex, message);
+        false
+    }
+}
+
+/// Return type of a startup function defined with the [`php_startup`] macro,
+/// either `()` or a [`Result`] whose error is displayed when the extension
+/// fails to start.
+///
+/// [`php_startup`]: crate::php_startup
+pub trait StartupResult {
+    /// Returns the error message if the startup function failed.
+    fn error(self) -> Option<String>;
+}
+
+impl StartupResult for () {
+    fn error(self) -> Option<String> {
+        None
+    }
+}
+
+impl<E: Display> StartupResult for Result<(), E> {
+    fn error(self) -> Option<String> {
+        self.err().map(|e| e.to_string())
+    }
+}
+
+/// Runs the body of a startup function defined with the [`php_startup`]
+/// macro.
+///
+/// Returns `SUCCESS`, or `FAILURE` after raising an `E_CORE_WARNING` with the
+/// error if the body failed.
+///
+/// [`php_startup`]: crate::php_startup
+pub fn run_startup<R: StartupResult>(body: impl FnOnce() -> R) -> i32 {
+    match body().error() {
+        None => ZEND_RESULT_CODE_SUCCESS,
+        Some(e) => {
+            startup_warning(e);
+            ZEND_RESULT_CODE_FAILURE
+        }
+    }
+}
+
+/// Raises an `E_CORE_WARNING` with an error preventing the extension from
+/// starting up.
+pub(crate) fn startup_warning(error: impl Display) {
+    if let Ok(e) = CString::new(error.to_string()) {
+        unsafe { zend_error(E_CORE_WARNING as _, FORMAT_STR.as_ptr().cast(), e.as_ptr()) };
+    }
+}
@@ -31,6 +31,9 @@ pub mod embed;
 pub mod internal;
 pub mod props;
 pub mod rc;
+#[cfg(feature = "serde")]
+#[cfg_attr(docs, doc(cfg(feature = "serde")))]
+pub mod serde;
 pub mod types;
 pub mod zend;
 
@@ -53,6 +56,9 @@ pub mod prelude {
     pub use crate::php_println;
     pub use crate::php_startup;
     pub use crate::types::ZendCallable;
+    pub use crate::wrap_constant;
+    pub use crate::wrap_function;
+    pub use crate::PhpException;
     pub use crate::ZvalConvert;
 }
 
@@ -67,13 +73,10 @@ pub const PHP_ZTS: bool = cfg!(php_zts);
 
 /// Attribute used to annotate constants to be exported to PHP.
 ///
-/// The declared constant is left intact (apart from the addition of the
-/// `#[allow(dead_code)]` attribute in the case that you do not use the Rust
-/// constant).
+/// The declared constant is left intact.
 ///
-/// These declarations must happen before you declare your [`macro@php_startup`]
-/// function (or [`macro@php_module`] function if you do not have a startup
-/// function).
+/// The constant must be added to the module with the [`wrap_constant!`] macro
+/// to be registered.
 ///
 /// # Example
 ///
@@ -85,10 +88,13 @@ pub const PHP_ZTS: bool = cfg!(php_zts);
 ///
 /// #[php_const]
 /// const ANOTHER_CONST: &str = "Hello, world!";
-/// # #[php_module]
-/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-/// #     module
-/// # }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+///         .constant(wrap_constant!(TEST_CONSTANT))
+///         .constant(wrap_constant!(ANOTHER_CONST))
+/// }
 /// ```
 pub use ext_php_rs_derive::php_const;
 
@@ -190,48 +196,46 @@ pub use ext_php_rs_derive::php_extern;
 ///
 /// ```no_run
 /// # #![cfg_attr(windows, feature(abi_vectorcall))]
-/// # use ext_php_rs::{prelude::*, exception::PhpException, zend::ExecuteData, convert::{FromZvalMut, IntoZval}, types::Zval, args::{Arg, ArgParser}};
+/// # use ext_php_rs::{prelude::*, exception::PhpException, zend::ExecuteData, convert::IntoZval, types::Zval};
 /// pub fn hello(name: String) -> String {
 ///     format!("Hello, {}!", name)
 /// }
 ///
 /// pub extern "C" fn _internal_php_hello(ex: &mut ExecuteData, retval: &mut Zval) {
-///     let mut name = Arg::new("name", <String as FromZvalMut>::TYPE);
-///     let parser = ex.parser()
-///         .arg(&mut name)
-///         .parse();
+///     let mut parser = ex.fast_parser();
 ///
-///     if parser.is_err() {
+///     if parser.check_count(1, 1).is_err() {
 ///         return;
 ///     }
 ///
-///     let result = hello(match name.val() {
-///         Some(val) => val,
-///         None => {
-///             PhpException::default("Invalid value given for argument `name`.".into())
-///                 .throw()
-///                 .expect("Failed to throw exception: Invalid value given for argument `name`.");
-///             return;
-///         }
+///     // Throws a `TypeError` if the argument cannot be converted.
+///     let result = hello(match parser.parse_next(false) {
+///         Ok(Some(val)) => val,
+///         _ => return,
 ///     });
 ///
 ///     match result.set_zval(retval, false) {
 ///         Ok(_) => {},
 ///         Err(e) => {
 ///             let e: PhpException = e.into();
-///             e.throw().expect("Failed to throw exception: Failed to set return value.");
+///             let _ = e.throw();
 ///         }
 ///     };
 /// }
 /// ```
 ///
+/// The arguments are read in place from the call frame, without allocating.
+/// Functions taking variadic arguments instead collect their arguments with an
+/// [`ArgParser`](crate::args::ArgParser).
+///
 /// This allows the original function to continue being used while also being
 /// exported as a PHP function.
 ///
 /// # Examples
 ///
 /// Creating a simple function which will return a string. The function still
-/// must be declared in the PHP module to be able to call.
+/// must be added to the PHP module with the [`wrap_function!`] macro to be able
+/// to call.
 ///
 /// ```
 /// # #![cfg_attr(windows, feature(abi_vectorcall))]
@@ -240,10 +244,11 @@ pub use ext_php_rs_derive::php_extern;
 /// pub fn hello(name: String) -> String {
 ///     format!("Hello, {}!", name)
 /// }
-/// # #[php_module]
-/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-/// #     module
-/// # }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module.function(wrap_function!(hello))
+/// }
 /// ```
 ///
 /// Parameters can also be deemed optional by passing the parameter name in the
@@ -332,6 +337,10 @@ pub use ext_php_rs_derive::php_function;
 ///   the method,
 ///   defaulting to public. The Rust visibility has no effect on the PHP
 ///   visibility.
+/// - `#[deprecated]` or `#[deprecated = "message"]` for deprecating a method,
+///   which emits an `E_DEPRECATED` notice when it is called. On constants, the
+///   attribute only adds a `@deprecated` tag to the stubs before PHP 8.4, which
+///   introduced deprecated class constants.
 ///
 /// Methods can take a immutable or a mutable reference to `self`, but cannot
 /// consume `self`. They can also take no reference to `self` which indicates a
@@ -385,7 +394,7 @@ pub use ext_php_rs_derive::php_function;
 ///
 /// #[php_module]
 /// pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
-///     module
+///     module.class::<Human>()
 /// }
 /// ```
 pub use ext_php_rs_derive::php_impl;
@@ -397,26 +406,30 @@ pub use ext_php_rs_derive::php_impl;
 /// called from PHP, which then calls the given function.
 ///
 /// As well as wrapping the function, the `ModuleBuilder` is initialized and
-/// functions which have already been declared with the [`macro@php_function`]
-/// attribute will be registered with the module, so ideally you won't have to
-/// do anything inside the function.
+/// passed to the function. The functions, classes and constants of the
+/// extension are added to the module through the builder:
 ///
-/// The attribute must be called on a function *last*, i.e. the last proc-macro
-/// to be compiled, as the attribute relies on all other PHP attributes being
-/// compiled before the module. If another PHP attribute is compiled after the
-/// module attribute, an error will be thrown.
+/// - Functions declared with [`macro@php_function`] are added with
+///   [`ModuleBuilder::function`] and the [`wrap_function!`] macro.
+/// - Classes declared with [`macro@php_class`] are added with
+///   [`ModuleBuilder::class`].
+/// - Constants declared with [`macro@php_const`] are added with
+///   [`ModuleBuilder::constant`] and the [`wrap_constant!`] macro.
+///
+/// As items are added explicitly, they may be declared anywhere, including in
+/// other modules and other crates.
 ///
 /// Note that if the function is not called `get_module`, it will be renamed.
 ///
-/// If you have defined classes using the [`macro@php_class`] macro and you have
-/// not defined a startup function, it will be automatically declared and
-/// registered.
+/// The attribute takes an optional `namespace` option, which sets the default
+/// namespace of the functions and constants added to the module, e.g.
+/// `#[php_module(namespace = "Acme\\Util")]`. Functions and constants with
+/// their own `namespace` option are not affected.
 ///
 /// # Example
 ///
 /// The `get_module` function is required in every PHP extension. This is a bare
-/// minimum example, since the function is declared above the module it will
-/// automatically be registered when the module attribute is called.
+/// minimum example, registering a single function.
 ///
 /// ```
 /// # #![cfg_attr(windows, feature(abi_vectorcall))]
@@ -428,9 +441,13 @@ pub use ext_php_rs_derive::php_impl;
 ///
 /// #[php_module]
 /// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-///     module
+///     module.function(wrap_function!(hello))
 /// }
 /// ```
+///
+/// [`ModuleBuilder::function`]: crate::builders::ModuleBuilder::function
+/// [`ModuleBuilder::class`]: crate::builders::ModuleBuilder::class
+/// [`ModuleBuilder::constant`]: crate::builders::ModuleBuilder::constant
 pub use ext_php_rs_derive::php_module;
 
 /// Annotates a struct that will be exported to PHP as a class.
@@ -444,6 +461,9 @@ pub use ext_php_rs_derive::php_module;
 /// * `name` - The name of the exported class, if it is different from the Rust
 ///   struct name. This can be useful for namespaced classes, as you cannot
 ///   place backslashes in Rust struct names.
+/// * `deprecated` or `deprecated = "message"` - Adds a `@deprecated` tag to
+///   the generated stubs. PHP cannot deprecate classes, so no notice is emitted
+///   when the class is used.
 ///
 /// Any struct that uses this attribute can also provide an optional set of
 /// extra attributes, used to modify the class. These attributes must be used
@@ -455,9 +475,9 @@ pub use ext_php_rs_derive::php_module;
 /// * `#[implements(ce)]` - Implements an interface on the new class. Can be
 ///   used multiple times, and `ce` may be any valid expression.
 ///
-/// This attribute (and its associated structs) must be defined *above* the
-/// startup function (which is annotated by the [`macro@php_startup`] macro, or
-/// automatically generated just above the [`macro@php_module`] function).
+/// The class must be added to the module with [`ModuleBuilder::class`] to be
+/// registered. As the class is registered through its type, it may be declared
+/// in another module or crate than the [`macro@php_module`] function.
 ///
 /// Fields defined on the struct *are not* the same as PHP properties, and are
 /// only accessible from Rust.
@@ -478,7 +498,7 @@ pub use ext_php_rs_derive::php_module;
 ///
 /// #[php_module]
 /// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-///     module
+///     module.class::<Example>()
 /// }
 /// ```
 ///
@@ -503,28 +523,27 @@ pub use ext_php_rs_derive::php_module;
 /// #[php_module]
 /// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
 ///     module
+///         .class::<Example>()
+///         .function(wrap_function!(throw_exception))
 /// }
 /// ```
+///
+/// [`ModuleBuilder::class`]: crate::builders::ModuleBuilder::class
 pub use ext_php_rs_derive::php_class;
 
 /// Annotates a function that will be called by PHP when the module starts up.
-/// Generally used to register classes and constants.
-///
-/// As well as annotating the function, any classes and constants that had been
-/// declared using the [`macro@php_class`], [`macro@php_const`] and
-/// [`macro@php_impl`] attributes will be registered inside this function.
+/// Generally used to register INI settings or resources that are not declared
+/// with the other attributes.
 ///
-/// This function *must* be declared before the [`macro@php_module`] function,
-/// as this function needs to be declared when building the module.
+/// The function is wrapped in an `extern "C"` function and turned into a
+/// [`StartupFunction`] constant, which is set as the startup function of the
+/// module with [`ModuleBuilder::startup_function`]. It is called after the
+/// classes, constants and resource types added to the module have been
+/// registered, or before when given the `before` option, i.e.
+/// `#[php_startup(before)]`.
 ///
-/// This function will automatically be generated if not already declared with
-/// this macro if you have registered any classes or constants when using the
-/// [`macro@php_module`] macro.
-///
-/// The attribute accepts one optional flag -- `#[php_startup(before)]` --
-/// which forces the annotated function to be called _before_ the other classes
-/// and constants are registered. By default the annotated function is called
-/// after these classes and constants are registered.
+/// The function may return a [`Result`], in which case an error makes the
+/// extension fail to start, displaying the error as a startup warning.
 ///
 /// # Example
 ///
@@ -532,14 +551,19 @@ pub use ext_php_rs_derive::php_class;
 /// # #![cfg_attr(windows, feature(abi_vectorcall))]
 /// # use ext_php_rs::prelude::*;
 /// #[php_startup]
-/// pub fn startup_function() {
+/// pub fn startup_function() -> Result<(), String> {
 ///     // do whatever you need to do...
+///     Ok(())
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module.startup_function(startup_function)
 /// }
-/// # #[php_module]
-/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-/// #     module
-/// # }
 /// ```
+///
+/// [`StartupFunction`]: crate::builders::StartupFunction
+/// [`ModuleBuilder::startup_function`]: crate::builders::ModuleBuilder::startup_function
 pub use ext_php_rs_derive::php_startup;
 
 /// Derives the traits required to convert a struct or enum to and from a
@@ -681,6 +705,89 @@ pub use ext_php_rs_derive::php_startup;
 /// [`Zval::string`]: crate::types::Zval.::string
 pub use ext_php_rs_derive::ZvalConvert;
 
+/// Derives a hierarchy of PHP exception classes from a Rust error enum, along
+/// with a [`From`] implementation converting the enum into a
+/// [`PhpException`](crate::exception::PhpException). Returning the error from
+/// a [`macro@php_function`] or method, e.g. with the `?` operator, throws the
+/// exception.
+///
+/// The enum must implement [`Display`](std::fmt::Display), which is used as
+/// the exception message.
+///
+/// A base class is generated for the enum, extending `Exception` by default.
+/// Each variant then generates a subclass of the base class, named after the
+/// variant suffixed with `Exception`. The named fields of the variant become
+/// properties of the subclass, and must implement [`IntoZval`], [`FromZval`]
+/// and [`Clone`]. Positional fields have no property name, and must be
+/// skipped or marked as the source.
+///
+/// The classes must be added to the module with [`ModuleBuilder::exception`].
+///
+/// # Options
+///
+/// The enum takes the following options in a `#[php(...)]` attribute:
+///
+/// * `name` - The name of the base class, defaults to the name of the enum.
+/// * `namespace` - The namespace the classes are placed in.
+/// * `extends` - The parent of the base class, e.g. `ce::error_exception()`.
+///
+/// Each variant takes the following options:
+///
+/// * `name` - The name of the class of the variant, within the namespace.
+/// * `code` - The exception code, defaults to zero.
+/// * `ce` - An existing class to throw instead of generating a subclass, e.g.
+///   `ce::value_error()`.
+///
+/// Fields marked with `#[php(skip)]` are not exposed as properties. A field
+/// marked with `#[php(source)]` must implement [`std::error::Error`], and is
+/// thrown as the previous exception of the variant, along with its own source
+/// chain.
+///
+/// # Example
+///
+/// ```
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// use std::fmt;
+///
+/// #[derive(Debug, PhpException)]
+/// #[php(namespace = "Acme", name = "AcmeException")]
+/// pub enum AcmeError {
+///     /// Thrown as `Acme\NotFoundException`, with a `path` property.
+///     #[php(code = 404)]
+///     NotFound { path: String },
+///     /// Thrown as the built-in `ValueError`.
+///     #[php(ce = "ext_php_rs::zend::ce::value_error()")]
+///     Invalid(String),
+/// }
+///
+/// impl fmt::Display for AcmeError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         match self {
+///             Self::NotFound { path } => write!(f, "{} was not found", path),
+///             Self::Invalid(reason) => write!(f, "invalid value: {}", reason),
+///         }
+///     }
+/// }
+///
+/// #[php_function]
+/// pub fn find(path: String) -> Result<String, AcmeError> {
+///     Err(AcmeError::NotFound { path })
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+///         .exception::<AcmeError>()
+///         .function(wrap_function!(find))
+/// }
+/// ```
+///
+/// [`IntoZval`]: crate::convert::IntoZval
+/// [`FromZval`]: crate::convert::FromZval
+/// [`ModuleBuilder::exception`]: crate::builders::ModuleBuilder::exception
+pub use ext_php_rs_derive::PhpException;
+
 /// Defines an `extern` function with the Zend fastcall convention based on
 /// operating system.
 ///
@@ -712,3 +819,51 @@ pub use ext_php_rs_derive::ZvalConvert;
 /// The `vectorcall` ABI is currently only supported on Windows with nightly
 /// Rust and the `abi_vectorcall` feature enabled.
 pub use ext_php_rs_derive::zend_fastcall;
+
+/// Returns the [`FunctionBuilder`] of a function declared with the
+/// [`macro@php_function`] attribute, used to add the function to a module.
+///
+/// The function may be given as a path, e.g. `wrap_function!(utils::hello)`,
+/// and can be declared in another module or crate.
+///
+/// # Example
+///
+/// ```
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// #[php_function]
+/// pub fn hello(name: String) -> String {
+///     format!("Hello, {}!", name)
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module.function(wrap_function!(hello))
+/// }
+/// ```
+///
+/// [`FunctionBuilder`]: crate::builders::FunctionBuilder
+pub use ext_php_rs_derive::wrap_function;
+
+/// Returns the [`ConstantBuilder`] of a constant declared with the
+/// [`macro@php_const`] attribute, used to add the constant to a module.
+///
+/// The constant may be given as a path, e.g. `wrap_constant!(utils::MAX_SIZE)`,
+/// and can be declared in another module or crate.
+///
+/// # Example
+///
+/// ```
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// #[php_const]
+/// const MAX_SIZE: i64 = 1024;
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module.constant(wrap_constant!(MAX_SIZE))
+/// }
+/// ```
+///
+/// [`ConstantBuilder`]: crate::builders::ConstantBuilder
+pub use ext_php_rs_derive::wrap_constant;
@@ -211,8 +211,6 @@ macro_rules! throw {
 /// impl RegisteredClass for Test {
 ///     const CLASS_NAME: &'static str = "Test";
 ///
-///     const CONSTRUCTOR: Option<ext_php_rs::class::ConstructorMeta<Self>> = None;
-///
 ///     fn get_metadata() -> &'static ext_php_rs::class::ClassMetadata<Self> {
 ///         todo!()
 ///     }
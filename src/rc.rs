@@ -4,7 +4,7 @@ use std::fmt::Debug;
 
 use crate::{
     ffi::{zend_refcounted_h, zend_string},
-    types::ZendObject,
+    types::{ZendHashTable, ZendObject},
 };
 
 /// Object used to store Zend reference counter.
@@ -58,4 +58,4 @@ macro_rules! rc {
     };
 }
 
-rc!(ZendObject, zend_string);
+rc!(ZendObject, zend_string, ZendHashTable);
@@ -0,0 +1,1006 @@
+//! Conversion between PHP values and Rust types implementing the `serde`
+//! traits.
+//!
+//! Values are serialized as follows:
+//!
+//! | Rust                                    | PHP                               |
+//! | --------------------------------------- | --------------------------------- |
+//! | `bool`                                  | `bool`                            |
+//! | integers                                | `int`                             |
+//! | `f32`, `f64`                            | `float`                           |
+//! | `char`, `str`, bytes                    | `string`                          |
+//! | `None`, `()`, unit structs              | `null`                            |
+//! | sequences, tuples                       | list `array`                      |
+//! | maps                                    | associative `array`               |
+//! | structs                                 | `stdClass` object                 |
+//! | unit variants                           | `string` of the variant name      |
+//! | other variants                          | `array` of the variant name to its value |
+//!
+//! Deserialization accepts the same values, as well as arrays for structs and
+//! objects for maps.
+
+use std::{convert::TryFrom, fmt::Display, ops::Deref};
+
+use ::serde::{
+    de::{
+        self, value::StrDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{self, Impossible},
+    Deserialize, Serialize,
+};
+
+use crate::{
+    boxed::ZBox,
+    convert::{FromZval, IntoZval},
+    error::{Error, Result},
+    flags::DataType,
+    types::{ZendHashTable, ZendLong, ZendObject, Zval},
+};
+
+/// Serializes a value into a zval.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// let map = HashMap::from([("a", 1), ("b", 2)]);
+/// let zval = ext_php_rs::serde::to_zval(&map).unwrap();
+///
+/// assert!(zval.is_array());
+/// ```
+pub fn to_zval<T: Serialize + ?Sized>(value: &T) -> Result<Zval> {
+    value.serialize(Serializer)
+}
+
+/// Deserializes a value from a zval. Strings are borrowed from the zval where
+/// possible.
+pub fn from_zval<'de, T: Deserialize<'de>>(zval: &'de Zval) -> Result<T> {
+    T::deserialize(Deserializer::new(zval))
+}
+
+/// Wrapper to pass a value implementing the `serde` traits to and from PHP.
+///
+/// Can be used as a function parameter or return type. A parameter that cannot
+/// be deserialized throws a `ValueError` giving the reason reported by serde.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use ext_php_rs::{prelude::*, serde::Serde};
+/// use std::collections::BTreeMap;
+///
+/// #[php_function]
+/// pub fn word_lengths(words: Vec<String>) -> Serde<BTreeMap<String, usize>> {
+///     Serde(words.into_iter().map(|w| { let len = w.len(); (w, len) }).collect())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Serde<T>(pub T);
+
+impl<T> Serde<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Serde<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Serialize> IntoZval for Serde<T> {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        *zv = to_zval(&self.0)?;
+        Ok(())
+    }
+}
+
+impl<'a, T: DeserializeOwned> FromZval<'a> for Serde<T> {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        from_zval(zval).ok().map(Serde)
+    }
+
+    fn value_error(zval: &Zval) -> Option<String> {
+        from_zval::<T>(zval)
+            .err()
+            .map(|e| format!("could not be deserialized: {e}"))
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Serde(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Serde(msg.to_string())
+    }
+}
+
+/// Serializer producing zvals.
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Zval;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Zval> {
+        v.into_zval(false)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_long(ZendLong::try_from(v).map_err(|_| Error::IntegerOverflow)?);
+        Ok(zv)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Zval> {
+        self.serialize_i64(i64::try_from(v).map_err(|_| Error::IntegerOverflow)?)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Zval> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Zval> {
+        v.into_zval(false)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Zval> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Zval> {
+        v.into_zval(false)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_binary(v.to_vec());
+        Ok(zv)
+    }
+
+    fn serialize_none(self) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Zval> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Zval> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Zval> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Zval> {
+        variant_zval(variant, value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray> {
+        Ok(SerializeArray {
+            ht: new_array(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant> {
+        Ok(SerializeTupleVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap> {
+        Ok(SerializeMap {
+            ht: new_array(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<SerializeObject> {
+        Ok(SerializeObject {
+            obj: ZendObject::new_stdclass(),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant> {
+        Ok(SerializeStructVariant {
+            variant,
+            inner: self.serialize_struct(name, len)?,
+        })
+    }
+}
+
+fn new_array(len: usize) -> ZBox<ZendHashTable> {
+    ZendHashTable::with_capacity(u32::try_from(len).unwrap_or(u32::MAX))
+}
+
+/// Wraps the value of an enum variant in an array keyed by the variant name.
+fn variant_zval(variant: &str, value: Zval) -> Result<Zval> {
+    let mut ht = ZendHashTable::with_capacity(1);
+    ht.insert_key(ArrayKey::String(variant.into()), value)?;
+    ht.into_zval(false)
+}
+
+/// Serializes sequences and tuples into list arrays.
+pub struct SerializeArray {
+    ht: ZBox<ZendHashTable>,
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.ht.push(value.serialize(Serializer)?)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.ht.into_zval(false)
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes tuple variants into an array holding the list of fields.
+pub struct SerializeTupleVariant {
+    variant: &'static str,
+    inner: SerializeArray,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        variant_zval(self.variant, ser::SerializeSeq::end(self.inner)?)
+    }
+}
+
+/// Serializes maps into associative arrays.
+pub struct SerializeMap {
+    ht: ZBox<ZendHashTable>,
+    key: Option<ArrayKey>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Serde("Map value serialized before its key.".into()))?;
+        self.ht.insert_key(key, value.serialize(Serializer)?)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.ht.into_zval(false)
+    }
+}
+
+/// Serializes structs into `stdClass` objects.
+pub struct SerializeObject {
+    obj: ZBox<ZendObject>,
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.obj.set_property(key, value.serialize(Serializer)?)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.obj.into_zval(false)
+    }
+}
+
+/// Serializes struct variants into an array holding the `stdClass` object of
+/// the fields.
+pub struct SerializeStructVariant {
+    variant: &'static str,
+    inner: SerializeObject,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        variant_zval(self.variant, ser::SerializeStruct::end(self.inner)?)
+    }
+}
+
+/// Serializes map keys into array keys. Strings holding a decimal integer are
+/// converted to integer keys, as PHP does.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn invalid() -> Error {
+        Error::Serde("Array keys must be integers or strings.".into())
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = ArrayKey;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<ArrayKey, Error>;
+    type SerializeTuple = Impossible<ArrayKey, Error>;
+    type SerializeTupleStruct = Impossible<ArrayKey, Error>;
+    type SerializeTupleVariant = Impossible<ArrayKey, Error>;
+    type SerializeMap = Impossible<ArrayKey, Error>;
+    type SerializeStruct = Impossible<ArrayKey, Error>;
+    type SerializeStructVariant = Impossible<ArrayKey, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<ArrayKey> {
+        Ok(ArrayKey::Long(
+            i64::try_from(v).map_err(|_| Error::IntegerOverflow)?,
+        ))
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_char(self, v: char) -> Result<ArrayKey> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<ArrayKey> {
+        Ok(v.into())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_none(self) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<ArrayKey> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<ArrayKey> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<ArrayKey> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<ArrayKey> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(Self::invalid())
+    }
+}
+
+/// The maximum depth of arrays and objects nested in the deserialized value.
+/// Arrays holding a reference to themselves and objects holding themselves as
+/// a property are nested infinitely, and fail to deserialize once the limit
+/// is reached instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Deserializer reading from a zval.
+#[derive(Clone, Copy)]
+pub struct Deserializer<'de> {
+    zval: &'de Zval,
+    depth: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer reading from the given zval, dereferencing it
+    /// if it is a reference.
+    pub fn new(zval: &'de Zval) -> Self {
+        Self::with_depth(zval, 0)
+    }
+
+    fn with_depth(mut zval: &'de Zval, depth: usize) -> Self {
+        while zval.is_reference() || zval.is_indirect() {
+            zval = zval.dereference();
+        }
+        Self { zval, depth }
+    }
+
+    /// Returns a deserializer reading a value held by the current array or
+    /// object.
+    fn nested(&self, zval: &'de Zval) -> Result<Self> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::Serde(format!(
+                "Values nested deeper than {MAX_DEPTH} levels cannot be deserialized."
+            )));
+        }
+        Ok(Self::with_depth(zval, self.depth + 1))
+    }
+
+    fn entries(&self) -> Option<(&'de ZendHashTable, bool)> {
+        match self.zval.array() {
+            Some(ht) => Some((ht, false)),
+            None => Some((self.zval.object()?.get_properties().ok()?, true)),
+        }
+    }
+
+    fn invalid(&self) -> Error {
+        Error::ZvalConversion(self.zval.get_type())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.zval.get_type() {
+            DataType::Undef | DataType::Null => visitor.visit_unit(),
+            DataType::False => visitor.visit_bool(false),
+            DataType::True => visitor.visit_bool(true),
+            DataType::Long => visitor.visit_i64(self.zval.long().unwrap_or_default()),
+            DataType::Double => visitor.visit_f64(self.zval.double().unwrap_or_default()),
+            DataType::String => match self.zval.zend_str() {
+                Some(s) => match std::str::from_utf8(s.as_bytes()) {
+                    Ok(s) => visitor.visit_borrowed_str(s),
+                    Err(_) => visitor.visit_borrowed_bytes(s.as_bytes()),
+                },
+                None => Err(self.invalid()),
+            },
+            DataType::Array => match self.zval.array() {
+                Some(ht) if ht.has_sequential_keys() => visitor.visit_seq(SeqAccess {
+                    parent: self,
+                    values: ht.values(),
+                }),
+                _ => self.deserialize_map(visitor),
+            },
+            DataType::Object(_) => self.deserialize_map(visitor),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.zval.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.zval.zend_str() {
+            Some(s) => visitor.visit_borrowed_bytes(s.as_bytes()),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.zval.array() {
+            Some(ht) => visitor.visit_seq(SeqAccess {
+                parent: self,
+                values: ht.values(),
+            }),
+            None => Err(self.invalid()),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.entries() {
+            Some((ht, properties)) => {
+                // Keys are read as zvals, as string keys may not be valid
+                // UTF-8.
+                let mut entries = ht.iter();
+                visitor.visit_map(MapAccess {
+                    parent: self,
+                    entries: std::iter::from_fn(move || entries.next_zval()),
+                    remaining: ht.len(),
+                    value: None,
+                    properties,
+                })
+            }
+            None => Err(self.invalid()),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if let Some(variant) = self.zval.str() {
+            let variant: StrDeserializer<'_, Error> = variant.into_deserializer();
+            return visitor.visit_enum(variant);
+        }
+
+        let mut entries = self.zval.array().ok_or_else(|| self.invalid())?.iter();
+        match (entries.next_zval(), entries.next_zval()) {
+            (Some((variant, value)), None) => visitor.visit_enum(EnumAccess {
+                variant: KeyDeserializer::new(&variant, false)?,
+                value: self.nested(value)?,
+            }),
+            _ => Err(Error::Serde(
+                "Enum variants must be given as a string or an array with a single key.".into(),
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct identifier
+    }
+}
+
+/// Deserializes the values of an array as a sequence.
+struct SeqAccess<'de, I> {
+    parent: Deserializer<'de>,
+    values: I,
+}
+
+impl<'de, I> de::SeqAccess<'de> for SeqAccess<'de, I>
+where
+    I: ExactSizeIterator<Item = &'de Zval>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.values
+            .next()
+            .map(|value| seed.deserialize(self.parent.nested(value)?))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Deserializes the entries of an array or the properties of an object as a
+/// map.
+struct MapAccess<'de, I> {
+    parent: Deserializer<'de>,
+    entries: I,
+    remaining: usize,
+    value: Option<&'de Zval>,
+    properties: bool,
+}
+
+impl<'de, I> de::MapAccess<'de> for MapAccess<'de, I>
+where
+    I: Iterator<Item = (Zval, &'de Zval)>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let (key, value) = match self.entries.next() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        self.remaining = self.remaining.saturating_sub(1);
+        self.value = Some(value);
+        seed.deserialize(KeyDeserializer::new(&key, self.properties)?)
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::Serde("Map value deserialized before its key.".into()))?;
+        seed.deserialize(self.parent.nested(value)?)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Deserializes enum variants given as an array with a single key.
+struct EnumAccess<'de> {
+    variant: KeyDeserializer,
+    value: Deserializer<'de>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = Error;
+    type Variant = Deserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(self.variant)?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+/// Deserializes array keys. Integer keys are converted to strings where a
+/// string is expected, and string keys which are not valid UTF-8 are given as
+/// bytes.
+enum KeyDeserializer {
+    Long(i64),
+    String(Vec<u8>),
+}
+
+impl KeyDeserializer {
+    /// Creates a deserializer reading the given key. The scope is removed
+    /// from the names of private and protected properties if `property` is
+    /// set.
+    fn new(key: &Zval, property: bool) -> Result<Self> {
+        if let Some(key) = key.long() {
+            return Ok(Self::Long(key));
+        }
+
+        let name = key
+            .zend_str()
+            .ok_or_else(|| Error::ZvalConversion(key.get_type()))?
+            .as_bytes();
+        // The names of private and protected properties are prefixed with
+        // `\0<scope>\0`.
+        let name = match name {
+            [0, ..] if property => name.rsplit(|&b| b == 0).next().unwrap_or_default(),
+            _ => name,
+        };
+        Ok(Self::String(name.to_vec()))
+    }
+
+    fn into_string(self) -> Result<String> {
+        match self {
+            Self::Long(key) => Ok(key.to_string()),
+            Self::String(key) => String::from_utf8(key).map_err(|_| Error::InvalidUtf8),
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Self::Long(key) => visitor.visit_i64(key),
+            Self::String(key) => match String::from_utf8(key) {
+                Ok(key) => visitor.visit_string(key),
+                Err(e) => visitor.visit_byte_buf(e.into_bytes()),
+            },
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Self::Long(key) => visitor.visit_string(key.to_string()),
+            key => de::Deserializer::deserialize_any(key, visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self.into_string()?.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct ignored_any
+    }
+}
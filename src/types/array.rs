@@ -2,27 +2,38 @@
 //! they are represented by hash tables.
 
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    cell::Cell,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     convert::{TryFrom, TryInto},
-    ffi::CString,
+    ffi::{c_void, CString},
     fmt::{Debug, Display},
+    hash::Hash,
     iter::FromIterator,
+    os::raw::{c_char, c_int},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    ptr, slice,
 };
 
 use crate::{
+    args::type_name,
     boxed::{ZBox, ZBoxable},
     convert::{FromZval, IntoZval},
     error::{Error, Result},
+    exception::reset_panic_backtrace,
     ffi::{
-        _zend_new_array, zend_array_count, zend_array_destroy, zend_array_dup, zend_hash_clean,
+        _zend_new_array, ext_php_rs_zend_array_is_list, zend_array_count, zend_array_destroy,
+        zend_array_dup, zend_compare, zend_hash_clean, zend_hash_del_bucket,
         zend_hash_get_current_data_ex, zend_hash_get_current_key_type_ex,
         zend_hash_get_current_key_zval_ex, zend_hash_index_del, zend_hash_index_find,
         zend_hash_index_update, zend_hash_move_backwards_ex, zend_hash_move_forward_ex,
-        zend_hash_next_index_insert, zend_hash_str_del, zend_hash_str_find, zend_hash_str_update,
+        zend_hash_next_index_insert, zend_hash_packed_to_hash, zend_hash_rehash, zend_hash_sort_ex,
+        zend_hash_str_del, zend_hash_str_find, zend_hash_str_update, zend_sort, Bucket,
         HashPosition, HT_MIN_SIZE,
     },
-    flags::DataType,
-    types::Zval,
+    flags::{DataType, ZvalTypeFlags},
+    types::{ZendLong, ZendStr, Zval},
 };
 
 /// A PHP hashtable.
@@ -208,7 +219,7 @@ impl ZendHashTable {
     /// ht.insert("test", "hello world");
     /// assert_eq!(ht.get("test").and_then(|zv| zv.str()), Some("hello world"));
     /// ```
-    pub fn get_mut(&self, key: &'_ str) -> Option<&mut Zval> {
+    pub fn get_mut(&mut self, key: &'_ str) -> Option<&mut Zval> {
         let str = CString::new(key).ok()?;
         unsafe { zend_hash_str_find(self, str.as_ptr(), key.len() as _).as_mut() }
     }
@@ -261,7 +272,7 @@ impl ZendHashTable {
     /// ht.push(100);
     /// assert_eq!(ht.get_index(0).and_then(|zv| zv.long()), Some(100));
     /// ```
-    pub fn get_index_mut(&self, key: u64) -> Option<&mut Zval> {
+    pub fn get_index_mut(&mut self, key: u64) -> Option<&mut Zval> {
         unsafe { zend_hash_index_find(self, key).as_mut() }
     }
 
@@ -273,7 +284,7 @@ impl ZendHashTable {
     ///
     /// # Returns
     ///
-    /// * `Some(())` - Key was successfully removed.
+    /// * `Some(Zval)` - The value removed from the hash table.
     /// * `None` - No key was removed, did not exist.
     ///
     /// # Example
@@ -286,21 +297,17 @@ impl ZendHashTable {
     /// ht.insert("test", "hello world");
     /// assert_eq!(ht.len(), 1);
     ///
-    /// ht.remove("test");
+    /// let value = ht.remove("test");
+    /// assert_eq!(value.and_then(|zv| zv.string()), Some("hello world".into()));
     /// assert_eq!(ht.len(), 0);
     /// ```
-    pub fn remove(&mut self, key: &str) -> Option<()> {
-        let result =
-            unsafe { zend_hash_str_del(self, CString::new(key).ok()?.as_ptr(), key.len() as _) };
-
-        if result < 0 {
-            None
-        } else {
-            Some(())
-        }
+    pub fn remove(&mut self, key: &str) -> Option<Zval> {
+        let val = std::mem::take(self.find_str(key)?);
+        unsafe { zend_hash_str_del(self, key.as_ptr() as *const c_char, key.len() as _) };
+        Some(val)
     }
 
-    /// Attempts to remove a value from the hash table with a string key.
+    /// Attempts to remove a value from the hash table with an index.
     ///
     /// # Parameters
     ///
@@ -308,7 +315,7 @@ impl ZendHashTable {
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - Key was successfully removed.
+    /// * `Some(Zval)` - The value removed from the hash table.
     /// * `None` - No key was removed, did not exist.
     ///
     /// # Example
@@ -324,13 +331,469 @@ impl ZendHashTable {
     /// ht.remove_index(0);
     /// assert_eq!(ht.len(), 0);
     /// ```
-    pub fn remove_index(&mut self, key: u64) -> Option<()> {
-        let result = unsafe { zend_hash_index_del(self, key) };
+    pub fn remove_index(&mut self, key: u64) -> Option<Zval> {
+        let val = std::mem::take(self.find_index(key)?);
+        unsafe { zend_hash_index_del(self, key) };
+        Some(val)
+    }
+
+    /// Attempts to retrieve a value from the hash table with an [`ArrayKey`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to search for in the hash table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ArrayKey, ZendHashTable};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(100);
+    /// assert_eq!(ht.get_key(&ArrayKey::Long(0)).and_then(|zv| zv.long()), Some(100));
+    /// ```
+    pub fn get_key(&self, key: &ArrayKey) -> Option<&Zval> {
+        match key {
+            ArrayKey::Long(key) => self.get_index(*key as u64),
+            ArrayKey::String(key) => unsafe {
+                zend_hash_str_find(self, key.as_ptr() as *const c_char, key.len() as _).as_ref()
+            },
+        }
+    }
+
+    /// Attempts to retrieve a mutable reference to a value from the hash table
+    /// with an [`ArrayKey`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to search for in the hash table.
+    pub fn get_key_mut(&mut self, key: &ArrayKey) -> Option<&mut Zval> {
+        match key {
+            ArrayKey::Long(key) => self.find_index(*key as u64),
+            ArrayKey::String(key) => self.find_str(key),
+        }
+    }
+
+    /// Attempts to remove a value from the hash table with an [`ArrayKey`],
+    /// returning the removed value.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to remove from the hash table.
+    pub fn remove_key(&mut self, key: &ArrayKey) -> Option<Zval> {
+        match key {
+            ArrayKey::Long(key) => self.remove_index(*key as u64),
+            ArrayKey::String(key) => self.remove(key),
+        }
+    }
+
+    /// Inserts an item into the hash table with an [`ArrayKey`], or updates
+    /// the value if the key already exists. Returns a reference to the value
+    /// in the hash table.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to insert the value at in the hash table.
+    /// * `val` - The value to insert into the hash table.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if converting the value into a [`Zval`] failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ArrayKey, ZendHashTable};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert_key(ArrayKey::Long(5), "five");
+    /// ht.insert_key(ArrayKey::String("six".into()), 6);
+    /// assert_eq!(ht.len(), 2);
+    /// ```
+    pub fn insert_key<V>(&mut self, key: ArrayKey, val: V) -> Result<&mut Zval>
+    where
+        V: IntoZval,
+    {
+        let mut val = val.into_zval(false)?;
+        let ptr = unsafe {
+            match &key {
+                ArrayKey::Long(key) => zend_hash_index_update(self, *key as u64, &mut val),
+                ArrayKey::String(key) => zend_hash_str_update(
+                    self,
+                    key.as_ptr() as *const c_char,
+                    key.len() as _,
+                    &mut val,
+                ),
+            }
+        };
+        val.release();
 
-        if result < 0 {
-            None
+        // SAFETY: The hashtable returns a pointer to the inserted value.
+        unsafe { ptr.as_mut() }.ok_or(Error::InvalidPointer)
+    }
+
+    /// Gets the entry of the given key in the hash table, for in-place
+    /// manipulation.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the entry.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ArrayKey, ZendHashTable};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// for word in ["a", "b", "a"] {
+    ///     let count = ht
+    ///         .entry(ArrayKey::String(word.into()))
+    ///         .or_insert(0)
+    ///         .unwrap();
+    ///     count.set_long(count.long().unwrap_or_default() + 1);
+    /// }
+    ///
+    /// assert_eq!(ht.get("a").and_then(|zv| zv.long()), Some(2));
+    /// ```
+    pub fn entry(&mut self, key: ArrayKey) -> Entry<'_> {
+        if self.get_key(&key).is_some() {
+            Entry::Occupied(OccupiedEntry { ht: self, key })
         } else {
-            Some(())
+            Entry::Vacant(VacantEntry { ht: self, key })
+        }
+    }
+
+    /// Retains only the elements for which the given function returns `true`,
+    /// removing the other elements from the hash table. Keys are not
+    /// renumbered.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - Called with the key and value of each element.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// ht.push(3);
+    /// ht.retain(|_, val| val.long().map_or(false, |n| n % 2 == 1));
+    /// assert_eq!(ht.len(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&ArrayKey, &mut Zval) -> bool,
+    {
+        let keys = self.iter().map(|(key, _)| key).collect::<Vec<_>>();
+
+        for key in keys {
+            let keep = match self.get_key_mut(&key) {
+                Some(val) => f(&key, val),
+                None => continue,
+            };
+            if !keep {
+                self.remove_key(&key);
+            }
+        }
+    }
+
+    /// Sorts the values of the hash table with a comparison function, using
+    /// the sort of the engine. Keys are kept with their values, like the PHP
+    /// `uasort` function. The sort is stable.
+    ///
+    /// # Parameters
+    ///
+    /// * `compare` - Compares two values of the hash table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(3);
+    /// ht.push(1);
+    /// ht.push(2);
+    /// ht.sort_by(|a, b| a.long().cmp(&b.long()));
+    /// assert_eq!(ht.values().next().and_then(|zv| zv.long()), Some(1));
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Zval, &Zval) -> Ordering,
+    {
+        self.sort_buckets(|a, b| compare(&a.val, &b.val));
+    }
+
+    /// Sorts the hash table by key, using the standard PHP comparison of the
+    /// keys, like the PHP `ksort` function.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert("b", 2);
+    /// ht.insert("a", 1);
+    /// ht.ksort();
+    /// assert_eq!(ht.values().next().and_then(|zv| zv.long()), Some(1));
+    /// ```
+    pub fn ksort(&mut self) {
+        self.sort_buckets(|a, b| {
+            let mut a = bucket_key(a);
+            let mut b = bucket_key(b);
+            unsafe { zend_compare(&mut a, &mut b) }.cmp(&0)
+        });
+    }
+
+    /// Removes the elements in a range of positions of the hash table,
+    /// replacing them with the given values, like the PHP `array_splice`
+    /// function. Integer keys are renumbered, while string keys are kept.
+    ///
+    /// The elements are moved in place, which resets the internal pointer of
+    /// the hash table and moves the positions of any PHP iterators over it.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - Position of the first element to remove.
+    /// * `length` - Number of elements to remove.
+    /// * `replacement` - Values inserted in place of the removed elements.
+    ///
+    /// # Returns
+    ///
+    /// Returns the removed elements.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ZendHashTable, Zval};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// ht.push(3);
+    ///
+    /// let mut zero = Zval::new();
+    /// zero.set_long(0);
+    ///
+    /// let removed = ht.splice(1, 1, vec![zero]);
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(ht.get_index(1).and_then(|zv| zv.long()), Some(0));
+    /// ```
+    pub fn splice<I>(&mut self, offset: usize, length: usize, replacement: I) -> ZBox<Self>
+    where
+        I: IntoIterator<Item = Zval>,
+    {
+        // Removes the holes left by removed elements, so positions are indexes.
+        self.packed_to_hash();
+        unsafe { zend_hash_rehash(self) };
+
+        let len = self.buckets_mut().len();
+        let offset = offset.min(len);
+        let end = offset.saturating_add(length).min(len);
+
+        let mut removed = ZendHashTable::new();
+        for i in offset..end {
+            let bucket: *mut Bucket = &mut self.buckets_mut()[i];
+            // SAFETY: Deleting a bucket marks it as removed without moving the buckets.
+            unsafe {
+                let val = std::mem::take(&mut (*bucket).val);
+                removed.append_renumbered((*bucket).key.as_ref(), val);
+                zend_hash_del_bucket(self, bucket);
+            }
+        }
+
+        // The replacement is appended, then moved before the elements following
+        // the removed ones. The holes are removed first, as they would be removed
+        // when the hash table grows.
+        unsafe { zend_hash_rehash(self) };
+        let used = self.buckets_mut().len();
+        self.extend(replacement);
+        let inserted = self.buckets_mut().len() - used;
+        self.buckets_mut()[offset..].rotate_right(inserted);
+        self.renumber();
+
+        removed
+    }
+
+    /// Merges the elements of another hash table into the hash table, like the
+    /// PHP `array_merge` function. Integer keys are renumbered and the values
+    /// with integer keys are appended, while the values with string keys
+    /// replace the values with the same keys.
+    ///
+    /// The elements are renumbered in place, which resets the internal pointer
+    /// of the hash table.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The hash table to merge into the hash table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut a = ZendHashTable::new();
+    /// a.push(1);
+    /// a.insert("key", "a");
+    ///
+    /// let mut b = ZendHashTable::new();
+    /// b.push(2);
+    /// b.insert("key", "b");
+    ///
+    /// a.merge(&b);
+    /// assert_eq!(a.len(), 3);
+    /// assert_eq!(a.get("key").and_then(|zv| zv.str()), Some("b"));
+    /// ```
+    pub fn merge(&mut self, other: &ZendHashTable) {
+        self.packed_to_hash();
+        self.renumber();
+
+        let mut entries = other.iter();
+        while let Some((key, val)) = entries.next_zval() {
+            self.append_renumbered(key.zend_str(), val.shallow_clone());
+        }
+    }
+
+    /// Reverses the order of the elements of the hash table, like the PHP
+    /// `array_reverse` function. Integer keys are renumbered, while string
+    /// keys are kept.
+    ///
+    /// The elements are moved in place, which resets the internal pointer of
+    /// the hash table and moves the positions of any PHP iterators over it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// ht.reverse();
+    /// assert_eq!(ht.get_index(0).and_then(|zv| zv.long()), Some(2));
+    /// ```
+    pub fn reverse(&mut self) {
+        self.packed_to_hash();
+        self.buckets_mut().reverse();
+        self.renumber();
+    }
+
+    /// Returns a mutable reference to the value with the given string key.
+    fn find_str(&mut self, key: &str) -> Option<&mut Zval> {
+        unsafe { zend_hash_str_find(self, key.as_ptr() as *const c_char, key.len() as _).as_mut() }
+    }
+
+    /// Returns a mutable reference to the value with the given index.
+    fn find_index(&mut self, key: u64) -> Option<&mut Zval> {
+        unsafe { zend_hash_index_find(self, key).as_mut() }
+    }
+
+    /// Converts a packed hash table into a regular hash table, so its elements
+    /// are stored in buckets.
+    fn packed_to_hash(&mut self) {
+        if self.is_packed() {
+            unsafe { zend_hash_packed_to_hash(self) };
+        }
+    }
+
+    /// Returns the used buckets of the hash table, including the buckets of
+    /// removed elements, which hold an undefined value. The hash table must not
+    /// be packed.
+    fn buckets_mut(&mut self) -> &mut [Bucket] {
+        #[cfg(php82)]
+        let data = unsafe { self.__bindgen_anon_1.arData };
+        #[cfg(not(php82))]
+        let data = self.arData;
+
+        if self.nNumUsed == 0 {
+            return &mut [];
+        }
+        // SAFETY: The first `nNumUsed` buckets of the hash table are initialized.
+        unsafe { slice::from_raw_parts_mut(data, self.nNumUsed as usize) }
+    }
+
+    /// Renumbers the integer keys of the hash table in order from zero,
+    /// keeping the string keys, and rebuilds the hash of the hash table once
+    /// its buckets have been moved. The hash table must not be packed.
+    fn renumber(&mut self) {
+        let mut next: ZendLong = 0;
+        for bucket in self.buckets_mut() {
+            if bucket.key.is_null() && bucket.val.get_type() != DataType::Undef {
+                bucket.h = next as _;
+                next += 1;
+            }
+        }
+        self.nNextFreeElement = next;
+        self.nInternalPointer = 0;
+        unsafe { zend_hash_rehash(self) };
+    }
+
+    /// Appends a value at the next integer key if `key` is [`None`], or
+    /// inserts it at the given string key otherwise.
+    fn append_renumbered(&mut self, key: Option<&ZendStr>, mut val: Zval) {
+        match key {
+            // Inserting a zval cannot fail, as `push` only returns `Err` if converting
+            // `val` to a zval fails.
+            None => {
+                let _ = self.push(val);
+            }
+            Some(key) => {
+                let key = key.as_bytes();
+                unsafe {
+                    zend_hash_str_update(
+                        self,
+                        key.as_ptr() as *const c_char,
+                        key.len() as _,
+                        &mut val,
+                    )
+                };
+                val.release();
+            }
+        }
+    }
+
+    /// Sorts the buckets of the hash table in place with the sort of the
+    /// engine, keeping the keys. Buckets comparing equal keep their order.
+    fn sort_buckets<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Bucket, &Bucket) -> Ordering,
+    {
+        // A panic can't unwind through the sort of the engine, so it is resumed once
+        // the sort has finished.
+        let mut panic = None;
+        let mut compare = |a: &Bucket, b: &Bucket| {
+            if panic.is_some() {
+                return Ordering::Equal;
+            }
+            match catch_unwind(AssertUnwindSafe(|| compare(a, b))) {
+                Ok(ordering) => ordering,
+                Err(payload) => {
+                    panic = Some(payload);
+                    Ordering::Equal
+                }
+            }
+        };
+        let mut compare: &mut dyn FnMut(&Bucket, &Bucket) -> Ordering = &mut compare;
+        reset_panic_backtrace();
+
+        let previous = SORT_COMPARE.with(|cmp| cmp.replace(&mut compare as *mut _ as *mut c_void));
+        unsafe { zend_hash_sort_ex(self, Some(zend_sort), Some(compare_buckets), false) };
+        SORT_COMPARE.with(|cmp| cmp.set(previous));
+
+        if let Some(payload) = panic {
+            resume_unwind(payload);
         }
     }
 
@@ -488,10 +951,7 @@ impl ZendHashTable {
     /// assert!(!ht.has_sequential_keys());
     /// ```
     pub fn has_sequential_keys(&self) -> bool {
-        !self
-            .into_iter()
-            .enumerate()
-            .any(|(i, (k, _))| ArrayKey::Long(i as i64) != k)
+        unsafe { ext_php_rs_zend_array_is_list(self as *const Self as *mut Self) }
     }
 
     /// Returns an iterator over the values contained inside the hashtable, as
@@ -537,6 +997,37 @@ impl ZendHashTable {
     }
 }
 
+thread_local! {
+    /// Comparison function of the sort in progress on the thread, a pointer to a
+    /// `&mut dyn FnMut(&Bucket, &Bucket) -> Ordering`.
+    static SORT_COMPARE: Cell<*mut c_void> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Compares two buckets with the comparison function of the sort in progress,
+/// falling back to the original order of the buckets.
+unsafe extern "C" fn compare_buckets(a: *mut Bucket, b: *mut Bucket) -> c_int {
+    let compare =
+        SORT_COMPARE.with(|cmp| cmp.get()) as *mut &mut dyn FnMut(&Bucket, &Bucket) -> Ordering;
+    let (a, b) = (&*a, &*b);
+
+    // The engine stores the original position of each bucket in `u2.extra`.
+    let ordering = (*compare)(a, b).then_with(|| a.val.u2.extra.cmp(&b.val.u2.extra));
+    ordering as c_int
+}
+
+/// Returns the key of a bucket as a zval. String keys are borrowed from the
+/// bucket, as the zval is not refcounted.
+fn bucket_key(bucket: &Bucket) -> Zval {
+    let mut key = Zval::new();
+    if bucket.key.is_null() {
+        key.set_long(bucket.h as ZendLong);
+    } else {
+        key.value.str_ = bucket.key;
+        key.u1.type_info = ZvalTypeFlags::String.bits();
+    }
+    key
+}
+
 unsafe impl ZBoxable for ZendHashTable {
     fn free(&mut self) {
         // SAFETY: ZBox has immutable access to `self`.
@@ -578,7 +1069,7 @@ pub struct Iter<'a> {
     end_pos: HashPosition,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ArrayKey {
     Long(i64),
     String(String),
@@ -608,15 +1099,282 @@ impl Display for ArrayKey {
     }
 }
 
+impl From<i64> for ArrayKey {
+    fn from(key: i64) -> Self {
+        ArrayKey::Long(key)
+    }
+}
+
+/// Converts a string to a key the same way PHP does, where a string holding a
+/// decimal integer becomes an integer key.
+impl From<String> for ArrayKey {
+    fn from(key: String) -> Self {
+        match numeric_key(&key) {
+            Some(key) => ArrayKey::Long(key),
+            None => ArrayKey::String(key),
+        }
+    }
+}
+
+/// Converts a string to a key the same way PHP does, where a string holding a
+/// decimal integer becomes an integer key.
+impl From<&str> for ArrayKey {
+    fn from(key: &str) -> Self {
+        match numeric_key(key) {
+            Some(key) => ArrayKey::Long(key),
+            None => ArrayKey::String(key.into()),
+        }
+    }
+}
+
+impl From<&String> for ArrayKey {
+    fn from(key: &String) -> Self {
+        key.as_str().into()
+    }
+}
+
+impl From<Cow<'_, str>> for ArrayKey {
+    fn from(key: Cow<'_, str>) -> Self {
+        match key {
+            Cow::Borrowed(key) => key.into(),
+            Cow::Owned(key) => key.into(),
+        }
+    }
+}
+
+macro_rules! array_key_from_int {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for ArrayKey {
+            fn from(key: $ty) -> Self {
+                ArrayKey::Long(key.into())
+            }
+        }
+    )*};
+}
+
+array_key_from_int!(i8, i16, i32, u8, u16, u32);
+
+/// Implements the conversions of integers which may not fit in an integer key,
+/// failing with [`Error::IntegerOverflow`] if they do not.
+macro_rules! array_key_try_from_int {
+    ($($ty:ty),*) => {$(
+        impl TryFrom<$ty> for ArrayKey {
+            type Error = Error;
+
+            fn try_from(key: $ty) -> Result<Self> {
+                key.try_into()
+                    .map(ArrayKey::Long)
+                    .map_err(|_| Error::IntegerOverflow)
+            }
+        }
+    )*};
+}
+
+array_key_try_from_int!(u64, usize, isize);
+
+/// Converts an integer key to its decimal representation.
+impl From<ArrayKey> for String {
+    fn from(key: ArrayKey) -> Self {
+        match key {
+            ArrayKey::Long(key) => key.to_string(),
+            ArrayKey::String(key) => key,
+        }
+    }
+}
+
+impl TryFrom<ArrayKey> for i64 {
+    type Error = Error;
+
+    fn try_from(key: ArrayKey) -> Result<Self> {
+        match key {
+            ArrayKey::Long(key) => Ok(key),
+            ArrayKey::String(_) => Err(Error::ZvalConversion(DataType::String)),
+        }
+    }
+}
+
+/// Implements the conversions of integer keys into unsigned integers, failing
+/// with [`Error::IntegerOverflow`] for negative keys.
+macro_rules! int_try_from_array_key {
+    ($($ty:ty),*) => {$(
+        impl TryFrom<ArrayKey> for $ty {
+            type Error = Error;
+
+            fn try_from(key: ArrayKey) -> Result<Self> {
+                i64::try_from(key)?
+                    .try_into()
+                    .map_err(|_| Error::IntegerOverflow)
+            }
+        }
+    )*};
+}
+
+int_try_from_array_key!(u64, usize);
+
+/// Returns the integer a string key is converted to by PHP, if any.
+fn numeric_key(key: &str) -> Option<i64> {
+    let digits = key.strip_prefix('-').unwrap_or(key);
+    if digits.is_empty()
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+        || (digits.starts_with('0') && (digits.len() > 1 || digits.len() != key.len()))
+    {
+        return None;
+    }
+    key.parse().ok()
+}
+
+/// A view into an entry of a hashtable, which is either occupied or vacant.
+/// Returned by [`ZendHashTable::entry`].
+pub enum Entry<'a> {
+    /// An entry holding a value.
+    Occupied(OccupiedEntry<'a>),
+    /// An entry without a value.
+    Vacant(VacantEntry<'a>),
+}
+
+/// An entry of a hashtable holding a value.
+pub struct OccupiedEntry<'a> {
+    ht: &'a mut ZendHashTable,
+    key: ArrayKey,
+}
+
+/// An entry of a hashtable without a value.
+pub struct VacantEntry<'a> {
+    ht: &'a mut ZendHashTable,
+    key: ArrayKey,
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &ArrayKey {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts the given value if the entry is vacant, returning a reference
+    /// to the value of the entry.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - The value to insert if the entry is vacant.
+    pub fn or_insert<V: IntoZval>(self, default: V) -> Result<&'a mut Zval> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the value returned by the given function if the entry is
+    /// vacant, returning a reference to the value of the entry.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - Returns the value to insert if the entry is vacant.
+    pub fn or_insert_with<V, F>(self, default: F) -> Result<&'a mut Zval>
+    where
+        V: IntoZval,
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls the given function with the value of the entry if it is occupied.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - Called with the value of the entry.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut Zval),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &ArrayKey {
+        &self.key
+    }
+
+    /// Returns a reference to the value of the entry.
+    pub fn get(&self) -> &Zval {
+        self.ht
+            .get_key(&self.key)
+            .expect("Occupied entry has no value")
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> &mut Zval {
+        self.ht
+            .get_key_mut(&self.key)
+            .expect("Occupied entry has no value")
+    }
+
+    /// Converts the entry into a mutable reference to its value, bound to the
+    /// lifetime of the hashtable.
+    pub fn into_mut(self) -> &'a mut Zval {
+        self.ht
+            .get_key_mut(&self.key)
+            .expect("Occupied entry has no value")
+    }
+
+    /// Replaces the value of the entry, returning the previous value.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The new value of the entry.
+    pub fn insert<V: IntoZval>(&mut self, val: V) -> Result<Zval> {
+        let val = val.into_zval(false)?;
+        Ok(std::mem::replace(self.get_mut(), val))
+    }
+
+    /// Removes the entry from the hashtable, returning its value.
+    pub fn remove(self) -> Zval {
+        self.ht
+            .remove_key(&self.key)
+            .expect("Occupied entry has no value")
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &ArrayKey {
+        &self.key
+    }
+
+    /// Returns the key of the entry, consuming the entry.
+    pub fn into_key(self) -> ArrayKey {
+        self.key
+    }
+
+    /// Inserts a value into the entry, returning a reference to the value.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value of the entry.
+    pub fn insert<V: IntoZval>(self, val: V) -> Result<&'a mut Zval> {
+        self.ht.insert_key(self.key, val)
+    }
+}
+
+/// Keys are either integers or strings, so `mixed` is declared to keep weak
+/// typing from converting integer arguments into strings. Numeric strings are
+/// converted into integer keys, as PHP does.
 impl<'a> FromZval<'a> for ArrayKey {
-    const TYPE: DataType = DataType::String;
+    const TYPE: DataType = DataType::Mixed;
 
     fn from_zval(zval: &'a Zval) -> Option<Self> {
         if let Some(key) = zval.long() {
             return Some(ArrayKey::Long(key));
         }
         if let Some(key) = zval.string() {
-            return Some(ArrayKey::String(key));
+            return Some(key.into());
         }
         None
     }
@@ -879,52 +1637,121 @@ impl<'a> FromZval<'a> for &'a ZendHashTable {
 }
 
 ///////////////////////////////////////////
-// HashMap
+// HashMap, BTreeMap
 ///////////////////////////////////////////
 
-impl<'a, V> TryFrom<&'a ZendHashTable> for HashMap<String, V>
+/// Converts the entries of a hashtable into map entries.
+fn map_entries<'a, K, V>(ht: &'a ZendHashTable) -> impl Iterator<Item = Result<(K, V)>> + 'a
 where
+    K: TryFrom<ArrayKey>,
     V: FromZval<'a>,
 {
-    type Error = Error;
+    ht.iter().map(|(key, val)| {
+        let key_type = if key.is_long() {
+            DataType::Long
+        } else {
+            DataType::String
+        };
+        let key = K::try_from(key).map_err(|_| Error::ZvalConversion(key_type))?;
+        let val = V::from_zval(val).ok_or_else(|| Error::ZvalConversion(val.get_type()))?;
+        Ok((key, val))
+    })
+}
 
-    fn try_from(value: &'a ZendHashTable) -> Result<Self> {
-        let mut hm = HashMap::with_capacity(value.len());
+/// Creates a hashtable from map entries.
+fn from_map_entries<K, V, I>(len: usize, entries: I) -> Result<ZBox<ZendHashTable>>
+where
+    K: TryInto<ArrayKey>,
+    V: IntoZval,
+    I: IntoIterator<Item = (K, V)>,
+{
+    let mut ht = ZendHashTable::with_capacity(len.try_into().map_err(|_| Error::IntegerOverflow)?);
 
-        for (key, val) in value {
-            hm.insert(
-                key.to_string(),
-                V::from_zval(val).ok_or_else(|| Error::ZvalConversion(val.get_type()))?,
-            );
-        }
+    for (k, v) in entries {
+        let k = k.try_into().map_err(|_| Error::IntegerOverflow)?;
+        ht.insert_key(k, v)?;
+    }
 
-        Ok(hm)
+    Ok(ht)
+}
+
+impl<'a, K, V> TryFrom<&'a ZendHashTable> for HashMap<K, V>
+where
+    K: TryFrom<ArrayKey> + Eq + Hash,
+    V: FromZval<'a>,
+{
+    type Error = Error;
+
+    fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+        map_entries(value).collect()
     }
 }
 
 impl<K, V> TryFrom<HashMap<K, V>> for ZBox<ZendHashTable>
 where
-    K: AsRef<str>,
+    K: TryInto<ArrayKey>,
     V: IntoZval,
 {
     type Error = Error;
 
     fn try_from(value: HashMap<K, V>) -> Result<Self> {
-        let mut ht = ZendHashTable::with_capacity(
-            value.len().try_into().map_err(|_| Error::IntegerOverflow)?,
-        );
+        from_map_entries(value.len(), value)
+    }
+}
 
-        for (k, v) in value.into_iter() {
-            ht.insert(k.as_ref(), v)?;
-        }
+impl<K, V> IntoZval for HashMap<K, V>
+where
+    K: TryInto<ArrayKey>,
+    V: IntoZval,
+{
+    const TYPE: DataType = DataType::Array;
 
-        Ok(ht)
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        let arr = self.try_into()?;
+        zv.set_hashtable(arr);
+        Ok(())
     }
 }
 
-impl<K, V> IntoZval for HashMap<K, V>
+impl<'a, K, V> FromZval<'a> for HashMap<K, V>
+where
+    K: TryFrom<ArrayKey> + Eq + Hash,
+    V: FromZval<'a>,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.array().and_then(|arr| arr.try_into().ok())
+    }
+}
+
+impl<'a, K, V> TryFrom<&'a ZendHashTable> for BTreeMap<K, V>
+where
+    K: TryFrom<ArrayKey> + Ord,
+    V: FromZval<'a>,
+{
+    type Error = Error;
+
+    fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+        map_entries(value).collect()
+    }
+}
+
+impl<K, V> TryFrom<BTreeMap<K, V>> for ZBox<ZendHashTable>
+where
+    K: TryInto<ArrayKey>,
+    V: IntoZval,
+{
+    type Error = Error;
+
+    fn try_from(value: BTreeMap<K, V>) -> Result<Self> {
+        from_map_entries(value.len(), value)
+    }
+}
+
+impl<K, V> IntoZval for BTreeMap<K, V>
 where
-    K: AsRef<str>,
+    K: TryInto<ArrayKey>,
     V: IntoZval,
 {
     const TYPE: DataType = DataType::Array;
@@ -936,9 +1763,10 @@ where
     }
 }
 
-impl<'a, T> FromZval<'a> for HashMap<String, T>
+impl<'a, K, V> FromZval<'a> for BTreeMap<K, V>
 where
-    T: FromZval<'a>,
+    K: TryFrom<ArrayKey> + Ord,
+    V: FromZval<'a>,
 {
     const TYPE: DataType = DataType::Array;
 
@@ -1000,6 +1828,16 @@ where
     }
 }
 
+/// Describes the values of type `T` for the `ValueError` thrown when a
+/// collection argument could not be converted, e.g. `values of type int which
+/// must be between 0 and 255`.
+fn values_of<'a, T: FromZval<'a>>() -> String {
+    match T::value_constraint() {
+        Some(constraint) => format!("values of type {} which {constraint}", type_name(T::TYPE)),
+        None => format!("values of type {}", type_name(T::TYPE)),
+    }
+}
+
 impl<'a, T> FromZval<'a> for Vec<T>
 where
     T: FromZval<'a>,
@@ -1009,6 +1847,265 @@ where
     fn from_zval(zval: &'a Zval) -> Option<Self> {
         zval.array().and_then(|arr| arr.try_into().ok())
     }
+    fn value_constraint() -> Option<String> {
+        Some(format!("must only contain {}", values_of::<T>()))
+    }
+}
+
+/// Implements the conversions between list arrays and a collection of values.
+macro_rules! list_conversions {
+    ($($ty:ident [$($bounds:tt)*]),* $(,)?) => {$(
+        impl<'a, T> TryFrom<&'a ZendHashTable> for $ty<T>
+        where
+            T: FromZval<'a> $($bounds)*,
+        {
+            type Error = Error;
+
+            fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+                value
+                    .values()
+                    .map(|val| T::from_zval(val).ok_or_else(|| Error::ZvalConversion(val.get_type())))
+                    .collect()
+            }
+        }
+
+        impl<T> TryFrom<$ty<T>> for ZBox<ZendHashTable>
+        where
+            T: IntoZval,
+        {
+            type Error = Error;
+
+            fn try_from(value: $ty<T>) -> Result<Self> {
+                let mut ht = ZendHashTable::with_capacity(
+                    value.len().try_into().map_err(|_| Error::IntegerOverflow)?,
+                );
+
+                for val in value.into_iter() {
+                    ht.push(val)?;
+                }
+
+                Ok(ht)
+            }
+        }
+
+        impl<T> IntoZval for $ty<T>
+        where
+            T: IntoZval,
+        {
+            const TYPE: DataType = DataType::Array;
+
+            fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+                let arr = self.try_into()?;
+                zv.set_hashtable(arr);
+                Ok(())
+            }
+        }
+
+        impl<'a, T> FromZval<'a> for $ty<T>
+        where
+            T: FromZval<'a> $($bounds)*,
+        {
+            const TYPE: DataType = DataType::Array;
+
+            fn from_zval(zval: &'a Zval) -> Option<Self> {
+                zval.array().and_then(|arr| arr.try_into().ok())
+            }
+
+            fn value_constraint() -> Option<String> {
+                Some(format!("must only contain {}", values_of::<T>()))
+            }
+        }
+    )*};
+}
+
+list_conversions! {
+    VecDeque [],
+    HashSet [+ Eq + Hash],
+    BTreeSet [+ Ord],
+}
+
+///////////////////////////////////////////
+// Fixed size arrays
+///////////////////////////////////////////
+
+impl<'a, T, const N: usize> TryFrom<&'a ZendHashTable> for [T; N]
+where
+    T: FromZval<'a>,
+{
+    type Error = Error;
+
+    fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+        if value.len() != N {
+            return Err(Error::IncorrectArguments(value.len(), N));
+        }
+        if !value.has_sequential_keys() {
+            return Err(Error::ZvalConversion(DataType::Array));
+        }
+
+        let vec: Vec<T> = value.try_into()?;
+        vec.try_into()
+            .map_err(|vec: Vec<T>| Error::IncorrectArguments(vec.len(), N))
+    }
+}
+
+impl<T, const N: usize> TryFrom<[T; N]> for ZBox<ZendHashTable>
+where
+    T: IntoZval,
+{
+    type Error = Error;
+
+    fn try_from(value: [T; N]) -> Result<Self> {
+        let mut ht =
+            ZendHashTable::with_capacity(N.try_into().map_err(|_| Error::IntegerOverflow)?);
+
+        for val in value {
+            ht.push(val)?;
+        }
+
+        Ok(ht)
+    }
+}
+
+impl<T, const N: usize> IntoZval for [T; N]
+where
+    T: IntoZval,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        let arr = self.try_into()?;
+        zv.set_hashtable(arr);
+        Ok(())
+    }
+}
+
+impl<'a, T, const N: usize> FromZval<'a> for [T; N]
+where
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.array().and_then(|arr| arr.try_into().ok())
+    }
+    fn value_constraint() -> Option<String> {
+        Some(format!("must be a list of {N} {}", values_of::<T>()))
+    }
+}
+
+///////////////////////////////////////////
+// Tuples
+///////////////////////////////////////////
+
+/// Implements the conversions between list arrays and tuples, where each
+/// element of the tuple is a value of the array.
+macro_rules! tuple_conversions {
+    ($(($($name:ident),+)),* $(,)?) => {$(
+        impl<'a, $($name),+> TryFrom<&'a ZendHashTable> for ($($name,)+)
+        where
+            $($name: FromZval<'a>,)+
+        {
+            type Error = Error;
+
+            #[allow(non_snake_case)]
+            fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+                let len = [$(stringify!($name)),+].len();
+                if value.len() != len {
+                    return Err(Error::IncorrectArguments(value.len(), len));
+                }
+                if !value.has_sequential_keys() {
+                    return Err(Error::ZvalConversion(DataType::Array));
+                }
+
+                let mut values = value.values();
+                $(
+                    let val = values.next().ok_or(Error::IncorrectArguments(value.len(), len))?;
+                    let $name = $name::from_zval(val)
+                        .ok_or_else(|| Error::ZvalConversion(val.get_type()))?;
+                )+
+                Ok(($($name,)+))
+            }
+        }
+
+        impl<$($name),+> TryFrom<($($name,)+)> for ZBox<ZendHashTable>
+        where
+            $($name: IntoZval,)+
+        {
+            type Error = Error;
+
+            #[allow(non_snake_case)]
+            fn try_from(value: ($($name,)+)) -> Result<Self> {
+                let ($($name,)+) = value;
+                let mut ht = ZendHashTable::new();
+                $(ht.push($name)?;)+
+                Ok(ht)
+            }
+        }
+
+        impl<$($name),+> IntoZval for ($($name,)+)
+        where
+            $($name: IntoZval,)+
+        {
+            const TYPE: DataType = DataType::Array;
+
+            fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+                let arr = self.try_into()?;
+                zv.set_hashtable(arr);
+                Ok(())
+            }
+        }
+
+        impl<'a, $($name),+> FromZval<'a> for ($($name,)+)
+        where
+            $($name: FromZval<'a>,)+
+        {
+            const TYPE: DataType = DataType::Array;
+
+            fn from_zval(zval: &'a Zval) -> Option<Self> {
+                zval.array().and_then(|arr| arr.try_into().ok())
+            }
+
+            fn value_constraint() -> Option<String> {
+                let types = [$(type_name($name::TYPE)),+];
+                Some(format!("must be a list of type [{}]", types.join(", ")))
+            }
+        }
+    )*};
+}
+
+tuple_conversions! {
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+}
+
+impl Extend<Zval> for ZendHashTable {
+    fn extend<T: IntoIterator<Item = Zval>>(&mut self, iter: T) {
+        for item in iter.into_iter() {
+            // Inserting a zval cannot fail, as `push` only returns `Err` if converting
+            // `val` to a zval fails.
+            let _ = self.push(item);
+        }
+    }
+}
+
+impl Extend<(ArrayKey, Zval)> for ZendHashTable {
+    fn extend<T: IntoIterator<Item = (ArrayKey, Zval)>>(&mut self, iter: T) {
+        for (key, val) in iter.into_iter() {
+            // Inserting a zval cannot fail, as `insert_key` only returns `Err` if
+            // converting `val` to a zval fails.
+            let _ = self.insert_key(key, val);
+        }
+    }
 }
 
 impl FromIterator<Zval> for ZBox<ZendHashTable> {
@@ -1,13 +1,23 @@
 //! Types related to callables in PHP (anonymous functions, functions, etc).
 
-use std::{convert::TryFrom, ops::Deref};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::Deref,
+    ptr,
+};
 
 use crate::{
-    convert::{FromZval, IntoZvalDyn},
+    convert::{FromZval, IntoZval, IntoZvalDyn},
     error::{Error, Result},
-    ffi::_call_user_function_impl,
+    ffi::{
+        _call_user_function_impl, zend_call_known_function, zend_fcall_info_cache,
+        zend_is_callable_ex, zend_release_fcall_info_cache, ZEND_ACC_CALL_VIA_TRAMPOLINE,
+    },
     flags::DataType,
-    zend::ExecutorGlobals,
+    zend::{call_php, ExecutorGlobals},
 };
 
 use super::Zval;
@@ -113,7 +123,7 @@ impl<'a> ZendCallable<'a> {
             .collect::<Result<Vec<_>>>()?;
         let packed = params.into_boxed_slice();
 
-        let result = unsafe {
+        let result = call_php(|| unsafe {
             _call_user_function_impl(
                 std::ptr::null_mut(),
                 self.0.as_ref() as *const crate::ffi::_zval_struct as *mut crate::ffi::_zval_struct,
@@ -122,15 +132,13 @@ impl<'a> ZendCallable<'a> {
                 packed.as_ptr() as *mut _,
                 std::ptr::null_mut(),
             )
-        };
+        })?;
 
         if result < 0 {
-            Err(Error::Callable)
-        } else if let Some(e) = ExecutorGlobals::take_exception() {
-            Err(Error::Exception(e))
-        } else {
-            Ok(retval)
+            return Err(Error::Callable);
         }
+        ExecutorGlobals::check_exception()?;
+        Ok(retval)
     }
 }
 
@@ -150,6 +158,221 @@ impl TryFrom<Zval> for ZendCallable<'_> {
     }
 }
 
+/// A PHP callable taking the arguments `Args` and returning `Ret`, resolved
+/// once and then called without looking the function up again.
+///
+/// Unlike [`ZendCallable`], the arguments are given as a tuple, converted
+/// into zvals on the stack, and the return value is converted into `Ret`. The
+/// callable holds a reference to the underlying zval, so it stays valid for
+/// as long as the [`Callable`] is alive.
+///
+/// Methods called through `__call` or `__callStatic` are looked up again on
+/// every call, as PHP releases the function once it has been called.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::Callable;
+///
+/// let strpos = Callable::<(&str, &str), i64>::try_from_name("strpos").unwrap();
+/// assert_eq!(strpos.call(("hello", "e")).unwrap(), 1);
+/// ```
+pub struct Callable<Args, Ret> {
+    callable: Zval,
+    /// The resolved function, unless it is a trampoline.
+    fcc: Option<zend_fcall_info_cache>,
+    _marker: PhantomData<fn(Args) -> Ret>,
+}
+
+impl<Args, Ret> Callable<Args, Ret>
+where
+    Args: CallableArgs,
+    for<'a> Ret: FromZval<'a>,
+{
+    /// Attempts to create a new [`Callable`] from a zval, resolving the
+    /// function it refers to.
+    ///
+    /// # Parameters
+    ///
+    /// * `callable` - The underlying [`Zval`] that is callable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [`Zval`] was not callable.
+    pub fn new(callable: &Zval) -> Result<Self> {
+        let callable = callable.shallow_clone();
+        let mut fcc = resolve(&callable)?;
+        let fcc = if is_trampoline(&fcc) {
+            // SAFETY: The trampoline was allocated by PHP when resolving the callable.
+            unsafe { zend_release_fcall_info_cache(&mut fcc) };
+            None
+        } else {
+            Some(fcc)
+        };
+
+        Ok(Self {
+            callable,
+            fcc,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Attempts to create a new [`Callable`] from a function name. Returns a
+    /// result containing the callable if the function existed and was
+    /// callable.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the callable function.
+    pub fn try_from_name(name: &str) -> Result<Self> {
+        let mut callable = Zval::new();
+        callable.set_string(name, false)?;
+
+        Self::new(&callable)
+    }
+
+    /// Calls the callable with the given arguments.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to call the function with, as a tuple.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result converted into `Ret` upon success. If converting
+    /// the arguments or the return value fails, or an exception is thrown, an
+    /// [`Err`] is returned.
+    pub fn call(&self, args: Args) -> Result<Ret> {
+        let mut args = args.into_zvals()?;
+        let args = args.as_mut();
+        let fcc = match self.fcc {
+            Some(fcc) => fcc,
+            // The trampoline is released by PHP once it has been called.
+            None => resolve(&self.callable)?,
+        };
+
+        let mut retval = Zval::new();
+        call_php(|| unsafe {
+            zend_call_known_function(
+                fcc.function_handler,
+                fcc.object,
+                fcc.called_scope,
+                &mut retval,
+                args.len() as _,
+                args.as_mut_ptr(),
+                ptr::null_mut(),
+            )
+        })?;
+
+        ExecutorGlobals::check_exception()?;
+        Ret::from_zval(&retval).ok_or_else(|| Error::ZvalConversion(retval.get_type()))
+    }
+}
+
+/// Resolves the function a callable refers to.
+pub(crate) fn resolve(callable: &Zval) -> Result<zend_fcall_info_cache> {
+    let mut fcc = MaybeUninit::uninit();
+
+    // SAFETY: PHP only reads from the callable, and fills in the cache if it is
+    // callable.
+    let callable = unsafe {
+        zend_is_callable_ex(
+            callable as *const Zval as *mut Zval,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            fcc.as_mut_ptr(),
+            ptr::null_mut(),
+        )
+    };
+
+    if callable {
+        // SAFETY: The cache was filled in as the zval is callable.
+        Ok(unsafe { fcc.assume_init() })
+    } else {
+        Err(Error::Callable)
+    }
+}
+
+/// Returns whether the resolved function is a trampoline, i.e. a call to
+/// `__call` or `__callStatic`.
+pub(crate) fn is_trampoline(fcc: &zend_fcall_info_cache) -> bool {
+    // SAFETY: The function was resolved by PHP.
+    unsafe { (*fcc.function_handler).common.fn_flags & ZEND_ACC_CALL_VIA_TRAMPOLINE != 0 }
+}
+
+impl<Args, Ret> Debug for Callable<Args, Ret> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Callable")
+            .field("callable", &self.callable)
+            .field("fcc", &self.fcc)
+            .finish()
+    }
+}
+
+impl<'a, Args, Ret> FromZval<'a> for Callable<Args, Ret>
+where
+    Args: CallableArgs,
+    for<'b> Ret: FromZval<'b>,
+{
+    const TYPE: DataType = DataType::Callable;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        Self::new(zval).ok()
+    }
+}
+
+/// Arguments of a [`Callable`], converted into zvals when calling it.
+///
+/// Implemented on tuples of up to 12 values which implement [`IntoZval`].
+pub trait CallableArgs {
+    /// The zvals the arguments are converted into.
+    type Zvals: AsMut<[Zval]>;
+
+    /// Converts the arguments into zvals.
+    fn into_zvals(self) -> Result<Self::Zvals>;
+}
+
+impl CallableArgs for () {
+    type Zvals = [Zval; 0];
+
+    fn into_zvals(self) -> Result<Self::Zvals> {
+        Ok([])
+    }
+}
+
+macro_rules! callable_args {
+    ($(($n: literal; $($name: ident),+)),* $(,)?) => {$(
+        impl<$($name),+> CallableArgs for ($($name,)+)
+        where
+            $($name: IntoZval,)+
+        {
+            type Zvals = [Zval; $n];
+
+            #[allow(non_snake_case)]
+            fn into_zvals(self) -> Result<Self::Zvals> {
+                let ($($name,)+) = self;
+                Ok([$($name.into_zval(false)?),+])
+            }
+        }
+    )*};
+}
+
+callable_args! {
+    (1; A),
+    (2; A, B),
+    (3; A, B, C),
+    (4; A, B, C, D),
+    (5; A, B, C, D, E),
+    (6; A, B, C, D, E, F),
+    (7; A, B, C, D, E, F, G),
+    (8; A, B, C, D, E, F, G, H),
+    (9; A, B, C, D, E, F, G, H, I),
+    (10; A, B, C, D, E, F, G, H, I, J),
+    (11; A, B, C, D, E, F, G, H, I, J, K),
+    (12; A, B, C, D, E, F, G, H, I, J, K, L),
+}
+
 /// A container for a zval. Either contains a reference to a zval or an owned
 /// zval.
 #[derive(Debug)]
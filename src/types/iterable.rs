@@ -1,6 +1,8 @@
 use super::array::Iter as ZendHashTableIter;
 use super::iterator::Iter as ZendIteratorIter;
+use crate::boxed::ZBox;
 use crate::convert::FromZval;
+use crate::error::Result;
 use crate::flags::DataType;
 use crate::types::{ZendHashTable, ZendIterator, Zval};
 
@@ -9,26 +11,29 @@ use crate::types::{ZendHashTable, ZendIterator, Zval};
 #[derive(Debug)]
 pub enum Iterable<'a> {
     Array(&'a ZendHashTable),
-    Traversable(&'a mut ZendIterator),
+    Traversable(ZBox<ZendIterator>),
 }
 
 impl Iterable<'_> {
-    /// Creates a new rust iterator from a PHP iterable.
-    /// May return None if a Traversable cannot be rewound.
-    pub fn iter(&mut self) -> Option<Iter> {
+    /// Creates a new rust iterator from a PHP iterable, yielding owned
+    /// `(key, value)` pairs.
+    ///
+    /// Errors can only occur when iterating over a Traversable, see
+    /// [`ZendIterator::iter`].
+    pub fn iter(&mut self) -> Iter<'_> {
         match self {
-            Iterable::Array(array) => Some(Iter::Array(array.iter())),
-            Iterable::Traversable(traversable) => Some(Iter::Traversable(traversable.iter()?)),
+            Iterable::Array(array) => Iter::Array(array.iter()),
+            Iterable::Traversable(traversable) => Iter::Traversable(traversable.iter()),
         }
     }
 }
 
-impl<'a> IntoIterator for &'a mut Iterable<'a> {
-    type Item = (Zval, &'a Zval);
+impl<'a> IntoIterator for &'a mut Iterable<'_> {
+    type Item = Result<(Zval, Zval)>;
     type IntoIter = Iter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.iter().expect("Could not rewind iterator!")
+        self.iter()
     }
 }
 
@@ -54,12 +59,14 @@ pub enum Iter<'a> {
     Traversable(ZendIteratorIter<'a>),
 }
 
-impl<'a> Iterator for Iter<'a> {
-    type Item = (Zval, &'a Zval);
+impl Iterator for Iter<'_> {
+    type Item = Result<(Zval, Zval)>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self {
-            Iter::Array(array) => array.next_zval(),
+            Iter::Array(array) => array
+                .next_zval()
+                .map(|(key, value)| Ok((key, value.dereference().shallow_clone()))),
             Iter::Traversable(traversable) => traversable.next(),
         }
     }
@@ -1,8 +1,10 @@
-use crate::convert::FromZvalMut;
-use crate::ffi::{zend_object_iterator, ZEND_RESULT_CODE_SUCCESS};
+use crate::boxed::{ZBox, ZBoxable};
+use crate::convert::{FromZval, IntoZval};
+use crate::error::{Error, Result};
+use crate::ffi::{zend_iterator_dtor, zend_object_iterator, ZEND_RESULT_CODE_SUCCESS};
 use crate::flags::DataType;
-use crate::types::Zval;
-use crate::zend::ExecutorGlobals;
+use crate::types::{ZendObject, Zval};
+use crate::zend::{call_php, ce, ExecutorGlobals};
 use std::fmt::{Debug, Formatter};
 
 /// A PHP Iterator.
@@ -10,40 +12,44 @@ use std::fmt::{Debug, Formatter};
 /// In PHP, iterators are represented as zend_object_iterator. This allows user
 /// to iterate over objects implementing Traversable interface using foreach.
 ///
+/// Iterators are owned through a [`ZBox`], which releases the iterator when
+/// dropped. The values yielded by the iterator are owned [`Zval`]s, so they
+/// remain valid after the iterator has moved on. Exceptions thrown by the
+/// iterator, e.g. in a user-defined `current()` or `next()` method, are
+/// returned as an [`Err`].
+///
 /// Use ZendIterable to iterate over both iterators and arrays.
 pub type ZendIterator = zend_object_iterator;
 
 impl ZendIterator {
     /// Creates a new rust iterator from a zend_object_iterator.
     ///
-    /// Returns a iterator over the zend_object_iterator, or None if the
-    /// iterator cannot be rewound.
-    pub fn iter(&mut self) -> Option<Iter> {
-        self.index = 0;
-
-        if self.rewind() {
-            return Some(Iter { zi: self });
+    /// The iterator is rewound when the first element is requested. Iteration
+    /// stops after the first error, which is returned as the last item.
+    pub fn iter(&mut self) -> Iter<'_> {
+        Iter {
+            zi: self,
+            state: IterState::Rewind,
         }
-
-        None
     }
 
     /// Check if the current position of the iterator is valid.
     ///
     /// As an example this will call the user defined valid method of the
     /// ['\Iterator'] interface. see <https://www.php.net/manual/en/iterator.valid.php>
-    pub fn valid(&mut self) -> bool {
-        if let Some(valid) = unsafe { (*self.funcs).valid } {
-            let valid = unsafe { valid(&mut *self) == ZEND_RESULT_CODE_SUCCESS };
-
-            if ExecutorGlobals::has_exception() {
-                return false;
-            }
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if PHP bailed out or an exception was thrown.
+    pub fn valid(&mut self) -> Result<bool> {
+        let valid = match unsafe { (*self.funcs).valid } {
+            Some(valid) => valid,
+            None => return Ok(true),
+        };
+        let valid = call_php(|| unsafe { valid(&mut *self) == ZEND_RESULT_CODE_SUCCESS })?;
+        ExecutorGlobals::check_exception()?;
 
-            valid
-        } else {
-            true
-        }
+        Ok(valid)
     }
 
     /// Rewind the iterator to the first element.
@@ -51,18 +57,17 @@ impl ZendIterator {
     /// As an example this will call the user defined rewind method of the
     /// ['\Iterator'] interface. see <https://www.php.net/manual/en/iterator.rewind.php>
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// Returns true if the iterator was successfully rewind, false otherwise.
-    /// (when there is an exception during rewind)
-    pub fn rewind(&mut self) -> bool {
+    /// Returns an error if PHP bailed out or an exception was thrown, for
+    /// example when rewinding a generator that was already run.
+    pub fn rewind(&mut self) -> Result<()> {
+        self.index = 0;
         if let Some(rewind) = unsafe { (*self.funcs).rewind } {
-            unsafe {
-                rewind(&mut *self);
-            }
+            call_php(|| unsafe { rewind(&mut *self) })?;
         }
 
-        !ExecutorGlobals::has_exception()
+        ExecutorGlobals::check_exception()
     }
 
     /// Move the iterator forward to the next element.
@@ -70,65 +75,134 @@ impl ZendIterator {
     /// As an example this will call the user defined next method of the
     /// ['\Iterator'] interface. see <https://www.php.net/manual/en/iterator.next.php>
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// Returns true if the iterator was successfully move, false otherwise.
-    /// (when there is an exception during next)
-    pub fn move_forward(&mut self) -> bool {
+    /// Returns an error if PHP bailed out or an exception was thrown.
+    pub fn move_forward(&mut self) -> Result<()> {
+        self.index += 1;
         if let Some(move_forward) = unsafe { (*self.funcs).move_forward } {
-            unsafe {
-                move_forward(&mut *self);
-            }
+            call_php(|| unsafe { move_forward(&mut *self) })?;
         }
 
-        !ExecutorGlobals::has_exception()
+        ExecutorGlobals::check_exception()
     }
 
     /// Get the current data of the iterator.
     ///
     /// # Returns
     ///
-    /// Returns a reference to the current data of the iterator if available
-    /// , ['None'] otherwise.
-    pub fn get_current_data<'a>(&mut self) -> Option<&'a Zval> {
-        let get_current_data = unsafe { (*self.funcs).get_current_data }?;
-        let value = unsafe { &*get_current_data(&mut *self) };
-
-        if ExecutorGlobals::has_exception() {
-            return None;
-        }
-
-        Some(value)
+    /// Returns a copy of the current data of the iterator, which is null if
+    /// the iterator has no data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if PHP bailed out or an exception was thrown.
+    pub fn get_current_data(&mut self) -> Result<Zval> {
+        let get_current_data = match unsafe { (*self.funcs).get_current_data } {
+            Some(get_current_data) => get_current_data,
+            None => return Ok(Zval::new()),
+        };
+        let value = call_php(|| unsafe { get_current_data(&mut *self) })?;
+        ExecutorGlobals::check_exception()?;
+
+        // SAFETY: The data is owned by the iterator, and copied before it
+        // moves on.
+        Ok(unsafe { value.as_ref() }
+            .map(|value| value.dereference().shallow_clone())
+            .unwrap_or_default())
     }
 
     /// Get the current key of the iterator.
     ///
     /// # Returns
     ///
-    /// Returns a new ['Zval'] containing the current key of the iterator if
-    /// available , ['None'] otherwise.
-    pub fn get_current_key(&mut self) -> Option<Zval> {
-        let get_current_key = unsafe { (*self.funcs).get_current_key? };
+    /// Returns a new ['Zval'] containing the current key of the iterator, or
+    /// the position of the iterator if it does not provide keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if PHP bailed out or an exception was thrown.
+    pub fn get_current_key(&mut self) -> Result<Zval> {
         let mut key = Zval::new();
-
-        unsafe {
-            get_current_key(&mut *self, &mut key);
+        match unsafe { (*self.funcs).get_current_key } {
+            Some(get_current_key) => {
+                call_php(|| unsafe { get_current_key(&mut *self, &mut key) })?;
+                ExecutorGlobals::check_exception()?;
+            }
+            None => key.set_long(self.index as i64),
         }
 
-        if ExecutorGlobals::has_exception() {
-            return None;
-        }
+        Ok(key)
+    }
 
-        Some(key)
+    /// Returns the generator being iterated over, or [`None`] if the
+    /// iterator does not iterate over a [`Generator`].
+    ///
+    /// [`Generator`]: https://www.php.net/manual/en/class.generator.php
+    pub fn generator(&self) -> Option<&ZendObject> {
+        self.data
+            .object()
+            .filter(|obj| obj.instance_of(ce::generator()))
+    }
+
+    /// Sends a value to the generator being iterated over, resuming it. See
+    /// <https://www.php.net/manual/en/generator.send.php>
+    ///
+    /// The generator moves on to its next `yield`, so the position of the
+    /// iterator is moved forward like [`ZendIterator::move_forward`] does.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value the current `yield` expression evaluates to.
+    ///
+    /// # Returns
+    ///
+    /// Returns the value yielded next by the generator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Callable`] if the iterator does not iterate over a
+    /// generator, or an error if PHP bailed out or an exception was thrown.
+    pub fn send<T: IntoZval>(&mut self, value: T) -> Result<Zval> {
+        let sent = self
+            .generator()
+            .ok_or(Error::Callable)?
+            .call_method("send")?
+            .arg(value)
+            .invoke()?;
+        self.index += 1;
+
+        Ok(sent)
+    }
+
+    /// Returns the value returned by the generator being iterated over. See
+    /// <https://www.php.net/manual/en/generator.getreturn.php>
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Callable`] if the iterator does not iterate over a
+    /// generator, or an error if PHP bailed out or an exception was thrown,
+    /// for example when the generator has not returned yet.
+    pub fn get_return(&self) -> Result<Zval> {
+        self.generator()
+            .ok_or(Error::Callable)?
+            .call_method("getReturn")?
+            .invoke()
+    }
+}
+
+unsafe impl ZBoxable for ZendIterator {
+    fn free(&mut self) {
+        unsafe { zend_iterator_dtor(self) }
     }
 }
 
 impl<'a> IntoIterator for &'a mut ZendIterator {
-    type Item = (Zval, &'a Zval);
+    type Item = Result<(Zval, Zval)>;
     type IntoIter = Iter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.iter().expect("Could not rewind iterator!")
+        self.iter()
     }
 }
 
@@ -138,47 +212,62 @@ impl Debug for ZendIterator {
     }
 }
 
-/// Immutable iterator upon a reference to a PHP iterator.
+/// Position of an [`Iter`] in the underlying iterator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IterState {
+    /// The iterator must be rewound before the first element.
+    Rewind,
+    /// The iterator is at the element returned last, and must be moved
+    /// forward.
+    Next,
+    /// The iterator is exhausted or failed.
+    Done,
+}
+
+/// Iterator over a PHP iterator, yielding owned `(key, value)` pairs.
 pub struct Iter<'a> {
     zi: &'a mut ZendIterator,
+    state: IterState,
 }
 
-impl<'a> Iterator for Iter<'a> {
-    type Item = (Zval, &'a Zval);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        // Call next when index > 0, so next is really called at the start of each
-        // iteration, which allow to work better with generator iterator
-        if self.zi.index > 0 && !self.zi.move_forward() {
-            return None;
+impl Iter<'_> {
+    /// Moves to and returns the next element of the iterator.
+    fn fetch(&mut self) -> Result<Option<(Zval, Zval)>> {
+        match self.state {
+            IterState::Rewind => self.zi.rewind()?,
+            IterState::Next => self.zi.move_forward()?,
+            IterState::Done => return Ok(None),
         }
+        self.state = IterState::Next;
 
-        if !self.zi.valid() {
-            return None;
+        if !self.zi.valid()? {
+            return Ok(None);
         }
 
-        self.zi.index += 1;
-
-        let real_index = self.zi.index - 1;
+        // PHP reads the value before the key in `foreach`.
+        let value = self.zi.get_current_data()?;
+        let key = self.zi.get_current_key()?;
+        Ok(Some((key, value)))
+    }
+}
 
-        let key = match self.zi.get_current_key() {
-            None => {
-                let mut z = Zval::new();
-                z.set_long(real_index as i64);
-                z
-            }
-            Some(key) => key,
-        };
+impl Iterator for Iter<'_> {
+    type Item = Result<(Zval, Zval)>;
 
-        self.zi.get_current_data().map(|value| (key, value))
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.fetch().transpose();
+        if !matches!(item, Some(Ok(_))) {
+            self.state = IterState::Done;
+        }
+        item
     }
 }
 
-impl<'a> FromZvalMut<'a> for &'a mut ZendIterator {
+impl FromZval<'_> for ZBox<ZendIterator> {
     const TYPE: DataType = DataType::Object(Some("Traversable"));
 
-    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
-        zval.object()?.get_class_entry().get_iterator(zval, false)
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.traversable()
     }
 }
 
@@ -202,32 +291,32 @@ mod tests {
 
             assert!(zval.is_traversable());
 
-            let iterator = zval.traversable().unwrap();
+            let mut iterator = zval.traversable().unwrap();
 
-            assert!(iterator.valid());
+            assert!(iterator.valid().unwrap());
 
             {
-                let mut iter = iterator.iter().unwrap();
+                let mut iter = iterator.iter();
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.long(), Some(0));
                 assert!(value.is_long());
                 assert_eq!(value.long().unwrap(), 1);
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.long(), Some(1));
                 assert!(value.is_long());
                 assert_eq!(value.long().unwrap(), 2);
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.long(), Some(2));
                 assert!(value.is_long());
                 assert_eq!(value.long().unwrap(), 3);
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert!(key.is_object());
                 assert!(value.is_object());
@@ -254,34 +343,34 @@ mod tests {
 
             assert!(zval.is_traversable());
 
-            let iterator = zval.traversable().unwrap();
+            let mut iterator = zval.traversable().unwrap();
 
-            assert!(iterator.valid());
+            assert!(iterator.valid().unwrap());
 
             {
-                let mut iter = iterator.iter().unwrap();
+                let mut iter = iterator.iter();
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert!(!key.is_long());
                 assert_eq!(key.str(), Some("key"));
                 assert!(value.is_string());
                 assert_eq!(value.str(), Some("foo"));
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert!(key.is_long());
                 assert_eq!(key.long(), Some(10));
                 assert!(value.is_string());
                 assert_eq!(value.string().unwrap(), "bar");
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.long(), Some(2));
                 assert!(value.is_string());
                 assert_eq!(value.string().unwrap(), "baz");
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert!(key.is_object());
                 assert!(value.is_object());
@@ -293,27 +382,27 @@ mod tests {
 
             // Test rewind
             {
-                let mut iter = iterator.iter().unwrap();
+                let mut iter = iterator.iter();
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.str(), Some("key"));
                 assert!(value.is_string());
                 assert_eq!(value.string().unwrap(), "foo");
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.long(), Some(10));
                 assert!(value.is_string());
                 assert_eq!(value.string().unwrap(), "bar");
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert_eq!(key.long(), Some(2));
                 assert!(value.is_string());
                 assert_eq!(value.string().unwrap(), "baz");
 
-                let (key, value) = iter.next().unwrap();
+                let (key, value) = iter.next().unwrap().unwrap();
 
                 assert!(key.is_object());
                 assert!(value.is_object());
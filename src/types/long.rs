@@ -3,11 +3,10 @@
 //! it is 64-bits.
 
 use crate::{
-    convert::IntoZval,
+    convert::{FromZval, IntoZval},
     error::{Error, Result},
     ffi::zend_long,
     flags::DataType,
-    macros::{into_zval, try_from_zval},
     types::Zval,
 };
 
@@ -58,15 +57,45 @@ try_into_zval_int!(u64);
 try_into_zval_int!(isize);
 try_into_zval_int!(usize);
 
-try_from_zval!(i8, long, Long);
-try_from_zval!(i16, long, Long);
-try_from_zval!(i32, long, Long);
-try_from_zval!(i64, long, Long);
+/// Derives `TryFrom<Zval>` and `FromZval` on an integer type, which fail if
+/// the value is out of the range of the type.
+macro_rules! try_from_zval_int {
+    ($type: ty) => {
+        impl FromZval<'_> for $type {
+            const TYPE: DataType = DataType::Long;
+
+            fn from_zval(zval: &Zval) -> Option<Self> {
+                zval.long().and_then(|val| val.try_into().ok())
+            }
+
+            fn value_constraint() -> Option<String> {
+                Some(format!(
+                    "must be between {} and {}",
+                    <$type>::MIN,
+                    <$type>::MAX
+                ))
+            }
+        }
+
+        impl TryFrom<Zval> for $type {
+            type Error = Error;
+
+            fn try_from(value: Zval) -> Result<Self> {
+                Self::from_zval(&value).ok_or(Error::ZvalConversion(value.get_type()))
+            }
+        }
+    };
+}
+
+try_from_zval_int!(i8);
+try_from_zval_int!(i16);
+try_from_zval_int!(i32);
+try_from_zval_int!(i64);
 
-try_from_zval!(u8, long, Long);
-try_from_zval!(u16, long, Long);
-try_from_zval!(u32, long, Long);
-try_from_zval!(u64, long, Long);
+try_from_zval_int!(u8);
+try_from_zval_int!(u16);
+try_from_zval_int!(u32);
+try_from_zval_int!(u64);
 
-try_from_zval!(usize, long, Long);
-try_from_zval!(isize, long, Long);
+try_from_zval_int!(usize);
+try_from_zval_int!(isize);
@@ -10,17 +10,23 @@ mod iterable;
 mod iterator;
 mod long;
 mod object;
+mod packed;
+mod resource;
 mod string;
+mod value;
 mod zval;
 
-pub use array::{ArrayKey, ZendHashTable};
-pub use callable::ZendCallable;
+pub use array::{ArrayKey, Entry, OccupiedEntry, VacantEntry, ZendHashTable};
+pub use callable::{Callable, CallableArgs, ZendCallable};
 pub use class_object::ZendClassObject;
 pub use iterable::Iterable;
 pub use iterator::ZendIterator;
 pub use long::ZendLong;
 pub use object::{PropertyQuery, ZendObject};
-pub use string::ZendStr;
+pub use packed::{PackedElement, PackedIter, PackedView};
+pub use resource::Resource;
+pub use string::{Bytes, LossyString, ZendStr, ZendStrBuilder};
+pub use value::PhpValue;
 pub use zval::Zval;
 
 use crate::{convert::FromZval, flags::DataType, macros::into_zval};
@@ -5,18 +5,20 @@ use std::{convert::TryInto, fmt::Debug, ops::DerefMut, os::raw::c_char};
 
 use crate::{
     boxed::{ZBox, ZBoxable},
+    builders::CallBuilder,
     class::RegisteredClass,
     convert::{FromZendObject, FromZval, FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::{
         ext_php_rs_zend_object_release, object_properties_init, zend_call_known_function,
-        zend_function, zend_hash_str_find_ptr_lc, zend_object, zend_objects_new, HashTable,
-        ZEND_ISEMPTY, ZEND_PROPERTY_EXISTS, ZEND_PROPERTY_ISSET,
+        zend_fcall_info_cache, zend_function, zend_hash_str_find_ptr_lc, zend_object,
+        zend_objects_new, zend_update_property_ex, HashTable, ZEND_ISEMPTY, ZEND_PROPERTY_EXISTS,
+        ZEND_PROPERTY_ISSET,
     },
-    flags::DataType,
+    flags::{DataType, MethodFlags},
     rc::PhpRc,
-    types::{ZendClassObject, ZendStr, Zval},
-    zend::{ce, ClassEntry, ExecutorGlobals, ZendObjectHandlers},
+    types::{CallableArgs, ZendClassObject, ZendStr, Zval},
+    zend::{call_php, ce, ClassEntry, ExecutorGlobals, ZendObjectHandlers},
 };
 
 /// A PHP object.
@@ -84,6 +86,36 @@ impl ZendObject {
         Self::new(ce::stdclass())
     }
 
+    /// Creates a new instance of the Rust class `T`, calling its constructor
+    /// with the given arguments like `new` in PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to pass to the constructor, as a tuple.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the class cannot be constructed, see
+    /// [`ClassEntry::instantiate`].
+    pub fn new_with<T: RegisteredClass, A: CallableArgs>(
+        args: A,
+    ) -> Result<ZBox<ZendClassObject<T>>> {
+        let obj = T::get_metadata().ce().instantiate(args)?.into_raw();
+        let class_obj = ZendClassObject::<T>::from_zend_obj_mut(obj)
+            .filter(|class_obj| class_obj.obj.is_some())
+            .map(|class_obj| class_obj as *mut _);
+        match class_obj {
+            // SAFETY: The box was consumed above, and the Rust struct was
+            // initialized by the constructor.
+            Some(class_obj) => Ok(unsafe { ZBox::from_raw(class_obj) }),
+            None => {
+                // SAFETY: The box was consumed above.
+                drop(unsafe { ZBox::from_raw(obj) });
+                Err(Error::InvalidScope)
+            }
+        }
+    }
+
     /// Converts a class object into an owned [`ZendObject`]. This removes any
     /// possibility of accessing the underlying attached Rust struct.
     pub fn from_class_object<T: RegisteredClass>(obj: ZBox<ZendClassObject<T>>) -> ZBox<Self> {
@@ -161,19 +193,66 @@ impl ZendObject {
             if res.is_null() {
                 return Err(Error::Callable);
             }
-            zend_call_known_function(
-                res,
-                self as *const _ as *mut _,
-                self.ce,
-                &mut retval,
-                len as _,
-                packed.as_ptr() as *mut _,
-                std::ptr::null_mut(),
-            )
+            call_php(|| {
+                zend_call_known_function(
+                    res,
+                    self as *const _ as *mut _,
+                    self.ce,
+                    &mut retval,
+                    len as _,
+                    packed.as_ptr() as *mut _,
+                    std::ptr::null_mut(),
+                )
+            })?
         };
 
         Ok(retval)
     }
+
+    /// Starts building a call to a method of the object, allowing arguments
+    /// to be passed by reference or by name.
+    ///
+    /// The method is resolved like `$object->method()` in PHP, through the
+    /// `get_method` handler of the object: its visibility is checked against
+    /// the scope of the calling PHP code, and `__call` is used if the method
+    /// does not exist or is not accessible.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Callable`] if the object has no method called `name`,
+    /// or an exception if the method is not accessible.
+    pub fn call_method(&self, name: &str) -> Result<CallBuilder<'_>> {
+        let get_method = unsafe { self.handlers()? }
+            .get_method
+            .ok_or(Error::Callable)?;
+        let mut name = ZendStr::new(name, false);
+        let mut object = self.mut_ptr();
+        let func = unsafe { get_method(&mut object, name.deref_mut(), std::ptr::null()) };
+        ExecutorGlobals::check_exception()?;
+        // SAFETY: The function was resolved by PHP, for the object it returned.
+        let func = unsafe { func.as_ref() }.ok_or(Error::Callable)?;
+        let object = unsafe { &*object };
+        let flags = MethodFlags::from_bits_truncate(unsafe { func.common.fn_flags });
+
+        // SAFETY: The cache only holds pointers, and the fields that differ
+        // between PHP versions are unused.
+        let mut fcc: zend_fcall_info_cache = unsafe { std::mem::zeroed() };
+        fcc.function_handler = func as *const _ as *mut _;
+        fcc.calling_scope = object.ce;
+        fcc.called_scope = object.ce;
+        fcc.object = object as *const _ as *mut _;
+        // The builder takes ownership of a `__call` trampoline, releasing it if
+        // the call is never made.
+        Ok(CallBuilder::from_fcc(
+            fcc,
+            flags.contains(MethodFlags::CallViaTrampoline),
+        ))
+    }
+
     /// Attempts to read a property from the Object. Returns a result containing
     /// the value of the property if it exists and can be read, and an
     /// [`Error`] otherwise.
@@ -231,6 +310,35 @@ impl ZendObject {
         Ok(())
     }
 
+    /// Attempts to set a property on the object from the scope of the given
+    /// class, allowing protected and private properties declared by the class
+    /// to be written.
+    ///
+    /// # Parameters
+    ///
+    /// * `scope` - The class the property is written from.
+    /// * `name` - The name of the property.
+    /// * `value` - The value to set the property to.
+    pub fn update_property(
+        &mut self,
+        scope: &ClassEntry,
+        name: &str,
+        value: impl IntoZval,
+    ) -> Result<()> {
+        let mut name = ZendStr::new(name, false);
+        let mut value = value.into_zval(false)?;
+
+        unsafe {
+            zend_update_property_ex(
+                scope as *const _ as *mut _,
+                self,
+                name.deref_mut(),
+                &mut value,
+            )
+        };
+        Ok(())
+    }
+
     /// Checks if a property exists on an object. Takes a property name and
     /// query parameter, which defines what classifies if a property exists
     /// or not. See [`PropertyQuery`] for more information.
@@ -378,7 +486,7 @@ impl IntoZval for &mut ZendObject {
 impl FromZendObject<'_> for String {
     fn from_zend_object(obj: &ZendObject) -> Result<Self> {
         let mut ret = Zval::new();
-        unsafe {
+        call_php(|| unsafe {
             zend_call_known_function(
                 (*obj.ce).__tostring,
                 obj as *const _ as *mut _,
@@ -388,7 +496,7 @@ impl FromZendObject<'_> for String {
                 std::ptr::null_mut(),
                 std::ptr::null_mut(),
             );
-        }
+        })?;
 
         if let Some(err) = ExecutorGlobals::take_exception() {
             // TODO: become an error
@@ -0,0 +1,357 @@
+//! Typed views over packed PHP arrays, which are arrays with sequential
+//! integer keys stored without a hash.
+
+use std::{convert::TryInto, fmt::Debug, iter::FusedIterator, marker::PhantomData, ptr, slice};
+
+use crate::{
+    args::type_name,
+    boxed::ZBox,
+    convert::FromZval,
+    error::{Error, Result},
+    ffi::{zend_hash_real_init_packed, HASH_FLAG_PACKED},
+    flags::{DataType, ZvalTypeFlags},
+    types::{ZendHashTable, ZendLong, Zval},
+};
+
+#[cfg(not(php82))]
+use crate::ffi::Bucket;
+
+/// A slot of a packed array. Since PHP 8.2, packed arrays store their values
+/// directly, before that they are stored in buckets alongside their key.
+#[cfg(php82)]
+type PackedSlot = Zval;
+#[cfg(not(php82))]
+type PackedSlot = Bucket;
+
+/// A type that can be read from and written to the values of a packed array
+/// without conversion.
+pub trait PackedElement: Copy {
+    /// The type of the zvals holding the element.
+    const TYPE: DataType;
+
+    /// Reads the element from a zval of type [`TYPE`](Self::TYPE).
+    fn from_packed(zval: &Zval) -> Self;
+
+    /// Creates a zval of type [`TYPE`](Self::TYPE) holding the element.
+    fn into_packed(self) -> Zval;
+}
+
+impl PackedElement for ZendLong {
+    const TYPE: DataType = DataType::Long;
+
+    #[inline]
+    fn from_packed(zval: &Zval) -> Self {
+        unsafe { zval.value.lval }
+    }
+
+    #[inline]
+    fn into_packed(self) -> Zval {
+        let mut zval = Zval::new();
+        zval.value.lval = self;
+        zval.u1.type_info = ZvalTypeFlags::Long.bits();
+        zval
+    }
+}
+
+impl PackedElement for f64 {
+    const TYPE: DataType = DataType::Double;
+
+    #[inline]
+    fn from_packed(zval: &Zval) -> Self {
+        unsafe { zval.value.dval }
+    }
+
+    #[inline]
+    fn into_packed(self) -> Zval {
+        let mut zval = Zval::new();
+        zval.value.dval = self;
+        zval.u1.type_info = ZvalTypeFlags::Double.bits();
+        zval
+    }
+}
+
+/// A read-only view over a packed PHP array whose values all have the same
+/// type.
+///
+/// The array is checked once when the view is created, after which the values
+/// are read directly from the array, without being cloned or type-checked.
+/// Can be used as a function parameter, in which case the function throws a
+/// `TypeError` if the array is not a packed array of `T`.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::{PackedView, ZendLong};
+///
+/// fn sum(values: PackedView<ZendLong>) -> ZendLong {
+///     values.iter().sum()
+/// }
+/// ```
+pub struct PackedView<'a, T> {
+    slots: &'a [PackedSlot],
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: PackedElement> PackedView<'a, T> {
+    /// Creates a view over the given array.
+    ///
+    /// # Returns
+    ///
+    /// Returns the view if the array is empty, or is a packed array without
+    /// holes where every value is of type `T`, and [`None`] otherwise.
+    pub fn new(ht: &'a ZendHashTable) -> Option<Self> {
+        if ht.nNumOfElements == 0 {
+            return Some(Self {
+                slots: &[],
+                _marker: PhantomData,
+            });
+        }
+
+        // A packed array without holes has exactly the keys `0..nNumUsed`.
+        if !ht.is_packed() || ht.nNumUsed != ht.nNumOfElements {
+            return None;
+        }
+
+        // SAFETY: The first `nNumUsed` slots of a packed array are initialized.
+        let slots = unsafe { slice::from_raw_parts(packed_data(ht), ht.nNumUsed as usize) };
+        if slots
+            .iter()
+            .any(|slot| slot_zval(slot).get_type() != T::TYPE)
+        {
+            return None;
+        }
+
+        Some(Self {
+            slots,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of values in the array.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if the array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Returns the value at the given index, or [`None`] if the index is out
+    /// of bounds.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.slots
+            .get(index)
+            .map(|slot| T::from_packed(slot_zval(slot)))
+    }
+
+    /// Returns an iterator over the values of the array.
+    pub fn iter(&self) -> PackedIter<'a, T> {
+        PackedIter {
+            slots: self.slots.iter(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Copies the values of the array into a vector.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<T> Clone for PackedView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PackedView<'_, T> {}
+
+impl<T: PackedElement + Debug> Debug for PackedView<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: PackedElement> IntoIterator for PackedView<'a, T> {
+    type Item = T;
+    type IntoIter = PackedIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: PackedElement> FromZval<'a> for PackedView<'a, T> {
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        Self::new(zval.array()?)
+    }
+    fn value_constraint() -> Option<String> {
+        Some(format!(
+            "must be a packed list of type {}",
+            type_name(T::TYPE)
+        ))
+    }
+}
+
+/// Iterator over the values of a [`PackedView`].
+pub struct PackedIter<'a, T> {
+    slots: slice::Iter<'a, PackedSlot>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: PackedElement> Iterator for PackedIter<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots
+            .next()
+            .map(|slot| T::from_packed(slot_zval(slot)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.slots.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.slots
+            .nth(n)
+            .map(|slot| T::from_packed(slot_zval(slot)))
+    }
+}
+
+impl<T: PackedElement> DoubleEndedIterator for PackedIter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.slots
+            .next_back()
+            .map(|slot| T::from_packed(slot_zval(slot)))
+    }
+}
+
+impl<T: PackedElement> ExactSizeIterator for PackedIter<'_, T> {
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+impl<T: PackedElement> FusedIterator for PackedIter<'_, T> {}
+
+impl ZendHashTable {
+    /// Returns `true` if the hashtable is a packed array, which only has
+    /// integer keys stored in ascending order.
+    pub fn is_packed(&self) -> bool {
+        unsafe { self.u.flags & HASH_FLAG_PACKED != 0 }
+    }
+
+    /// Returns a typed view over the values of the hashtable. See
+    /// [`PackedView::new`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let ht = ZendHashTable::from_packed_slice(&[1.5, 2.5]).unwrap();
+    /// let view = ht.packed_view::<f64>().unwrap();
+    ///
+    /// assert_eq!(view.iter().sum::<f64>(), 4.0);
+    /// ```
+    pub fn packed_view<T: PackedElement>(&self) -> Option<PackedView<'_, T>> {
+        PackedView::new(self)
+    }
+
+    /// Creates a packed array holding the given values, writing them directly
+    /// into the storage of the array.
+    ///
+    /// # Parameters
+    ///
+    /// * `values` - The values of the array.
+    ///
+    /// # Returns
+    ///
+    /// Returns the array, or an error if the slice is too long to fit in a PHP
+    /// array.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let ht = ZendHashTable::from_packed_slice(&[1, 2, 3]).unwrap();
+    ///
+    /// assert!(ht.is_packed());
+    /// assert_eq!(ht.get_index(2).and_then(|zv| zv.long()), Some(3));
+    /// ```
+    pub fn from_packed_slice<T: PackedElement>(values: &[T]) -> Result<ZBox<Self>> {
+        let len: u32 = values
+            .len()
+            .try_into()
+            .map_err(|_| Error::IntegerOverflow)?;
+        let mut ht = Self::with_capacity(len);
+        if len == 0 {
+            return Ok(ht);
+        }
+
+        unsafe {
+            // SAFETY: The array was allocated with room for at least `len` values, which
+            // are all initialized before being counted as used.
+            zend_hash_real_init_packed(&mut *ht);
+            let data = packed_data(&ht);
+            for (i, value) in values.iter().enumerate() {
+                ptr::write(data.add(i), packed_slot(i, value.into_packed()));
+            }
+        }
+        ht.nNumUsed = len;
+        ht.nNumOfElements = len;
+        ht.nNextFreeElement = len as ZendLong;
+
+        Ok(ht)
+    }
+}
+
+/// Returns a pointer to the slots of a packed array.
+fn packed_data(ht: &ZendHashTable) -> *mut PackedSlot {
+    #[cfg(php82)]
+    unsafe {
+        ht.__bindgen_anon_1.arPacked
+    }
+    #[cfg(not(php82))]
+    {
+        ht.arData
+    }
+}
+
+#[inline]
+fn slot_zval(slot: &PackedSlot) -> &Zval {
+    #[cfg(php82)]
+    {
+        slot
+    }
+    #[cfg(not(php82))]
+    {
+        &slot.val
+    }
+}
+
+#[inline]
+#[allow(unused_variables)]
+fn packed_slot(index: usize, zval: Zval) -> PackedSlot {
+    #[cfg(php82)]
+    {
+        zval
+    }
+    #[cfg(not(php82))]
+    {
+        Bucket {
+            val: zval,
+            h: index as _,
+            key: ptr::null_mut(),
+        }
+    }
+}
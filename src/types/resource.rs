@@ -0,0 +1,245 @@
+//! Typed PHP resources, which hold a Rust value that is dropped when PHP
+//! releases the resource.
+
+use std::{
+    any::TypeId, collections::HashMap, ffi::CString, fmt::Debug, marker::PhantomData, mem,
+    os::raw::c_int, ptr,
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::{
+    boxed::{ZBox, ZBoxable},
+    convert::{FromZval, FromZvalMut, IntoZval},
+    error::{Error, Result},
+    ffi::{
+        zend_list_close, zend_list_delete, zend_register_list_destructors_ex,
+        zend_register_resource, zend_resource,
+    },
+    flags::DataType,
+    types::{ZendLong, Zval},
+};
+
+/// The resource type numbers assigned by PHP to the registered Rust types.
+static RESOURCE_TYPES: Lazy<RwLock<HashMap<TypeId, c_int>>> = Lazy::new(Default::default);
+
+/// A PHP resource holding a value of type `T`.
+///
+/// The resource type of `T` must be registered when the extension starts up
+/// with [`ModuleBuilder::resource`]. The value is dropped when the last
+/// reference to the resource is released by PHP, or when the resource is
+/// closed.
+///
+/// `&Resource<T>` and `&mut Resource<T>` can be used as function parameters,
+/// which only accept resources of type `T`. New resources are created with
+/// [`Resource::new`] and returned to PHP as a [`ZBox<Resource<T>>`].
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use ext_php_rs::{boxed::ZBox, prelude::*, types::Resource};
+///
+/// pub struct Connection {
+///     host: String,
+/// }
+///
+/// #[php_function]
+/// pub fn connect(host: String) -> PhpResult<ZBox<Resource<Connection>>> {
+///     Ok(Resource::new(Connection { host })?)
+/// }
+///
+/// #[php_function]
+/// pub fn connection_host(conn: &Resource<Connection>) -> Option<String> {
+///     conn.get().map(|conn| conn.host.clone())
+/// }
+///
+/// #[php_module]
+/// pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+///         .resource::<Connection>("connection")
+///         .function(wrap_function!(connect))
+///         .function(wrap_function!(connection_host))
+/// }
+/// ```
+///
+/// [`ModuleBuilder::resource`]: crate::builders::ModuleBuilder::resource
+#[repr(transparent)]
+pub struct Resource<T> {
+    inner: zend_resource,
+    _marker: PhantomData<T>,
+}
+
+impl<T: 'static> Resource<T> {
+    /// Registers the resource type of `T` with PHP, along with the destructor
+    /// dropping the values of the resources.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the resource type, returned by
+    ///   `get_resource_type()`.
+    /// * `module_number` - The module number of the extension.
+    pub(crate) fn register(name: &str, module_number: i32) -> Result<()> {
+        // PHP keeps a pointer to the name for as long as the type is registered.
+        let name = CString::new(name)?.into_raw();
+        let id = unsafe {
+            zend_register_list_destructors_ex(Some(destroy::<T>), None, name, module_number)
+        };
+        RESOURCE_TYPES.write().insert(TypeId::of::<T>(), id);
+        Ok(())
+    }
+
+    /// Returns the resource type number of `T`, or [`None`] if it has not
+    /// been registered.
+    fn resource_type() -> Option<c_int> {
+        RESOURCE_TYPES.read().get(&TypeId::of::<T>()).copied()
+    }
+
+    /// Creates a new resource holding the given value.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value held by the resource.
+    ///
+    /// # Returns
+    ///
+    /// Returns the resource, or an error if the resource type of `T` has not
+    /// been registered.
+    pub fn new(value: T) -> Result<ZBox<Self>> {
+        let ty = Self::resource_type().ok_or(Error::UnregisteredResource)?;
+        let value = Box::into_raw(Box::new(value));
+
+        // SAFETY: `zend_register_resource` returns a valid resource, holding one
+        // reference which is owned by the box.
+        unsafe {
+            let res = zend_register_resource(value.cast(), ty);
+            Ok(ZBox::from_raw(res.cast()))
+        }
+    }
+
+    /// Returns a reference to a resource of type `T`, or [`None`] if the
+    /// resource is of another type or has been closed.
+    ///
+    /// # Parameters
+    ///
+    /// * `res` - A pointer to the resource, for example retrieved with
+    ///   [`Zval::resource`].
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be null or point to a valid resource, which outlives
+    /// the returned reference.
+    pub unsafe fn from_ptr<'a>(res: *mut zend_resource) -> Option<&'a mut Self> {
+        let res = res.as_mut()?;
+        if Some(res.type_) == Self::resource_type() {
+            Some(&mut *(res as *mut zend_resource).cast())
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the value held by the resource, or [`None`] if
+    /// the resource has been closed.
+    pub fn get(&self) -> Option<&T> {
+        unsafe { self.inner.ptr.cast::<T>().as_ref() }
+    }
+
+    /// Returns a mutable reference to the value held by the resource, or
+    /// [`None`] if the resource has been closed.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.inner.ptr.cast::<T>().as_mut() }
+    }
+
+    /// Closes the resource, returning the value it held, or [`None`] if the
+    /// resource was already closed. This works like `fclose()` does for
+    /// streams: the resource stays alive while it is referenced, but is no
+    /// longer of type `T`.
+    pub fn close(&mut self) -> Option<T> {
+        let value = mem::replace(&mut self.inner.ptr, ptr::null_mut()).cast::<T>();
+        unsafe { zend_list_close(&mut self.inner) };
+
+        // SAFETY: The pointer of an open resource is a boxed `T`.
+        (!value.is_null()).then(|| *unsafe { Box::from_raw(value) })
+    }
+}
+
+impl<T> Resource<T> {
+    /// Returns the number identifying the resource, which PHP shows when
+    /// dumping the resource and returns from `get_resource_id()`.
+    pub fn id(&self) -> ZendLong {
+        self.inner.handle
+    }
+
+    /// Returns a pointer to the underlying resource, for use with APIs working
+    /// with raw resources.
+    pub fn as_ptr(&self) -> *mut zend_resource {
+        &self.inner as *const _ as *mut _
+    }
+}
+
+/// Drops the value held by a resource of type `T` when PHP destroys the
+/// resource.
+extern "C" fn destroy<T>(res: *mut zend_resource) {
+    // SAFETY: PHP only calls the destructor of resources of type `T`, whose
+    // pointer is a boxed `T`, or null once the value has been taken.
+    unsafe {
+        let value = mem::replace(&mut (*res).ptr, ptr::null_mut()).cast::<T>();
+        if !value.is_null() {
+            drop(Box::from_raw(value));
+        }
+    }
+}
+
+unsafe impl<T> ZBoxable for Resource<T> {
+    fn free(&mut self) {
+        unsafe { zend_list_delete(&mut self.inner) }
+    }
+}
+
+impl<T: Debug + 'static> Debug for Resource<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resource")
+            .field("id", &self.id())
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+impl<'a, T: 'static> FromZval<'a> for &'a Resource<T> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        // SAFETY: The zval holds a valid resource for its lifetime.
+        unsafe { Resource::from_ptr(zval.resource()?) }.map(|res| &*res)
+    }
+}
+
+impl<'a, T: 'static> FromZvalMut<'a> for &'a mut Resource<T> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        // SAFETY: The zval holds a valid resource for its lifetime.
+        unsafe { Resource::from_ptr(zval.resource()?) }
+    }
+}
+
+impl<T: 'static> IntoZval for ZBox<Resource<T>> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        // The reference held by the box is moved into the zval.
+        zv.set_resource(self.into_raw().as_ptr());
+        Ok(())
+    }
+}
+
+impl<T: 'static> IntoZval for &mut Resource<T> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        self.inner.gc.refcount += 1;
+        zv.set_resource(self.as_ptr());
+        Ok(())
+    }
+}
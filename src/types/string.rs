@@ -4,9 +4,12 @@
 use std::{
     borrow::Cow,
     convert::TryFrom,
-    ffi::{CStr, CString},
-    fmt::Debug,
-    slice,
+    ffi::{CStr, CString, OsStr, OsString},
+    fmt::{self, Debug},
+    io, mem,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    ptr, slice,
 };
 
 use parking_lot::{const_mutex, Mutex};
@@ -17,8 +20,9 @@ use crate::{
     error::{Error, Result},
     ffi::{
         ext_php_rs_is_known_valid_utf8, ext_php_rs_set_known_valid_utf8,
-        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release, zend_string,
-        zend_string_init_interned,
+        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release,
+        ext_php_rs_zend_string_separate, smart_str, smart_str_erealloc, smart_str_realloc,
+        zend_string, zend_string_init_interned,
     },
     flags::DataType,
     macros::try_from_zval,
@@ -308,6 +312,37 @@ impl ZendStr {
         unsafe { slice::from_raw_parts(self.val.as_ptr().cast(), self.len()) }
     }
 
+    /// Returns a mutable reference to the bytes of a Zend string, copying the
+    /// string first if it is shared with other owners or interned. Like
+    /// [`Rc::make_mut`], a string which is only referenced by the given box is
+    /// modified in place.
+    ///
+    /// A copy keeps the persistence of the original string.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The string to modify.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let mut s = ZendStr::new("hello", false);
+    /// ZendStr::make_mut(&mut s).make_ascii_uppercase();
+    /// assert_eq!(s.as_str().unwrap(), "HELLO");
+    /// ```
+    ///
+    /// [`Rc::make_mut`]: std::rc::Rc::make_mut
+    pub fn make_mut(this: &mut ZBox<Self>) -> &mut [u8] {
+        unsafe {
+            // When the string is copied, the reference held by the box is released.
+            let separated = ext_php_rs_zend_string_separate(this.as_mut_ptr());
+            ptr::write(this, ZBox::from_raw(separated));
+            slice::from_raw_parts_mut(this.val.as_mut_ptr().cast(), this.len())
+        }
+    }
+
     /// Returns a raw pointer to this object
     pub fn as_ptr(&self) -> *const ZendStr {
         self as *const _
@@ -325,6 +360,188 @@ unsafe impl ZBoxable for ZendStr {
     }
 }
 
+/// A Zend string which is built in place.
+///
+/// Appending to the builder grows the underlying string in chunks, and
+/// [`build`] returns it without copying its contents. This makes it suited
+/// to producing large strings, which would otherwise be assembled in Rust and
+/// then copied into a Zend string.
+///
+/// The builder implements both [`fmt::Write`] and [`io::Write`], so that it
+/// can be the target of [`write!`] or of any writer API.
+///
+/// As with [`ZendStr::new`], a non-persistent string must not outlive the
+/// request it was built in.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::ZendStrBuilder;
+/// use std::fmt::Write;
+///
+/// let mut builder = ZendStrBuilder::new(false);
+/// for i in 0..3 {
+///     write!(builder, "<li>{}</li>", i).unwrap();
+/// }
+/// let s = builder.build();
+/// assert_eq!(s.as_str().unwrap(), "<li>0</li><li>1</li><li>2</li>");
+/// ```
+///
+/// [`build`]: ZendStrBuilder::build
+pub struct ZendStrBuilder {
+    buf: smart_str,
+    persistent: bool,
+}
+
+impl ZendStrBuilder {
+    /// Creates a new, empty builder. No memory is allocated until bytes are
+    /// appended.
+    ///
+    /// # Parameters
+    ///
+    /// * `persistent` - Whether the built string should persist through the
+    ///   request boundary.
+    pub fn new(persistent: bool) -> Self {
+        Self {
+            buf: smart_str {
+                s: ptr::null_mut(),
+                a: 0,
+            },
+            persistent,
+        }
+    }
+
+    /// Creates a new builder with room for at least `capacity` bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The number of bytes to allocate room for.
+    /// * `persistent` - Whether the built string should persist through the
+    ///   request boundary.
+    pub fn with_capacity(capacity: usize, persistent: bool) -> Self {
+        let mut builder = Self::new(persistent);
+        builder.reserve(capacity);
+        builder
+    }
+
+    /// Makes room for at least `additional` more bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `additional` - The number of bytes to make room for.
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self
+            .len()
+            .checked_add(additional)
+            .expect("Zend string length overflow");
+        if self.buf.s.is_null() || len >= self.buf.a {
+            unsafe {
+                if self.persistent {
+                    smart_str_realloc(&mut self.buf, len);
+                } else {
+                    smart_str_erealloc(&mut self.buf, len);
+                }
+            }
+        }
+    }
+
+    /// Appends bytes to the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The bytes to append.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.reserve(bytes.len());
+
+        // SAFETY: The string has room for the bytes after reserving.
+        unsafe {
+            let s = &mut *self.buf.s;
+            ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                s.val.as_mut_ptr().add(s.len).cast(),
+                bytes.len(),
+            );
+            s.len += bytes.len();
+        }
+    }
+
+    /// Appends a string slice to the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `str` - The string slice to append.
+    pub fn push_str(&mut self, str: &str) {
+        self.push_bytes(str.as_bytes());
+    }
+
+    /// Returns the length of the string built so far.
+    pub fn len(&self) -> usize {
+        unsafe { self.buf.s.as_ref() }.map_or(0, ZendStr::len)
+    }
+
+    /// Returns true if nothing has been appended yet, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes of the string built so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { self.buf.s.as_ref() }.map_or(&[], ZendStr::as_bytes)
+    }
+
+    /// Returns a pointer to the underlying smart string, for use with the Zend
+    /// APIs which write to one.
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut smart_str {
+        &mut self.buf
+    }
+
+    /// Finishes the string, returning it without copying its contents.
+    pub fn build(mut self) -> ZBox<ZendStr> {
+        let s = mem::replace(&mut self.buf.s, ptr::null_mut());
+        match unsafe { s.as_mut() } {
+            // SAFETY: The smart string API always leaves room for the
+            // terminating null byte.
+            Some(s) => unsafe {
+                *s.val.as_mut_ptr().add(s.len) = 0;
+                ZBox::from_raw(s)
+            },
+            None => ZendStr::new("", self.persistent),
+        }
+    }
+}
+
+impl Default for ZendStrBuilder {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl Drop for ZendStrBuilder {
+    fn drop(&mut self) {
+        if !self.buf.s.is_null() {
+            unsafe { ext_php_rs_zend_string_release(self.buf.s) };
+        }
+    }
+}
+
+impl fmt::Write for ZendStrBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl io::Write for ZendStrBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 impl Debug for ZendStr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.as_str().fmt(f)
@@ -444,6 +661,7 @@ macro_rules! try_into_zval_str {
 
 try_into_zval_str!(String);
 try_into_zval_str!(&str);
+try_into_zval_str!(Cow<'_, str>);
 try_from_zval!(String, string, String);
 
 impl<'a> FromZval<'a> for &'a str {
@@ -454,6 +672,261 @@ impl<'a> FromZval<'a> for &'a str {
     }
 }
 
+impl<'a> FromZval<'a> for Cow<'a, str> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.str().map(Cow::Borrowed)
+    }
+}
+
+/// A PHP string converted to UTF-8, replacing invalid sequences with
+/// `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// [`String`], [`&str`] and [`Cow<str>`] reject strings which are not valid
+/// UTF-8. Use [`LossyString`] to accept any string instead. The string is
+/// only copied when it needs to be repaired.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::LossyString;
+///
+/// #[php_function]
+/// pub fn shout(text: LossyString) -> String {
+///     text.to_uppercase()
+/// }
+/// ```
+///
+/// [`&str`]: str
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LossyString<'a>(pub Cow<'a, str>);
+
+impl Deref for LossyString<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> From<LossyString<'a>> for Cow<'a, str> {
+    fn from(str: LossyString<'a>) -> Self {
+        str.0
+    }
+}
+
+impl From<LossyString<'_>> for String {
+    fn from(str: LossyString<'_>) -> Self {
+        str.0.into_owned()
+    }
+}
+
+impl<'a> FromZval<'a> for LossyString<'a> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        let zs = zval.zend_str()?;
+        Some(Self(match zs.as_str() {
+            Ok(str) => Cow::Borrowed(str),
+            Err(_) => String::from_utf8_lossy(zs.as_bytes()),
+        }))
+    }
+}
+
+impl IntoZval for LossyString<'_> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.0.set_zval(zv, persistent)
+    }
+}
+
+// PHP strings are arbitrary bytes, so byte slices convert from any string.
+// `Vec<u8>` is converted from and into a PHP array like any other `Vec`, use
+// `Bytes` or `Box<[u8]>` for owned bytes instead.
+
+impl<'a> FromZval<'a> for &'a [u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.zend_str().map(ZendStr::as_bytes)
+    }
+}
+
+impl FromZval<'_> for Box<[u8]> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.zend_str().map(|zs| zs.as_bytes().into())
+    }
+}
+
+impl IntoZval for &[u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_zend_string(ZendStr::new(self, persistent));
+        Ok(())
+    }
+}
+
+impl IntoZval for Box<[u8]> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        (&*self).set_zval(zv, persistent)
+    }
+}
+
+/// An owned PHP string of arbitrary bytes.
+///
+/// A [`Vec<u8>`] converts from and into a PHP array of integers, like any
+/// other [`Vec`]. Wrap the bytes in [`Bytes`] to convert them from and into a
+/// PHP string instead. Unlike [`String`], any string is accepted, whether or
+/// not it is valid UTF-8.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::Bytes;
+///
+/// #[php_function]
+/// pub fn xor(data: Bytes, key: u8) -> Bytes {
+///     data.iter().map(|byte| byte ^ key).collect::<Vec<_>>().into()
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Deref for Bytes {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Bytes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+impl FromZval<'_> for Bytes {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.zend_str().map(|zs| Self(zs.as_bytes().to_vec()))
+    }
+}
+
+impl TryFrom<Zval> for Bytes {
+    type Error = Error;
+
+    fn try_from(value: Zval) -> Result<Self> {
+        Self::from_zval(&value).ok_or_else(|| Error::ZvalConversion(value.get_type()))
+    }
+}
+
+impl IntoZval for Bytes {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.0.as_slice().set_zval(zv, persistent)
+    }
+}
+
+/// Returns the bytes of a PHP string as an OS string. On Windows, OS strings
+/// can only be created from valid UTF-8.
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Some(OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        std::str::from_utf8(bytes).ok().map(OsStr::new)
+    }
+}
+
+/// Returns the bytes of an OS string to store in a PHP string. On Windows,
+/// the OS string must be valid Unicode.
+fn os_str_as_bytes(str: &OsStr) -> Result<&[u8]> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Ok(str.as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        str.to_str().map(str::as_bytes).ok_or(Error::InvalidUtf8)
+    }
+}
+
+impl<'a> FromZval<'a> for &'a OsStr {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        os_str_from_bytes(zval.zend_str()?.as_bytes())
+    }
+}
+
+impl FromZval<'_> for OsString {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        <&OsStr>::from_zval(zval).map(Into::into)
+    }
+}
+
+impl<'a> FromZval<'a> for &'a Path {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        <&OsStr>::from_zval(zval).map(Path::new)
+    }
+}
+
+impl FromZval<'_> for PathBuf {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        <&OsStr>::from_zval(zval).map(Into::into)
+    }
+}
+
+macro_rules! into_zval_os_str {
+    ($($type: ty),*) => {$(
+        impl IntoZval for $type {
+            const TYPE: DataType = DataType::String;
+
+            fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+                os_str_as_bytes(self.as_ref())?.set_zval(zv, persistent)
+            }
+        }
+    )*};
+}
+
+into_zval_os_str!(&OsStr, OsString, &Path, PathBuf);
+
 #[cfg(test)]
 #[cfg(feature = "embed")]
 mod tests {
@@ -0,0 +1,366 @@
+//! An owned representation of PHP values, which does not depend on the Zend
+//! engine and can therefore be sent across threads.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    convert::{TryFrom, TryInto},
+    ffi::c_void,
+    hash::{Hash, Hasher},
+    mem,
+};
+
+#[cfg(php81)]
+use std::ops::DerefMut;
+
+use indexmap::IndexMap;
+
+use crate::{
+    convert::{FromZval, IntoZval},
+    error::{Error, Result},
+    flags::{ClassFlags, DataType},
+    types::{ArrayKey, ZendHashTable, ZendLong, ZendObject, ZendStr, Zval},
+    zend::ClassEntry,
+};
+
+#[cfg(php81)]
+use crate::ffi::{zend_enum_get_case, ZEND_CLASS_CONST_IS_CASE};
+
+/// An owned copy of a PHP value.
+///
+/// Unlike [`Zval`], which points into memory managed by the Zend engine,
+/// [`PhpValue`] owns all of its data. It can be sent to other threads, stored
+/// across requests and compared or hashed without calling into PHP.
+///
+/// Converting a zval with [`PhpValue::try_from`] copies it deeply:
+///
+/// * References are followed and the referenced value is copied.
+/// * Objects are copied as their class name and properties. Private and
+///   protected properties are stored under their mangled name, as in an array
+///   cast, so that private properties of parent classes are kept apart. Any
+///   other state attached to the object, such as a Rust struct or a closure,
+///   is not copied.
+/// * Arrays or objects which contain themselves are replaced by [`Null`] where
+///   they recur.
+/// * Resources and other internal types are copied as [`Null`].
+///
+/// The conversion fails with [`Error::InvalidUtf8`] if an array key or
+/// property name is not valid UTF-8.
+///
+/// Converting a [`PhpValue`] back into a zval creates new arrays and objects.
+/// Objects are created without calling their constructor, and fail to convert
+/// if their class does not exist or is abstract, an interface or a trait. Enum
+/// cases are converted back into the case with the same `name` property.
+///
+/// [`Null`]: PhpValue::Null
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::{PhpValue, Zval};
+/// use std::thread;
+///
+/// fn process(zval: &Zval) -> PhpValue {
+///     let value = PhpValue::try_from(zval).unwrap();
+///
+///     thread::spawn(move || match value {
+///         PhpValue::Array(entries) => PhpValue::Long(entries.len() as _),
+///         _ => PhpValue::Null,
+///     })
+///     .join()
+///     .unwrap()
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhpValue {
+    /// The `null` value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// An integer.
+    Long(ZendLong),
+    /// A floating point number.
+    Double(f64),
+    /// A binary-safe string.
+    String(Vec<u8>),
+    /// An array, with its entries in insertion order.
+    Array(IndexMap<ArrayKey, PhpValue>),
+    /// An object.
+    Object {
+        /// The name of the class of the object.
+        class: String,
+        /// The properties of the object, in declaration order.
+        props: IndexMap<String, PhpValue>,
+    },
+}
+
+impl PhpValue {
+    /// Copies a zval, replacing arrays and objects which are already being
+    /// copied with `null`.
+    fn read(zval: &Zval, parents: &mut Vec<*const c_void>) -> Result<Self> {
+        let mut zval = zval;
+        while zval.is_reference() || zval.is_indirect() {
+            zval = zval.dereference();
+        }
+
+        Ok(if let Some(val) = zval.bool() {
+            Self::Bool(val)
+        } else if let Some(val) = zval.long() {
+            Self::Long(val)
+        } else if let Some(val) = zval.double() {
+            Self::Double(val)
+        } else if let Some(val) = zval.zend_str() {
+            Self::String(val.as_bytes().to_vec())
+        } else if let Some(ht) = zval.array() {
+            Self::read_nested(ht as *const _ as _, parents, |parents| {
+                Ok(Self::Array(
+                    Self::read_entries(ht, parents, |_| true)?
+                        .into_iter()
+                        .collect(),
+                ))
+            })?
+        } else if let Some(obj) = zval.object() {
+            Self::read_nested(obj as *const _ as _, parents, |parents| {
+                let props = match obj.get_properties() {
+                    // Typed properties which have not been initialized are undefined.
+                    Ok(props) => Self::read_entries(props, parents, |val| {
+                        val.dereference().get_type() != DataType::Undef
+                    })?
+                    .into_iter()
+                    .map(|(key, val)| (key.into(), val))
+                    .collect(),
+                    Err(_) => IndexMap::new(),
+                };
+
+                Ok(Self::Object {
+                    class: obj.get_class_name().unwrap_or_default(),
+                    props,
+                })
+            })?
+        } else {
+            Self::Null
+        })
+    }
+
+    /// Copies the entries of a hashtable which match the filter. Fails if a
+    /// key is not valid UTF-8.
+    fn read_entries(
+        ht: &ZendHashTable,
+        parents: &mut Vec<*const c_void>,
+        filter: impl Fn(&Zval) -> bool,
+    ) -> Result<Vec<(ArrayKey, Self)>> {
+        let mut entries = Vec::with_capacity(ht.len());
+        let mut iter = ht.iter();
+        while let Some((key, val)) = iter.next_zval() {
+            if !filter(val) {
+                continue;
+            }
+            let key = ArrayKey::from_zval(&key).ok_or(Error::InvalidUtf8)?;
+            entries.push((key, Self::read(val, parents)?));
+        }
+        Ok(entries)
+    }
+
+    fn read_nested(
+        ptr: *const c_void,
+        parents: &mut Vec<*const c_void>,
+        read: impl FnOnce(&mut Vec<*const c_void>) -> Result<Self>,
+    ) -> Result<Self> {
+        if parents.contains(&ptr) {
+            return Ok(Self::Null);
+        }
+
+        parents.push(ptr);
+        let value = read(parents);
+        parents.pop();
+        value
+    }
+}
+
+/// Returns the class a property is declared in and the name of the property,
+/// from the mangled name of a private or protected property.
+fn property_scope<'a>(ce: &'a ClassEntry, name: &'a str) -> Result<(&'a ClassEntry, &'a str)> {
+    match name
+        .strip_prefix('\0')
+        .and_then(|name| name.split_once('\0'))
+    {
+        Some(("*", name)) => Ok((ce, name)),
+        Some((class, name)) => ClassEntry::try_find(class)
+            .map(|scope| (scope, name))
+            .ok_or_else(|| Error::UnknownClass(class.into())),
+        None => Ok((ce, name)),
+    }
+}
+
+/// Returns the enum case with the name given by the `name` property.
+#[cfg(php81)]
+fn enum_case(ce: &ClassEntry, props: &IndexMap<String, PhpValue>) -> Result<*mut ZendObject> {
+    let name = match props.get("name") {
+        Some(PhpValue::String(name)) => {
+            std::str::from_utf8(name).map_err(|_| Error::InvalidProperty)?
+        }
+        _ => return Err(Error::InvalidProperty),
+    };
+
+    // `zend_enum_get_case` asserts that the case exists.
+    let is_case = ce.find_constant(name).is_some_and(|constant| {
+        let flags = unsafe { constant.value.u2.constant_flags };
+        flags & ZEND_CLASS_CONST_IS_CASE != 0
+    });
+    if !is_case {
+        return Err(Error::InvalidProperty);
+    }
+
+    let mut name = ZendStr::new(name, false);
+    let case = unsafe { zend_enum_get_case(ce as *const _ as *mut _, name.deref_mut()) };
+    if case.is_null() {
+        return Err(Error::InvalidProperty);
+    }
+    Ok(case)
+}
+
+impl TryFrom<&Zval> for PhpValue {
+    type Error = Error;
+
+    fn try_from(zval: &Zval) -> Result<Self> {
+        Self::read(zval, &mut Vec::new())
+    }
+}
+
+impl FromZval<'_> for PhpValue {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.try_into().ok()
+    }
+}
+
+impl IntoZval for PhpValue {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        match self {
+            Self::Null => zv.set_null(),
+            Self::Bool(val) => zv.set_bool(val),
+            Self::Long(val) => zv.set_long(val),
+            Self::Double(val) => zv.set_double(val),
+            Self::String(val) => zv.set_zend_string(ZendStr::new(val, persistent)),
+            Self::Array(entries) => {
+                let mut ht = ZendHashTable::with_capacity(
+                    entries
+                        .len()
+                        .try_into()
+                        .map_err(|_| Error::IntegerOverflow)?,
+                );
+                for (key, val) in entries {
+                    ht.insert_key(key, val)?;
+                }
+                zv.set_hashtable(ht);
+            }
+            Self::Object { class, props } => {
+                let ce = match ClassEntry::try_find(&class) {
+                    Some(ce) => ce,
+                    None => return Err(Error::UnknownClass(class)),
+                };
+                #[cfg(php81)]
+                {
+                    if ce.flags().contains(ClassFlags::Enum) {
+                        // Enum cases live as long as their class, which outlives the request.
+                        zv.set_object(unsafe { &mut *enum_case(ce, &props)? });
+                        return Ok(());
+                    }
+                }
+                if ce.flags().intersects(
+                    ClassFlags::Abstract
+                        | ClassFlags::ImplicitAbstractClass
+                        | ClassFlags::Interface
+                        | ClassFlags::Trait,
+                ) {
+                    return Err(Error::AbstractClass(class));
+                }
+
+                let mut obj = ZendObject::new(ce);
+                for (name, val) in props {
+                    let (scope, name) = property_scope(ce, &name)?;
+                    obj.update_property(scope, name, val)?;
+                }
+                obj.set_zval(zv, persistent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Hash for PhpValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Self::Null => {}
+            Self::Bool(val) => val.hash(state),
+            Self::Long(val) => val.hash(state),
+            // `0.0` and `-0.0` are equal, so they must hash the same.
+            Self::Double(val) => (val + 0.0).to_bits().hash(state),
+            Self::String(val) => val.hash(state),
+            Self::Array(entries) => hash_unordered(entries.iter(), state),
+            Self::Object { class, props } => {
+                class.hash(state);
+                hash_unordered(props.iter(), state);
+            }
+        }
+    }
+}
+
+/// Hashes the entries of a map. Maps are equal regardless of the order of
+/// their entries, so the hashes of the entries are combined by adding them.
+fn hash_unordered<T: Hash, H: Hasher>(entries: impl ExactSizeIterator<Item = T>, state: &mut H) {
+    entries.len().hash(state);
+    entries
+        .map(|entry| {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(0u64, u64::wrapping_add)
+        .hash(state);
+}
+
+impl From<bool> for PhpValue {
+    fn from(val: bool) -> Self {
+        Self::Bool(val)
+    }
+}
+
+impl From<ZendLong> for PhpValue {
+    fn from(val: ZendLong) -> Self {
+        Self::Long(val)
+    }
+}
+
+impl From<f64> for PhpValue {
+    fn from(val: f64) -> Self {
+        Self::Double(val)
+    }
+}
+
+impl From<&str> for PhpValue {
+    fn from(val: &str) -> Self {
+        Self::String(val.into())
+    }
+}
+
+impl From<String> for PhpValue {
+    fn from(val: String) -> Self {
+        Self::String(val.into())
+    }
+}
+
+impl From<Vec<u8>> for PhpValue {
+    fn from(val: Vec<u8>) -> Self {
+        Self::String(val)
+    }
+}
+
+impl<T: Into<PhpValue>> From<Option<T>> for PhpValue {
+    fn from(val: Option<T>) -> Self {
+        val.map_or(Self::Null, Into::into)
+    }
+}
@@ -2,24 +2,31 @@
 //! contains is determined by a property inside the struct. The content of the
 //! Zval is stored in a union.
 
-use std::{convert::TryInto, ffi::c_void, fmt::Debug, ptr};
+use std::{cmp::Ordering, convert::TryInto, ffi::c_void, fmt::Debug, ptr};
 
-use crate::types::iterable::Iterable;
 use crate::types::ZendIterator;
+use crate::types::{callable, iterable::Iterable};
 use crate::{
     binary::Pack,
     binary_slice::PackSlice,
     boxed::ZBox,
+    builders::CallBuilder,
     convert::{FromZval, FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
+    exception::throw_object,
     ffi::{
-        _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, zend_is_callable,
-        zend_is_identical, zend_is_iterable, zend_resource, zend_value, zval, zval_ptr_dtor,
+        _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, add_function, concat_function,
+        div_function, mod_function, mul_function, php_output_discard, php_output_get_contents,
+        php_output_start_default, php_var_dump, php_var_export_ex, pow_function, sub_function,
+        zend_array_dup, zend_compare, zend_is_callable, zend_is_identical, zend_is_iterable,
+        zend_print_zval_r_to_str, zend_resource, zend_result, zend_value, zval, zval_ptr_dtor,
+        ZEND_RESULT_CODE_SUCCESS,
     },
     flags::DataType,
     flags::ZvalTypeFlags,
     rc::PhpRc,
-    types::{ZendCallable, ZendHashTable, ZendLong, ZendObject, ZendStr},
+    types::{ZendCallable, ZendHashTable, ZendLong, ZendObject, ZendStr, ZendStrBuilder},
+    zend::{call_php, ExecutorGlobals},
 };
 
 /// A zend value. This is the primary storage container used throughout the Zend
@@ -192,11 +199,29 @@ impl Zval {
 
     /// Returns a mutable reference to the underlying zval hashtable if the zval
     /// contains an array.
+    ///
+    /// If the array is shared with other zvals, or is immutable, it is
+    /// separated first: the zval is given its own copy of the array, so that
+    /// changes are not visible through the other zvals.
     pub fn array_mut(&mut self) -> Option<&mut ZendHashTable> {
-        if self.is_array() {
-            unsafe { self.value.arr.as_mut() }
-        } else {
-            None
+        if !self.is_array() {
+            return None;
+        }
+
+        unsafe {
+            let arr = self.value.arr.as_mut()?;
+            if arr.get_count() > 1 {
+                let dup = zend_array_dup(arr);
+                // Immutable arrays are not refcounted.
+                if ZvalTypeFlags::from_bits_retain(self.u1.type_info)
+                    .contains(ZvalTypeFlags::RefCounted)
+                {
+                    arr.dec_count();
+                }
+                self.value.arr = dup;
+                self.u1.type_info = ZvalTypeFlags::ArrayEx.bits();
+            }
+            self.value.arr.as_mut()
         }
     }
 
@@ -270,11 +295,27 @@ impl Zval {
     }
 
     /// Returns an iterator over the zval if it is traversable.
-    pub fn traversable(&self) -> Option<&mut ZendIterator> {
-        if self.is_traversable() {
-            self.object()?.get_class_entry().get_iterator(self, false)
-        } else {
-            None
+    ///
+    /// Returns [`None`] if the zval is not traversable, or if creating the
+    /// iterator failed. An exception thrown while creating the iterator, e.g.
+    /// by `IteratorAggregate::getIterator()`, is left for PHP to throw, so
+    /// that it is rethrown instead of the `TypeError` of an argument which
+    /// could not be converted. Use [`ClassEntry::get_iterator`] to handle the
+    /// error instead.
+    ///
+    /// [`ClassEntry::get_iterator`]: crate::zend::ClassEntry::get_iterator
+    pub fn traversable(&self) -> Option<ZBox<ZendIterator>> {
+        if !self.is_traversable() {
+            return None;
+        }
+
+        match self.object()?.get_class_entry().get_iterator(self, false) {
+            Ok(iterator) => Some(iterator),
+            Err(Error::Exception(e)) => {
+                let _ = e.into_zval(false).and_then(throw_object);
+                None
+            }
+            Err(_) => None,
         }
     }
 
@@ -320,6 +361,17 @@ impl Zval {
         self.callable().ok_or(Error::Callable)?.try_call(params)
     }
 
+    /// Starts building a call to the callable contained in the zval, allowing
+    /// arguments to be passed by reference or by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Callable`] if the zval is not callable.
+    pub fn call(&self) -> Result<CallBuilder<'_>> {
+        let fcc = callable::resolve(self)?;
+        Ok(CallBuilder::from_fcc(fcc, callable::is_trampoline(&fcc)))
+    }
+
     /// Returns the type of the Zval.
     pub fn get_type(&self) -> DataType {
         DataType::from(unsafe { self.u1.v.type_ } as u32)
@@ -403,6 +455,232 @@ impl Zval {
         unsafe { zend_is_identical(self_p as *mut Self, other_p as *mut Self) }
     }
 
+    /// Checks if the zval is equal to another one.
+    /// This works like `==` in php.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The zval to compare against.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if an exception was thrown while comparing the values.
+    pub fn loose_eq(&self, other: &Self) -> Result<bool> {
+        Ok(self.compare_raw(other)? == 0)
+    }
+
+    /// Compares the zval to another one.
+    /// This works like `<=>` in php.
+    ///
+    /// Values which cannot be compared, such as objects of different classes,
+    /// are reported as [`Ordering::Greater`], as in PHP. `$a < $b` in PHP is
+    /// `a.compare(b)? == Ordering::Less`, and PHP evaluates `$a > $b` as
+    /// `$b < $a`.
+    ///
+    /// `Zval` does not implement [`PartialEq`] or [`PartialOrd`], as comparing
+    /// values may throw and PHP's comparisons are not transitive.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The zval to compare against.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if an exception was thrown while comparing the values.
+    pub fn compare(&self, other: &Self) -> Result<Ordering> {
+        Ok(self.compare_raw(other)?.cmp(&0))
+    }
+
+    fn compare_raw(&self, other: &Self) -> Result<i32> {
+        let result = call_php(|| unsafe {
+            zend_compare(self as *const _ as *mut _, other as *const _ as *mut _)
+        })?;
+        ExecutorGlobals::check_exception()?;
+        Ok(result)
+    }
+
+    /// Adds another zval to the zval.
+    /// This works like `+` in php.
+    ///
+    /// Integer results which overflow are returned as floats, and adding two
+    /// arrays returns their union.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The right hand side of the operation.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error carrying the thrown exception when the operation is
+    /// not supported for the given values, for example a `TypeError` when
+    /// adding an array to an integer.
+    pub fn add(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            add_function(result, op1, op2)
+        })
+    }
+
+    /// Subtracts another zval from the zval.
+    /// This works like `-` in php.
+    ///
+    /// See [`add`](Self::add) for the behaviour on overflow and errors.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The right hand side of the operation.
+    pub fn sub(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            sub_function(result, op1, op2)
+        })
+    }
+
+    /// Multiplies the zval by another zval.
+    /// This works like `*` in php.
+    ///
+    /// See [`add`](Self::add) for the behaviour on overflow and errors.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The right hand side of the operation.
+    pub fn mul(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            mul_function(result, op1, op2)
+        })
+    }
+
+    /// Divides the zval by another zval.
+    /// This works like `/` in php.
+    ///
+    /// Dividing by zero returns an error carrying a `DivisionByZeroError`.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The right hand side of the operation.
+    pub fn div(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            div_function(result, op1, op2)
+        })
+    }
+
+    /// Returns the remainder of dividing the zval by another zval.
+    /// This works like `%` in php, which converts both operands to integers.
+    ///
+    /// Dividing by zero returns an error carrying a `DivisionByZeroError`.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The right hand side of the operation.
+    pub fn rem(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            mod_function(result, op1, op2)
+        })
+    }
+
+    /// Raises the zval to the power of another zval.
+    /// This works like `**` in php.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The exponent.
+    pub fn pow(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            pow_function(result, op1, op2)
+        })
+    }
+
+    /// Concatenates the string representations of the zval and another zval.
+    /// This works like `.` in php.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The right hand side of the operation.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error carrying the thrown exception when one of the values
+    /// cannot be converted to a string.
+    pub fn concat(&self, other: &Self) -> Result<Zval> {
+        self.binary_op(other, |result, op1, op2| unsafe {
+            concat_function(result, op1, op2)
+        })
+    }
+
+    fn binary_op(
+        &self,
+        other: &Self,
+        op: impl FnOnce(*mut Zval, *mut Zval, *mut Zval) -> zend_result,
+    ) -> Result<Zval> {
+        let mut result = Zval::new();
+        // The operators only write to the operands when the result is one of them.
+        let status = call_php(|| {
+            op(
+                &mut result,
+                self as *const _ as *mut _,
+                other as *const _ as *mut _,
+            )
+        })?;
+
+        ExecutorGlobals::check_exception()?;
+        if status == ZEND_RESULT_CODE_SUCCESS {
+            Ok(result)
+        } else {
+            Err(Error::ZvalConversion(self.get_type()))
+        }
+    }
+
+    /// Renders the zval as valid PHP code, in the same format as
+    /// `var_export()`. This can be used to generate PHP files which return the
+    /// value.
+    ///
+    /// Arrays and objects which contain themselves are rendered as `NULL`
+    /// where they recur, and PHP emits a warning.
+    ///
+    /// # Returns
+    ///
+    /// Returns the code as a binary-safe string, or an error carrying the
+    /// exception thrown by an error handler.
+    pub fn to_php_literal(&self) -> Result<ZBox<ZendStr>> {
+        let mut code = ZendStrBuilder::new(false);
+        call_php(|| unsafe {
+            php_var_export_ex(self as *const _ as *mut _, 1, code.as_mut_ptr())
+        })?;
+        ExecutorGlobals::check_exception()?;
+        Ok(code.build())
+    }
+
+    /// Renders the zval in the same format as `var_dump()`, without printing
+    /// it.
+    ///
+    /// Objects are rendered using their `__debugInfo()` method if they have
+    /// one, and arrays and objects which contain themselves are rendered as
+    /// `*RECURSION*` where they recur.
+    ///
+    /// # Returns
+    ///
+    /// Returns the output as a binary-safe string, or an error if the output
+    /// could not be captured or an exception was thrown.
+    pub fn dump(&self) -> Result<ZBox<ZendStr>> {
+        capture_output(|| unsafe { php_var_dump(self as *const _ as *mut _, 1) })
+    }
+
+    /// Renders the zval in the same format as `print_r()`, without printing
+    /// it.
+    ///
+    /// Arrays and objects which contain themselves are rendered as
+    /// `*RECURSION*` where they recur.
+    ///
+    /// # Returns
+    ///
+    /// Returns the output as a binary-safe string, or an error carrying the
+    /// thrown exception.
+    pub fn print_r(&self) -> Result<ZBox<ZendStr>> {
+        let output = call_php(|| unsafe {
+            ZBox::from_raw(zend_print_zval_r_to_str(self as *const _ as *mut _))
+        })?;
+        ExecutorGlobals::check_exception()?;
+        Ok(output)
+    }
+
     /// Returns true if the zval is traversable, false otherwise.
     pub fn is_traversable(&self) -> bool {
         match self.object() {
@@ -644,6 +922,30 @@ impl Zval {
     }
 }
 
+/// Calls the given function while capturing everything PHP prints, and
+/// returns the captured output.
+fn capture_output(func: impl FnOnce()) -> Result<ZBox<ZendStr>> {
+    if unsafe { php_output_start_default() } != ZEND_RESULT_CODE_SUCCESS {
+        return Err(Error::OutputCapture);
+    }
+
+    let result = call_php(func);
+    let mut output = Zval::new();
+    // The output buffer is removed even if PHP bailed out, so that it does not
+    // swallow the output of the rest of the request.
+    unsafe {
+        php_output_get_contents(&mut output);
+        php_output_discard();
+    }
+
+    result?;
+    ExecutorGlobals::check_exception()?;
+    output
+        .zend_str()
+        .map(ToOwned::to_owned)
+        .ok_or(Error::OutputCapture)
+}
+
 impl Debug for Zval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut dbg = f.debug_struct("Zval");
@@ -703,6 +1005,14 @@ impl IntoZval for Zval {
     }
 }
 
+impl FromZval<'_> for Zval {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        Some(zval.shallow_clone())
+    }
+}
+
 impl<'a> FromZval<'a> for &'a Zval {
     const TYPE: DataType = DataType::Mixed;
 
@@ -18,6 +18,29 @@ void ext_php_rs_set_known_valid_utf8(zend_string *zs) {
   }
 }
 
+zend_string *ext_php_rs_zend_string_separate(zend_string *zs) {
+  return zend_string_separate(zs, GC_FLAGS(zs) & IS_STR_PERSISTENT);
+}
+
+bool ext_php_rs_zend_array_is_list(zend_array *arr) {
+#if PHP_VERSION_ID >= 80100
+  return zend_array_is_list(arr);
+#else
+  zend_ulong expected = 0;
+  zend_ulong index;
+  zend_string *key;
+
+  ZEND_HASH_FOREACH_KEY(arr, index, key) {
+    if (key || index != expected++) {
+      return false;
+    }
+  }
+  ZEND_HASH_FOREACH_END();
+
+  return true;
+#endif
+}
+
 const char *ext_php_rs_php_build_id() { return ZEND_MODULE_BUILD_ID; }
 
 void *ext_php_rs_zend_object_alloc(size_t obj_size, zend_class_entry *ce) {
@@ -28,6 +51,50 @@ void ext_php_rs_zend_object_release(zend_object *obj) {
   zend_object_release(obj);
 }
 
+void ext_php_rs_zend_object_store_ctor_failed(zend_object *obj) {
+  zend_object_store_ctor_failed(obj);
+}
+
+void ext_php_rs_zval_make_ref(zval *zv) { ZVAL_MAKE_REF(zv); }
+
+void ext_php_rs_zval_unwrap_ref(zval *zv) {
+  if (!Z_ISREF_P(zv)) {
+    return;
+  }
+
+  if (Z_REFCOUNT_P(zv) == 1) {
+    ZVAL_UNREF(zv);
+  } else {
+    zend_reference *ref = Z_REF_P(zv);
+    GC_DELREF(ref);
+    ZVAL_COPY(zv, &ref->val);
+  }
+}
+
+zval *ext_php_rs_zend_get_class_constant(zend_class_entry *ce, zend_string *name) {
+  return zend_get_class_constant_ex(ce->name, name, zend_get_executed_scope(),
+                                    ZEND_FETCH_CLASS_SILENT);
+}
+
+// Attaches a `#[\Deprecated]` attribute carrying the message to a registered
+// function or method, which PHP 8.4 adds to the notices of deprecated
+// functions. Earlier versions have no way of attaching the message.
+void ext_php_rs_zend_function_deprecate(zend_class_entry *ce, const char *name, size_t name_len,
+                                        const char *message, size_t len) {
+#if PHP_VERSION_ID >= 80400
+  HashTable *table = ce ? &ce->function_table : CG(function_table);
+  zend_function *func = zend_hash_str_find_ptr_lc(table, name, name_len);
+  if (func == NULL) {
+    return;
+  }
+
+  zend_attribute *attr =
+      zend_add_function_attribute(func, ZSTR_KNOWN(ZEND_STR_DEPRECATED_CAPITALIZED), 1);
+  ZVAL_STR(&attr->args[0].value, zend_string_init(message, len, 1));
+  attr->args[0].name = ZSTR_KNOWN(ZEND_STR_MESSAGE);
+#endif
+}
+
 zend_executor_globals *ext_php_rs_executor_globals() {
 #ifdef ZTS
 #ifdef ZEND_ENABLE_STATIC_TSRMLS_CACHE
@@ -22,18 +22,31 @@
 #include "zend_exceptions.h"
 #include "zend_inheritance.h"
 #include "zend_interfaces.h"
+#include "zend_closures.h"
+#include "zend_generators.h"
 #include "php_variables.h"
 #include "zend_ini.h"
 #include "main/SAPI.h"
+#if PHP_VERSION_ID >= 80100
+#include "zend_enum.h"
+#endif
 
 zend_string *ext_php_rs_zend_string_init(const char *str, size_t len, bool persistent);
 void ext_php_rs_zend_string_release(zend_string *zs);
 bool ext_php_rs_is_known_valid_utf8(const zend_string *zs);
 void ext_php_rs_set_known_valid_utf8(zend_string *zs);
+zend_string *ext_php_rs_zend_string_separate(zend_string *zs);
+bool ext_php_rs_zend_array_is_list(zend_array *arr);
 
 const char *ext_php_rs_php_build_id();
 void *ext_php_rs_zend_object_alloc(size_t obj_size, zend_class_entry *ce);
 void ext_php_rs_zend_object_release(zend_object *obj);
+void ext_php_rs_zend_object_store_ctor_failed(zend_object *obj);
+void ext_php_rs_zval_make_ref(zval *zv);
+void ext_php_rs_zval_unwrap_ref(zval *zv);
+zval *ext_php_rs_zend_get_class_constant(zend_class_entry *ce, zend_string *name);
+void ext_php_rs_zend_function_deprecate(zend_class_entry *ce, const char *name, size_t name_len,
+                                        const char *message, size_t len);
 zend_executor_globals *ext_php_rs_executor_globals();
 php_core_globals *ext_php_rs_process_globals();
 sapi_globals_struct *ext_php_rs_sapi_globals();
@@ -1,15 +1,24 @@
 //! Builder and objects for creating classes in the PHP world.
 
 use crate::ffi::instanceof_function_slow;
-use crate::types::{ZendIterator, Zval};
+use crate::types::{CallableArgs, ZendIterator, Zval};
 use crate::{
     boxed::ZBox,
-    ffi::zend_class_entry,
-    flags::ClassFlags,
+    builders::CallBuilder,
+    convert::{FromZval, IntoZval},
+    error::{Error, Result},
+    ffi::{
+        ext_php_rs_zend_get_class_constant, ext_php_rs_zend_object_store_ctor_failed,
+        object_init_ex, zend_call_known_function, zend_class_constant, zend_class_entry,
+        zend_fcall_info_cache, zend_hash_str_find, zend_std_get_static_method,
+        zend_std_get_static_property, zend_update_static_property_ex, BP_VAR_R, BP_VAR_W,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::{ClassFlags, MethodFlags},
     types::{ZendObject, ZendStr},
-    zend::ExecutorGlobals,
+    zend::{call_php, ExecutorGlobals},
 };
-use std::{convert::TryInto, fmt::Debug, ops::DerefMut};
+use std::{convert::TryInto, fmt::Debug, ops::DerefMut, ptr};
 
 /// A PHP class entry.
 ///
@@ -41,6 +50,256 @@ impl ClassEntry {
         ZendObject::new(self)
     }
 
+    /// Creates a new object of the class and calls its constructor with the
+    /// given arguments, like `new` in PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to pass to the constructor, as a tuple.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an argument could not be converted, the class
+    /// cannot be instantiated (e.g. it is abstract or its constructor is not
+    /// accessible), PHP bailed out or the constructor threw an exception.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::ClassEntry;
+    ///
+    /// let ce = ClassEntry::try_find("ArrayObject").unwrap();
+    /// let obj = ce.instantiate((vec![1, 2, 3],)).unwrap();
+    /// assert_eq!(obj.get_class_name().unwrap(), "ArrayObject");
+    /// ```
+    pub fn instantiate<A: CallableArgs>(&self, args: A) -> Result<ZBox<ZendObject>> {
+        let mut args = args.into_zvals()?;
+        let args = args.as_mut();
+
+        let mut zv = Zval::new();
+        let result = call_php(|| unsafe { object_init_ex(&mut zv, self as *const _ as *mut _) })?;
+        ExecutorGlobals::check_exception()?;
+        let obj = match zv.object_mut() {
+            Some(obj) if result == ZEND_RESULT_CODE_SUCCESS => obj as *mut ZendObject,
+            _ => return Err(Error::Object),
+        };
+        zv.release();
+        // SAFETY: The zval held the only reference to the new object.
+        let mut obj = unsafe { ZBox::from_raw(obj) };
+
+        let obj_ptr: *mut ZendObject = obj.deref_mut();
+        let result = call_php(|| unsafe {
+            let ctor = match (*(*obj_ptr).handlers).get_constructor {
+                Some(get_constructor) => get_constructor(obj_ptr),
+                None => ptr::null_mut(),
+            };
+            if !ctor.is_null() {
+                let mut retval = Zval::new();
+                zend_call_known_function(
+                    ctor,
+                    obj_ptr,
+                    (*obj_ptr).ce,
+                    &mut retval,
+                    args.len() as _,
+                    args.as_mut_ptr(),
+                    ptr::null_mut(),
+                );
+            }
+        });
+        let result = result.and_then(|_| ExecutorGlobals::check_exception());
+
+        if result.is_err() {
+            // Like PHP, the destructor is not called when the constructor fails.
+            unsafe { ext_php_rs_zend_object_store_ctor_failed(obj_ptr) };
+        }
+        result.map(|_| obj)
+    }
+
+    /// Starts building a call to a static method of the class, allowing
+    /// arguments to be passed by reference or by name.
+    ///
+    /// The method is resolved like `Class::method()` in PHP: its visibility
+    /// is checked against the scope of the calling PHP code, and
+    /// `__callStatic` is used if the method does not exist.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static method.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Callable`] if the method is not static, or an
+    /// exception if it does not exist or is not accessible.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::ClassEntry;
+    ///
+    /// let ce = ClassEntry::try_find("DateTime").unwrap();
+    /// let date = ce
+    ///     .call_static("createFromFormat")
+    ///     .unwrap()
+    ///     .named("format", "Y-m-d")
+    ///     .named("datetime", "2024-01-01")
+    ///     .invoke()
+    ///     .unwrap();
+    /// assert!(date.is_object());
+    /// ```
+    pub fn call_static(&self, name: &str) -> Result<CallBuilder<'_>> {
+        let mut name = ZendStr::new(name, false);
+        let func = unsafe {
+            zend_std_get_static_method(self as *const _ as *mut _, name.deref_mut(), ptr::null())
+        };
+        ExecutorGlobals::check_exception()?;
+        // SAFETY: The function was resolved by PHP.
+        let func = unsafe { func.as_ref() }.ok_or(Error::Callable)?;
+        let flags = MethodFlags::from_bits_truncate(unsafe { func.common.fn_flags });
+
+        // SAFETY: The cache only holds pointers, and the fields that differ
+        // between PHP versions are unused.
+        let mut fcc: zend_fcall_info_cache = unsafe { std::mem::zeroed() };
+        fcc.function_handler = func as *const _ as *mut _;
+        fcc.calling_scope = self as *const _ as *mut _;
+        fcc.called_scope = self as *const _ as *mut _;
+        // The builder takes ownership of a `__callStatic` trampoline, releasing
+        // it if the method is not static.
+        let builder = CallBuilder::from_fcc(fcc, flags.contains(MethodFlags::CallViaTrampoline));
+        if !flags.contains(MethodFlags::Static) {
+            return Err(Error::Callable);
+        }
+
+        Ok(builder)
+    }
+
+    /// Reads a static property of the class, like `Class::$name` in PHP.
+    ///
+    /// The visibility of the property is checked against the scope of the
+    /// calling PHP code.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property, without the `$`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an exception if the property does not exist or is not
+    /// accessible, and [`Error::ZvalConversion`] if the value could not be
+    /// converted into `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::ClassEntry;
+    ///
+    /// let ce = ClassEntry::try_find("Config").unwrap();
+    /// let cache: Vec<String> = ce.static_property("cache").unwrap();
+    /// ```
+    pub fn static_property<T>(&self, name: &str) -> Result<T>
+    where
+        for<'a> T: FromZval<'a>,
+    {
+        let zv = self.static_property_ptr(name, BP_VAR_R)?.dereference();
+        T::from_zval(zv).ok_or_else(|| Error::ZvalConversion(zv.get_type()))
+    }
+
+    /// Writes a static property of the class, like `Class::$name = $value` in
+    /// PHP.
+    ///
+    /// The visibility of the property is checked against the scope of the
+    /// calling PHP code, and the value is coerced to the type of the property
+    /// if it has one.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property, without the `$`.
+    /// * `value` - The value to set the property to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be converted into a zval, or
+    /// an exception if the property does not exist, is not accessible or the
+    /// value does not match its type.
+    pub fn set_static_property<T: IntoZval>(&self, name: &str, value: T) -> Result<()> {
+        let mut value = value.into_zval(false)?;
+        self.static_property_ptr(name, BP_VAR_W)?;
+
+        let mut name = ZendStr::new(name, false);
+        call_php(|| unsafe {
+            zend_update_static_property_ex(self as *const _ as *mut _, name.deref_mut(), &mut value)
+        })?;
+        ExecutorGlobals::check_exception()
+    }
+
+    /// Returns the static property called `name`, checking that it is
+    /// accessible for reading or writing, depending on `access`.
+    fn static_property_ptr(&self, name: &str, access: u32) -> Result<&Zval> {
+        let mut name = ZendStr::new(name, false);
+        let zv = call_php(|| unsafe {
+            zend_std_get_static_property(self as *const _ as *mut _, name.deref_mut(), access as _)
+        })?;
+        ExecutorGlobals::check_exception()?;
+
+        // SAFETY: Static properties live until the end of the request.
+        unsafe { zv.as_ref() }.ok_or(Error::InvalidProperty)
+    }
+
+    /// Reads a constant of the class, like `Class::NAME` in PHP. This
+    /// includes the cases of enums and `Class::class`.
+    ///
+    /// The constant is resolved by PHP, which checks its visibility against
+    /// the scope of the calling PHP code, evaluates its value if it is an
+    /// expression and emits a notice if it is deprecated.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidProperty`] if the constant does not exist or is
+    /// not accessible, an exception if evaluating its value failed, and
+    /// [`Error::ZvalConversion`] if the value could not be converted into `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::ClassEntry;
+    ///
+    /// let ce = ClassEntry::try_find("DateTimeInterface").unwrap();
+    /// let atom: String = ce.constant("ATOM").unwrap();
+    /// assert_eq!(atom, "Y-m-d\\TH:i:sP");
+    /// ```
+    pub fn constant<T>(&self, name: &str) -> Result<T>
+    where
+        for<'a> T: FromZval<'a>,
+    {
+        // `Class::class` is resolved by the compiler rather than stored as a constant.
+        if name.eq_ignore_ascii_case("class") {
+            let zv = self.name().ok_or(Error::InvalidPointer)?.into_zval(false)?;
+            return T::from_zval(&zv).ok_or_else(|| Error::ZvalConversion(zv.get_type()));
+        }
+
+        let mut name = ZendStr::new(name, false);
+        let zv = call_php(|| unsafe {
+            ext_php_rs_zend_get_class_constant(self as *const _ as *mut _, name.deref_mut())
+        })?;
+        ExecutorGlobals::check_exception()?;
+
+        // SAFETY: Constants live until the class is destroyed.
+        let zv = unsafe { zv.as_ref() }.ok_or(Error::InvalidProperty)?;
+        T::from_zval(zv).ok_or_else(|| Error::ZvalConversion(zv.get_type()))
+    }
+
+    /// Returns the declaration of the class constant with the given name,
+    /// declared by the class or inherited from a parent class or interface.
+    pub(crate) fn find_constant(&self, name: &str) -> Option<&zend_class_constant> {
+        unsafe {
+            let zv = zend_hash_str_find(&self.constants_table, name.as_ptr().cast(), name.len());
+            (zv.as_ref()?.value.ptr as *const zend_class_constant).as_ref()
+        }
+    }
+
     /// Returns the class flags.
     pub fn flags(&self) -> ClassFlags {
         ClassFlags::from_bits_truncate(self.ce_flags)
@@ -95,20 +354,39 @@ impl ClassEntry {
 
     /// Returns the iterator for the class for a specific instance
     ///
-    /// Returns [`None`] if there is no associated iterator for the class.
-    pub fn get_iterator<'a>(&self, zval: &'a Zval, by_ref: bool) -> Option<&'a mut ZendIterator> {
+    /// # Errors
+    ///
+    /// Returns [`Error::ZvalConversion`] if there is no associated iterator
+    /// for the class, or an error if PHP bailed out or an exception was
+    /// thrown while creating the iterator, for example by
+    /// `IteratorAggregate::getIterator()`.
+    pub fn get_iterator(&self, zval: &Zval, by_ref: bool) -> Result<ZBox<ZendIterator>> {
         let ptr: *const Self = self;
         let zval_ptr: *const Zval = zval;
 
-        let iterator = unsafe {
-            (*ptr).get_iterator?(
+        let get_iterator = self
+            .get_iterator
+            .ok_or_else(|| Error::ZvalConversion(zval.get_type()))?;
+        let iterator = call_php(|| unsafe {
+            get_iterator(
                 ptr as *mut ClassEntry,
                 zval_ptr as *mut Zval,
                 if by_ref { 1 } else { 0 },
             )
-        };
+        })?;
+
+        if let Err(e) = ExecutorGlobals::check_exception() {
+            if !iterator.is_null() {
+                // SAFETY: The iterator was created by PHP and is owned by us.
+                drop(unsafe { ZBox::from_raw(iterator) });
+            }
+            return Err(e);
+        }
 
+        // SAFETY: The iterator was created by PHP and is owned by us.
         unsafe { iterator.as_mut() }
+            .map(|iterator| unsafe { ZBox::from_raw(iterator) })
+            .ok_or(Error::InvalidPointer)
     }
 
     pub fn name(&self) -> Option<&str> {
@@ -1,7 +1,11 @@
-use crate::ffi::{zend_execute_data, ZEND_MM_ALIGNMENT, ZEND_MM_ALIGNMENT_MASK};
+use std::ffi::CString;
+
+use crate::ffi::{
+    zend_error, zend_execute_data, E_DEPRECATED, ZEND_MM_ALIGNMENT, ZEND_MM_ALIGNMENT_MASK,
+};
 
 use crate::{
-    args::ArgParser,
+    args::{ArgParser, FastArgParser},
     class::RegisteredClass,
     types::{ZendClassObject, ZendObject, Zval},
 };
@@ -131,7 +135,7 @@ impl ExecuteData {
     ///
     /// #[php_module]
     /// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-    ///     module
+    ///     module.class::<Example>()
     /// }
     /// ```
     ///
@@ -146,6 +150,65 @@ impl ExecuteData {
         )
     }
 
+    /// Returns a [`FastArgParser`], which reads the arguments contained inside
+    /// `self` in place without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{types::Zval, zend::ExecuteData};
+    ///
+    /// #[no_mangle]
+    /// pub extern "C" fn example_fn(ex: &mut ExecuteData, retval: &mut Zval) {
+    ///     let mut parser = ex.fast_parser();
+    ///
+    ///     if parser.check_count(1, 1).is_err() {
+    ///         return;
+    ///     }
+    ///
+    ///     let a: Option<i64> = parser.next_arg();
+    ///     dbg!(a);
+    /// }
+    /// ```
+    pub fn fast_parser(&mut self) -> FastArgParser<'_> {
+        self.fast_parser_object().0
+    }
+
+    /// Returns a [`FastArgParser`], which reads the arguments contained inside
+    /// `self` in place without allocating.
+    ///
+    /// A reference to `$this` is also returned in an [`Option`], which resolves
+    /// to [`None`] if this function is not called inside a method.
+    pub fn fast_parser_object(&mut self) -> (FastArgParser<'_>, Option<&mut ZendObject>) {
+        // SAFETY: All fields of the `u2` union are the same type.
+        let n_args = unsafe { self.This.u2.num_args };
+
+        // SAFETY: The arguments of an internal function are stored contiguously in the
+        // call frame, directly after the execute data. The function definition ensures
+        // the lifetime of the slice doesn't exceed the execution data lifetime.
+        let args =
+            unsafe { std::slice::from_raw_parts_mut(self.zend_call_var_num(0), n_args as usize) };
+        let obj = self.This.object_mut();
+
+        (FastArgParser::new(args), obj)
+    }
+
+    /// Returns a [`FastArgParser`], which reads the arguments contained inside
+    /// `self` in place without allocating.
+    ///
+    /// A reference to `$this` is also returned in an [`Option`], which resolves
+    /// to [`None`] if this function is not called inside a method that belongs
+    /// to an object with type `T`.
+    pub fn fast_parser_method<T: RegisteredClass>(
+        &mut self,
+    ) -> (FastArgParser<'_>, Option<&mut ZendClassObject<T>>) {
+        let (parser, obj) = self.fast_parser_object();
+        (
+            parser,
+            obj.and_then(|obj| ZendClassObject::from_zend_obj_mut(obj)),
+        )
+    }
+
     /// Attempts to retrieve a reference to the underlying class object of the
     /// Zend object.
     ///
@@ -169,7 +232,7 @@ impl ExecuteData {
     ///
     /// #[php_module]
     /// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
-    ///     module
+    ///     module.class::<Example>()
     /// }
     /// ```
     pub fn get_object<T: RegisteredClass>(&mut self) -> Option<&mut ZendClassObject<T>> {
@@ -201,6 +264,55 @@ impl ExecuteData {
         unsafe { self.func.as_ref() }
     }
 
+    /// Raises an `E_DEPRECATED` notice for the function that is being called,
+    /// worded like the notices PHP raises for its own deprecated functions,
+    /// e.g. `Function foo() is deprecated, use bar() instead`.
+    ///
+    /// If the notice is turned into an exception by an error handler, the
+    /// exception is left pending and the caller should return without doing
+    /// anything else, which can be checked with
+    /// [`ExecutorGlobals::has_exception`].
+    ///
+    /// # Parameters
+    ///
+    /// * `message` - The deprecation message appended to the notice. May be
+    ///   empty.
+    ///
+    /// [`ExecutorGlobals::has_exception`]: crate::zend::ExecutorGlobals::has_exception
+    pub fn trigger_deprecation(&self, message: &str) {
+        let func = match self.function() {
+            Some(func) => func,
+            None => return,
+        };
+        // SAFETY: All function types share the common header.
+        let (name, scope) = unsafe {
+            (
+                func.common.function_name.as_ref(),
+                func.common.scope.as_ref(),
+            )
+        };
+        let name = name.and_then(|name| name.as_str().ok()).unwrap_or_default();
+
+        let mut notice = match scope.and_then(|scope| scope.name()) {
+            Some(class) => format!("Method {class}::{name}() is deprecated"),
+            None => format!("Function {name}() is deprecated"),
+        };
+        if !message.is_empty() {
+            notice.push_str(", ");
+            notice.push_str(message);
+        }
+
+        if let Ok(notice) = CString::new(notice) {
+            unsafe {
+                zend_error(
+                    E_DEPRECATED as _,
+                    super::FORMAT_STR.as_ptr().cast(),
+                    notice.as_ptr(),
+                )
+            };
+        }
+    }
+
     /// Attempt to retrieve the previous execute data on the call stack.
     pub fn previous(&self) -> Option<&Self> {
         unsafe { self.prev_execute_data.as_ref() }
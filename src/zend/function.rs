@@ -1,8 +1,10 @@
 //! Builder for creating functions and methods in PHP.
 
-use std::{fmt::Debug, os::raw::c_char, ptr};
+use std::{ffi::CString, fmt::Debug, os::raw::c_char, ptr};
 
 use crate::{
+    args::ArgInfo,
+    builders::CallBuilder,
     convert::IntoZvalDyn,
     error::Result,
     ffi::{
@@ -13,7 +15,7 @@ use crate::{
     types::Zval,
 };
 
-use super::ClassEntry;
+use super::{call_php, ClassEntry};
 
 /// A Zend function entry.
 pub type FunctionEntry = zend_function_entry;
@@ -50,6 +52,40 @@ impl FunctionEntry {
     pub fn into_raw(self) -> *mut Self {
         Box::into_raw(Box::new(self))
     }
+
+    /// Frees the name and argument information of a function entry built by
+    /// a [`FunctionBuilder`].
+    ///
+    /// # Safety
+    ///
+    /// The entry must have been returned by [`FunctionBuilder::build`], and
+    /// no function registered from it may still be in use by PHP, as the
+    /// names of its arguments are not copied when registering.
+    ///
+    /// [`FunctionBuilder`]: crate::builders::FunctionBuilder
+    /// [`FunctionBuilder::build`]: crate::builders::FunctionBuilder::build
+    pub(crate) unsafe fn free(self) {
+        drop(CString::from_raw(self.fname as *mut c_char));
+
+        let arg_info: Box<[ArgInfo]> = Box::from_raw(ptr::slice_from_raw_parts_mut(
+            self.arg_info as *mut ArgInfo,
+            self.num_args as usize + 1,
+        ));
+        for (i, info) in arg_info.iter().enumerate() {
+            // The first entry is the header, whose name holds the number of
+            // required arguments.
+            if i > 0 {
+                drop(CString::from_raw(info.name as *mut c_char));
+            }
+            if !info.default_value.is_null() {
+                drop(CString::from_raw(info.default_value as *mut c_char));
+            }
+            // Only class types point to a (class name) string.
+            if !info.type_.ptr.is_null() {
+                drop(CString::from_raw(info.type_.ptr as *mut c_char));
+            }
+        }
+    }
 }
 
 pub type Function = zend_function;
@@ -119,7 +155,7 @@ impl Function {
             .collect::<Result<Vec<_>>>()?;
         let packed = params.into_boxed_slice();
 
-        unsafe {
+        call_php(|| unsafe {
             zend_call_known_function(
                 self as *const _ as *mut _,
                 std::ptr::null_mut(),
@@ -129,8 +165,34 @@ impl Function {
                 packed.as_ptr() as *mut _,
                 std::ptr::null_mut(),
             )
-        };
+        })?;
 
         Ok(retval)
     }
+
+    /// Starts building a call to the function, allowing arguments to be
+    /// passed by reference or by name. Methods are called statically, on the
+    /// class that declares them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::Function;
+    ///
+    /// let str_pad = Function::try_from_function("str_pad").unwrap();
+    /// let result = str_pad
+    ///     .call()
+    ///     .arg("5")
+    ///     .arg(3)
+    ///     .named("pad_type", 0) // STR_PAD_LEFT
+    ///     .named("pad_string", "0")
+    ///     .invoke()
+    ///     .unwrap();
+    /// assert_eq!(result.str(), Some("005"));
+    /// ```
+    pub fn call(&self) -> CallBuilder<'_> {
+        // SAFETY: All function types share the common header.
+        let scope = unsafe { self.common.scope };
+        CallBuilder::new(self as *const _ as *mut _, ptr::null_mut(), scope)
+    }
 }
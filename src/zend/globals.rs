@@ -9,6 +9,7 @@ use std::str;
 use parking_lot::{const_rwlock, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::boxed::ZBox;
+use crate::error::{Error, Result};
 use crate::exception::PhpResult;
 #[cfg(php82)]
 use crate::ffi::zend_atomic_bool_store;
@@ -137,6 +138,19 @@ impl ExecutorGlobals {
         Some(unsafe { ZBox::from_raw(exception_ptr.as_mut()?) })
     }
 
+    /// Takes the last PHP exception captured by the interpreter, returning it
+    /// as an [`Error::Exception`].
+    ///
+    /// This function requires the executor globals to be mutably held, which
+    /// could lead to a deadlock if the globals are already borrowed immutably
+    /// or mutably.
+    pub(crate) fn check_exception() -> Result<()> {
+        match Self::take_exception() {
+            Some(e) => Err(Error::Exception(e)),
+            None => Ok(()),
+        }
+    }
+
     /// Checks if the executor globals contain an exception.
     pub fn has_exception() -> bool {
         !Self::get().exception.is_null()
@@ -149,11 +163,7 @@ impl ExecutorGlobals {
     /// could lead to a deadlock if the globals are already borrowed immutably
     /// or mutably.
     pub fn throw_if_exception() -> PhpResult<()> {
-        if let Some(e) = Self::take_exception() {
-            Err(crate::error::Error::Exception(e).into())
-        } else {
-            Ok(())
-        }
+        Ok(Self::check_exception()?)
     }
 
     /// Request an interrupt of the PHP VM. This will call the registered
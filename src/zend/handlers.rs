@@ -2,7 +2,7 @@ use std::{ffi::c_void, mem::MaybeUninit, os::raw::c_int, ptr};
 
 use crate::{
     class::RegisteredClass,
-    exception::PhpResult,
+    exception::{catch_panic, PhpResult},
     ffi::{
         std_object_handlers, zend_is_true, zend_object_handlers, zend_object_std_dtor,
         zend_std_get_properties, zend_std_has_property, zend_std_read_property,
@@ -10,6 +10,7 @@ use crate::{
     },
     flags::ZvalTypeFlags,
     types::{ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
+    zend::{handler_scope, resume_bailout},
 };
 
 /// A set of functions associated with a PHP class.
@@ -57,10 +58,13 @@ impl ZendObjectHandlers {
             .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
             .expect("Invalid object pointer given for `free_obj`");
 
-        // Manually drop the object as we don't want to free the underlying memory.
-        ptr::drop_in_place(&mut obj.obj);
+        // Manually drop the object as we don't want to free the underlying memory. A
+        // panic while dropping can't be thrown as the object is being freed, so it is
+        // only reported by the panic hook.
+        let _ = handler_scope(|| catch_panic(|| ptr::drop_in_place(&mut obj.obj)));
 
-        zend_object_std_dtor(object)
+        zend_object_std_dtor(object);
+        resume_bailout();
     }
 
     unsafe extern "C" fn read_property<T: RegisteredClass>(
@@ -102,14 +106,20 @@ impl ZendObjectHandlers {
             })
         }
 
-        match internal::<T>(object, member, type_, cache_slot, rv) {
+        let rv = match handler_scope(|| {
+            catch_panic(|| internal::<T>(object, member, type_, cache_slot, rv))
+        })
+        .and_then(|res| res)
+        {
             Ok(rv) => rv,
             Err(e) => {
                 let _ = e.throw();
                 (*rv).set_null();
                 rv
             }
-        }
+        };
+        resume_bailout();
+        rv
     }
 
     unsafe extern "C" fn write_property<T: RegisteredClass>(
@@ -146,13 +156,19 @@ impl ZendObjectHandlers {
             })
         }
 
-        match internal::<T>(object, member, value, cache_slot) {
+        let rv = match handler_scope(|| {
+            catch_panic(|| internal::<T>(object, member, value, cache_slot))
+        })
+        .and_then(|res| res)
+        {
             Ok(rv) => rv,
             Err(e) => {
                 let _ = e.throw();
                 value
             }
-        }
+        };
+        resume_bailout();
+        rv
     }
 
     unsafe extern "C" fn get_properties<T: RegisteredClass>(
@@ -188,9 +204,12 @@ impl ZendObjectHandlers {
             .or_else(|| Some(ZendHashTable::new().into_raw()))
             .expect("Failed to get property hashtable");
 
-        if let Err(e) = internal::<T>(object, props) {
+        if let Err(e) =
+            handler_scope(|| catch_panic(|| internal::<T>(object, props))).and_then(|res| res)
+        {
             let _ = e.throw();
         }
+        resume_bailout();
 
         props
     }
@@ -274,12 +293,18 @@ impl ZendObjectHandlers {
             ))
         }
 
-        match internal::<T>(object, member, has_set_exists, cache_slot) {
+        let rv = match handler_scope(|| {
+            catch_panic(|| internal::<T>(object, member, has_set_exists, cache_slot))
+        })
+        .and_then(|res| res)
+        {
             Ok(rv) => rv,
             Err(e) => {
                 let _ = e.throw();
                 0
             }
-        }
+        };
+        resume_bailout();
+        rv
     }
 }
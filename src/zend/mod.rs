@@ -36,10 +36,11 @@ pub use module::ModuleEntry;
 pub use streams::*;
 #[cfg(feature = "embed")]
 pub(crate) use try_catch::panic_wrapper;
-pub use try_catch::{bailout, try_catch, try_catch_first};
+pub use try_catch::{bailout, bailout_pending, resume_bailout, try_catch, try_catch_first};
+pub(crate) use try_catch::{call_php, handler_scope};
 
-// Used as the format string for `php_printf`.
-const FORMAT_STR: &[u8] = b"%s\0";
+// Used as the format string for `php_printf` and `zend_error`.
+pub(crate) const FORMAT_STR: &[u8] = b"%s\0";
 
 /// Prints to stdout using the `php_printf` function.
 ///
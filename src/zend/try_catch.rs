@@ -1,10 +1,21 @@
+use crate::error::{Error, Result as PhpResult};
+use crate::exception::reset_panic_backtrace;
 use crate::ffi::{
     ext_php_rs_zend_bailout, ext_php_rs_zend_first_try_catch, ext_php_rs_zend_try_catch,
 };
+use std::cell::Cell;
 use std::ffi::c_void;
-use std::panic::{catch_unwind, resume_unwind, RefUnwindSafe};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe, RefUnwindSafe};
 use std::ptr::null_mut;
 
+thread_local! {
+    /// Whether a bailout caught while calling into PHP is waiting to be resumed.
+    static PENDING_BAILOUT: Cell<bool> = const { Cell::new(false) };
+
+    /// Number of handlers called by PHP currently running on this thread.
+    static HANDLER_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
 #[derive(Debug)]
 pub struct CatchError;
 
@@ -13,6 +24,7 @@ pub(crate) unsafe extern "C" fn panic_wrapper<R, F: FnMut() -> R + RefUnwindSafe
 ) -> *const c_void {
     // we try to catch panic here so we correctly shutdown php if it happens
     // mandatory when we do assert on test as other test would not run correctly
+    reset_panic_backtrace();
     let panic = catch_unwind(|| (*(ctx as *mut F))());
 
     Box::into_raw(Box::new(panic)) as *mut c_void
@@ -85,6 +97,78 @@ fn do_try_catch<R, F: FnMut() -> R + RefUnwindSafe>(func: F, first: bool) -> Res
     }
 }
 
+/// Calls into PHP, catching a bailout (e.g. a fatal error) raised by the PHP
+/// code rather than letting it jump over the Rust frames of the caller.
+///
+/// Inside a handler called by PHP (see [`handler_scope`]), a caught bailout is
+/// returned as [`Error::Bailout`], allowing the Rust code to unwind normally
+/// and drop its values. The bailout is then pending until it is resumed by
+/// [`resume_bailout`] once control returns to PHP, which the handlers
+/// generated by the library do. While a bailout is pending, no further calls
+/// into PHP are made.
+///
+/// Outside of a handler there is nothing to resume the bailout, so it is
+/// raised again straight away.
+pub(crate) fn call_php<R>(func: impl FnOnce() -> R) -> PhpResult<R> {
+    if bailout_pending() {
+        return Err(Error::Bailout);
+    }
+
+    let depth = HANDLER_DEPTH.with(|depth| depth.get());
+    let mut func = Some(func);
+    let slot = AssertUnwindSafe(&mut func);
+    let result = try_catch(move || (slot.0.take().expect("PHP call made twice"))());
+
+    result.map_err(|_| {
+        // The bailout may have jumped over handlers without leaving their scope.
+        HANDLER_DEPTH.with(|cur| cur.set(depth));
+        if depth == 0 {
+            // SAFETY: No handler is running, so the bailout would never be resumed.
+            unsafe { bailout() };
+        }
+        PENDING_BAILOUT.with(|pending| pending.set(true));
+        Error::Bailout
+    })
+}
+
+/// Runs the body of a handler called by PHP. Bailouts caught by [`call_php`]
+/// in the body are left pending, to be resumed with [`resume_bailout`] once
+/// the body has returned.
+pub(crate) fn handler_scope<R>(body: impl FnOnce() -> R) -> R {
+    struct Scope;
+
+    impl Drop for Scope {
+        fn drop(&mut self) {
+            HANDLER_DEPTH.with(|depth| depth.set(depth.get() - 1));
+        }
+    }
+
+    HANDLER_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _scope = Scope;
+    body()
+}
+
+/// Returns whether a bailout caught while calling into PHP is waiting to be
+/// resumed with [`resume_bailout`].
+pub fn bailout_pending() -> bool {
+    PENDING_BAILOUT.with(|pending| pending.get())
+}
+
+/// Resumes a bailout caught while calling into PHP, if any. Handlers called by
+/// PHP should call this function after all of their values have been dropped,
+/// right before returning to PHP.
+///
+/// # Safety
+///
+/// If a bailout is pending, this function does not return, and jumps to the
+/// last try catch block of PHP. The destructors of the values held by the
+/// caller are not run. See [`bailout`].
+pub unsafe fn resume_bailout() {
+    if PENDING_BAILOUT.with(|pending| pending.replace(false)) {
+        bailout();
+    }
+}
+
 /// Trigger a bailout
 ///
 /// This function will stop the execution of the current script
@@ -0,0 +1,18 @@
+<?php
+
+declare(strict_types=1);
+
+function strict_number_signed(mixed $a): int
+{
+    return test_number_signed($a);
+}
+
+function strict_number_float(mixed $a): float
+{
+    return test_number_float($a);
+}
+
+function strict_str(mixed $a): string
+{
+    return test_str($a);
+}
@@ -0,0 +1,14 @@
+<?php
+
+require('_utils.php');
+
+assert(test_args(1) === '1:10:');
+assert(test_args(1, 2) === '1:2:');
+assert(test_args(1, 2, 'three') === '1:2:three');
+
+// Too few and too many arguments
+assert_exception_thrown(fn () => test_args());
+assert_exception_thrown(fn () => test_args(1, 2, 'three', 4));
+
+// Invalid value for a required argument
+assert_exception_thrown(fn () => test_args('one'));
@@ -0,0 +1,4 @@
+#[test]
+fn args_works() {
+    assert!(crate::integration::run_php("args.php"));
+}
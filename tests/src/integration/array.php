@@ -25,3 +25,79 @@ assert(array_key_exists('c', $assoc));
 assert(in_array('1', $assoc));
 assert(in_array('2', $assoc));
 assert(in_array('3', $assoc));
+
+// Tests `reverse` matches `array_reverse`, including arrays with holes and
+// string keys which are not valid UTF-8
+$holes = [1, 2, 3, 4];
+unset($holes[1]);
+$binary = ["a\xff" => 1, 5 => 2];
+foreach ([[], [1], [1, 2, 'a' => 3], [5 => 'x', 'a' => 'y', 3 => 'z'], $holes, $binary] as $array) {
+    assert(test_array_reverse($array) === array_reverse($array));
+}
+
+// Tests `ksort` matches `ksort`
+$array = ['b' => 1, 'a' => 2, 10 => 3, 9 => 4];
+$sorted = $array;
+ksort($sorted);
+assert(test_array_ksort($array) === $sorted);
+
+// Tests `sort_by` keeps keys and equal values in order
+assert(test_array_sort([3, 1, 2, 1]) === [1 => 1, 3 => 1, 2 => 2, 0 => 3]);
+
+// Tests `retain` keeps the keys of the retained values
+assert(test_array_retain_odd([1, 2, 3, 4, 5]) === [0 => 1, 2 => 3, 4 => 5]);
+
+// Tests `splice` matches `array_splice`
+$cases = [
+    [[1, 2, 3, 4], 1, 2, [0]],
+    [[1, 2, 3], 0, 0, ['a', 'b']],
+    [[1, 2, 3], 2, 5, []],
+    [[1, 2, 3], 5, 1, [9]],
+    [['a' => 1, 7 => 2, 'b' => 3, 9 => 4], 1, 2, [5, 6, 7]],
+    [$holes, 1, 1, []],
+    [$binary, 0, 1, [3]],
+];
+foreach ($cases as [$array, $offset, $length, $replacement]) {
+    $spliced = $array;
+    $removed = array_splice($spliced, $offset, $length, $replacement);
+    assert(test_array_splice($array, $offset, $length, $replacement) === [$spliced, $removed]);
+}
+
+// Tests `merge` matches `array_merge`
+$cases = [
+    [[5 => 1, 'a' => 2], [5 => 1, 'a' => 3]],
+    [[], [3 => 'x']],
+    [$holes, $binary],
+    [['a' => 1, 'b' => 2], ['b' => 3, 4]],
+];
+foreach ($cases as [$a, $b]) {
+    assert(test_array_merge($a, $b) === array_merge($a, $b));
+}
+
+// Tests `remove_index` and `insert`
+assert(test_array_remove_first([7, 8]) === [1 => 8, 'removed' => 7]);
+
+// Tests the entry API
+assert(test_array_count_values(['x', 'y', 'x']) === ['x' => 2, 'y' => 1]);
+
+// Tests arrays are separated before being modified
+$original = [1];
+$pushed = test_array_separate($original);
+assert($original === [1]);
+assert($pushed === [1, 'pushed']);
+
+// Tests typed views over packed arrays
+assert(test_packed_sum([1, 2, 3, 4]) === 10);
+assert(test_packed_sum([]) === 0);
+assert(test_packed_scale([1.5, 2.0], 2.0) === [3.0, 4.0]);
+
+$holes = [1, 2, 3];
+unset($holes[1]);
+foreach ([[1, 'a'], [1.0, 2.0], ['a' => 1], [1 => 1, 0 => 2], $holes] as $invalid) {
+    try {
+        test_packed_sum($invalid);
+        assert(false, 'Invalid packed array accepted');
+    } catch (ValueError $e) {
+        assert($e->getMessage() === 'test_packed_sum(): Argument #1 ($values) must be a packed list of type int');
+    }
+}
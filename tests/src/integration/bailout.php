@@ -0,0 +1,11 @@
+<?php
+
+register_shutdown_function(function () {
+    echo 'dropped: ', test_bailout_dropped() ? 'yes' : 'no', PHP_EOL;
+});
+
+test_bailout(function () {
+    trigger_error('bailing out', E_USER_ERROR);
+});
+
+echo 'unreachable', PHP_EOL;
@@ -0,0 +1,11 @@
+#[test]
+fn bailout_works() {
+    let output = crate::integration::php_output("bailout.php");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // The fatal error ends the script, after the Rust values have been dropped.
+    assert_eq!(output.status.code(), Some(255), "{stdout}");
+    assert!(stdout.contains("Fatal error: bailing out"), "{stdout}");
+    assert!(stdout.contains("dropped: yes"), "{stdout}");
+    assert!(!stdout.contains("unreachable"), "{stdout}");
+}
@@ -3,3 +3,115 @@
 require('_utils.php');
 
 assert(test_callable(fn (string $a) => $a, 'test') === 'test');
+
+// Typed callables
+$rows = [];
+$checked = test_callable_typed(function (int $i, string $row) use (&$rows): bool {
+    $rows[] = $row;
+    return $i % 2 === 0;
+}, 3);
+assert($checked === [true, false, true]);
+assert($rows === ['row 0', 'row 1', 'row 2']);
+
+class Rows
+{
+    public function check(int $i, string $row): bool
+    {
+        return $i > 0;
+    }
+
+    public static function all(int $i, string $row): bool
+    {
+        return true;
+    }
+
+    public function __call(string $name, array $args): bool
+    {
+        return $args[0] === 1;
+    }
+}
+
+assert(test_callable_typed([new Rows, 'check'], 2) === [false, true]);
+assert(test_callable_typed('Rows::all', 2) === [true, true]);
+assert(test_callable_typed([new Rows, 'magic'], 3) === [false, true, false]);
+
+try {
+    test_callable_typed(function () {
+        throw new RuntimeException('row failed');
+    }, 1);
+    assert(false, 'Exception was not rethrown');
+} catch (Exception $e) {
+    assert($e->getPrevious() instanceof RuntimeException);
+}
+
+// The return value must convert into `bool`.
+assert_exception_thrown(fn () => test_callable_typed(fn () => 'yes', 1));
+assert_exception_thrown(fn () => test_callable_typed('not_a_function', 1));
+
+// Call builder
+$value = 1;
+$result = test_call_by_ref(function (&$v) {
+    $v++;
+    return 'incremented';
+}, $value);
+assert($result === ['incremented', 2]);
+assert($value === 1);
+
+assert(test_call_by_ref('sort', [3, 1, 2]) === [true, [1, 2, 3]]);
+assert(test_call_by_ref(fn ($v) => $v, 'unchanged') === ['unchanged', 'unchanged']);
+
+assert(test_call_preg_match('/(\d+)/', 'abc 123') === [['123', 4], ['123', 4]]);
+assert(test_call_preg_match('/(\d+)/', 'abc') === []);
+assert(test_call_str_pad('abc') === '---abc');
+
+class Calls
+{
+    public function sum(int $a, int $b = 0, int $c = 10): int
+    {
+        return $a + $b + $c;
+    }
+
+    public static function diff(int $a, int $b): int
+    {
+        return $a - $b;
+    }
+
+    public static function fail(int $a, int $b): void
+    {
+        throw new RuntimeException('call failed');
+    }
+
+    private function secret(int $a, int $b): int
+    {
+        return $a * $b;
+    }
+
+    public function callSecret(): int
+    {
+        return test_call_method($this, 'secret');
+    }
+}
+
+class MagicCalls
+{
+    public function __call(string $name, array $args): string
+    {
+        return $name . ':' . implode(',', array_keys($args));
+    }
+}
+
+assert(test_call_method(new Calls, 'sum') === 13);
+assert(test_call_static('Calls', 'diff') === -1);
+assert_exception_thrown(fn () => test_call_method(new Calls, 'missing'));
+// Methods are resolved from the scope of the calling code.
+assert_exception_thrown(fn () => test_call_method(new Calls, 'secret'));
+assert((new Calls)->callSecret() === 2);
+assert(test_call_method(new MagicCalls, 'anything') === 'anything:0,b');
+// Instance methods cannot be called statically.
+assert_exception_thrown(fn () => test_call_static('Calls', 'sum'));
+try {
+    test_call_static('Calls', 'fail');
+    assert(false, 'Exception was not rethrown');
+} catch (Exception $e) {
+    assert($e->getPrevious() instanceof RuntimeException);
+}
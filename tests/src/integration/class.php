@@ -19,3 +19,128 @@ assert($class->getNumber() === 2023);
 assert($class->boolean);
 $class->boolean = false;
 assert($class->boolean === false);
+
+// Instantiating classes from Rust calls their constructor.
+class Point
+{
+    public static $destructed = 0;
+
+    public function __construct(public int $x, public int $y = 0)
+    {
+        if ($x < 0) {
+            throw new InvalidArgumentException('negative');
+        }
+    }
+
+    public function __destruct()
+    {
+        self::$destructed++;
+    }
+}
+
+$point = test_instantiate('Point', 1, '2');
+assert($point instanceof Point);
+assert($point->x === 1 && $point->y === 2);
+unset($point);
+assert(Point::$destructed === 1);
+
+try {
+    test_instantiate('Point', -1, 0);
+    assert(false, 'Exception was not rethrown');
+} catch (Exception $e) {
+    assert($e->getPrevious() instanceof InvalidArgumentException);
+}
+// The destructor is not called when the constructor throws.
+assert(Point::$destructed === 1);
+
+$date = test_instantiate('DateTimeImmutable', '2024-01-02', null);
+assert($date->format('Y-m-d') === '2024-01-02');
+
+abstract class Shape
+{
+}
+
+class Hidden
+{
+    private function __construct()
+    {
+    }
+}
+
+assert_exception_thrown(fn () => test_instantiate('Shape', 1, 2));
+assert_exception_thrown(fn () => test_instantiate('Hidden', 1, 2));
+assert_exception_thrown(fn () => test_instantiate('Countable', 1, 2));
+
+assert(test_new_with(21) === 42);
+assert_exception_thrown(fn () => test_new_with('not a number'));
+
+// Static members of PHP classes.
+class Settings
+{
+    const VERSION = '1.2';
+    const FULL_VERSION = self::VERSION . '.0';
+    private const SECRET = 'hidden';
+
+    public static array $cache = [];
+    public static int $hits = 0;
+    private static $token = 'token';
+
+    private static function internal(int $a, int $b): string
+    {
+        return 'internal';
+    }
+
+    public static function __callStatic(string $name, array $args): array
+    {
+        return [$name, $args];
+    }
+
+    public static function fromInside(): array
+    {
+        return [
+            test_class_constant('Settings', 'SECRET'),
+            test_static_property('Settings', 'token'),
+            test_call_static('Settings', 'internal'),
+        ];
+    }
+}
+
+assert(test_class_constant('Settings', 'VERSION') === '1.2');
+assert(test_class_constant('Settings', 'FULL_VERSION') === '1.2.0');
+assert_exception_thrown(fn () => test_class_constant('Settings', 'SECRET'));
+assert_exception_thrown(fn () => test_class_constant('Settings', 'MISSING'));
+assert(test_class_constant('Settings', 'class') === 'Settings');
+
+// Constants are read from the class itself, which may not be registered under
+// its name.
+$anonymous = new class {
+    const VALUE = 'anonymous';
+    const FULL_VALUE = self::VALUE . '!';
+};
+assert(test_object_constant($anonymous, 'FULL_VALUE') === 'anonymous!');
+assert_exception_thrown(fn () => test_object_constant($anonymous, 'MISSING'));
+assert(test_object_constant($anonymous, 'class') === get_class($anonymous));
+
+assert(test_static_property('Settings', 'hits') === 0);
+test_set_static_property('Settings', 'cache', ['a' => 1]);
+assert(Settings::$cache === ['a' => 1]);
+// Values are coerced to the type of the property.
+test_set_static_property('Settings', 'hits', '5');
+assert(Settings::$hits === 5);
+assert(test_static_property('Settings', 'hits') === 5);
+assert_exception_thrown(fn () => test_set_static_property('Settings', 'hits', 'many'));
+assert_exception_thrown(fn () => test_set_static_property('Settings', 'cache', 1));
+assert_exception_thrown(fn () => test_static_property('Settings', 'token'));
+assert_exception_thrown(fn () => test_set_static_property('Settings', 'token', 'stolen'));
+assert_exception_thrown(fn () => test_static_property('Settings', 'missing'));
+
+// Private members are accessible from inside the class.
+assert(Settings::fromInside() === ['hidden', 'token', 'internal']);
+// `__callStatic` is used for missing and inaccessible methods.
+assert(test_call_static('Settings', 'missing') === ['missing', [1, 'b' => 2]]);
+assert(test_call_static('Settings', 'internal') === ['internal', [1, 'b' => 2]]);
+
+if (PHP_VERSION_ID >= 80100) {
+    eval('enum Suit: string { case Hearts = "H"; case Spades = "S"; }');
+    assert(test_class_constant('Suit', 'Hearts') === Suit::Hearts);
+}
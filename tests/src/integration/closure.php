@@ -6,6 +6,12 @@ $v = test_closure();
 
 // Closure
 assert($v('works') === 'works');
+assert($v instanceof Closure);
+assert(is_callable($v));
+assert(Closure::fromCallable($v) === $v);
+if (PHP_VERSION_ID >= 80100) {
+    assert(eval('return $v(...);') === $v);
+}
 
 // Closure once
 $closure = test_closure_once('test');
@@ -18,5 +24,84 @@ function take(\stdClass $rs): void { }
 try {
     take($closure);
 } catch (\TypeError $e) {
-    assert(str_starts_with($e->getMessage(), 'take(): Argument #1 ($rs) must be of type stdClass, RustClosure given, called in '));
-}
\ No newline at end of file
+    assert(str_starts_with($e->getMessage(), 'take(): Argument #1 ($rs) must be of type stdClass, Closure given, called in '));
+}
+
+function take_closure(\Closure $c): \Closure { return $c; }
+
+assert(take_closure($v) === $v);
+
+// Signature
+$typed = test_closure_typed();
+$f = new ReflectionFunction($typed);
+assert($f->getName() === '{closure}');
+assert($f->isInternal());
+assert($f->getNumberOfParameters() === 2);
+assert($f->getNumberOfRequiredParameters() === 2);
+[$a, $b] = $f->getParameters();
+assert($a->getName() === 'a' && (string) $a->getType() === 'int');
+assert($b->getName() === 'b' && (string) $b->getType() === '?string');
+assert((string) $f->getReturnType() === 'string');
+
+assert($typed(1, 'a') === '1a');
+assert($typed(2, null) === '2');
+
+try {
+    $typed(1);
+    assert(false, 'Missing argument was accepted');
+} catch (\ArgumentCountError $e) {
+}
+
+// Variadic
+$variadic = test_closure_variadic();
+$f = new ReflectionFunction($variadic);
+assert($f->isVariadic());
+assert($f->getNumberOfRequiredParameters() === 0);
+assert($variadic() === 0);
+assert($variadic(1, 'a', [], null) === 4);
+assert($variadic(...[1, 2, 3]) === 3);
+
+// Any number of declared arguments
+$args = test_closure_args();
+$f = new ReflectionFunction($args);
+assert($f->getNumberOfParameters() === 13);
+assert($f->getNumberOfRequiredParameters() === 13);
+assert($args(...range(1, 13)) === 91);
+
+// Binding
+$this_ = (new ReflectionFunction($typed))->getClosureThis();
+assert($this_ instanceof RustClosure);
+$bound = $typed->bindTo($this_);
+assert($bound instanceof Closure && $bound !== $typed);
+assert($bound(3, 'b') === '3b');
+assert(Closure::bind($typed, $this_, RustClosure::class)(4, 'c') === '4c');
+
+class Foo {}
+
+// As for closures created from methods, the closure cannot be bound to another
+// object or scope.
+assert(@$typed->bindTo(new Foo) === null);
+assert(@$typed->bindTo(null) === null);
+assert(@Closure::bind($typed, $this_, Foo::class) === null);
+assert(@$typed->call(new Foo, 5, 'd') === null);
+
+// Bound to the object of another Rust closure
+$other = $typed->bindTo((new ReflectionFunction($v))->getClosureThis());
+assert_exception_thrown(fn () => $other(6, 'e'));
+unset($other, $this_);
+
+// Lifetime
+$counter = test_closure_counter();
+assert($counter() === 1);
+assert(test_closure_dropped() === 0);
+unset($counter);
+assert(test_closure_dropped() === 1);
+
+$counter = test_closure_counter();
+$copy = $counter->bindTo((new ReflectionFunction($counter))->getClosureThis());
+assert($copy() === 1);
+unset($counter);
+assert(test_closure_dropped() === 1);
+assert($copy() === 2);
+unset($copy);
+assert(test_closure_dropped() === 2);
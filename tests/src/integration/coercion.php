@@ -0,0 +1,134 @@
+<?php
+
+require('_utils.php');
+
+function assert_type_error(callable $callback, string $message): void
+{
+    try {
+        $callback();
+    } catch (TypeError $e) {
+        assert($e->getMessage() === $message, $e->getMessage());
+        return;
+    }
+    throw new Exception("TypeError was not thrown", 255);
+}
+
+// Tests scalars are converted following PHP's weak typing rules
+assert(test_number_signed('42') === 42);
+assert(test_number_signed(' 42') === 42);
+assert(test_number_signed(42.0) === 42);
+assert(test_number_signed(true) === 1);
+assert(test_number_float(2) === 2.0);
+assert(test_number_float('1.5') === 1.5);
+assert(test_str(42) === '42');
+assert(test_string(1.5) === '1.5');
+assert(test_bool(1) === true);
+assert(test_bool('') === false);
+assert(test_nullable(5) === '5');
+assert(test_nullable(null) === null);
+
+// Tests array keys keep integers and convert numeric strings like PHP does
+assert(test_array_key(5) === 5);
+assert(test_array_key('5') === 5);
+assert(test_array_key('05') === '05');
+assert(test_array_key('a') === 'a');
+
+// Tests `null` is converted for arguments which are not nullable, which is
+// deprecated since PHP 8.1
+$deprecations = [];
+set_error_handler(function (int $errno, string $errstr) use (&$deprecations) {
+    $deprecations[] = $errstr;
+    return true;
+}, E_DEPRECATED);
+assert(test_number_signed(null) === 0);
+assert(test_str(null) === '');
+assert(test_bool(null) === false);
+restore_error_handler();
+assert($deprecations === (PHP_VERSION_ID >= 80100 ? [
+    'test_number_signed(): Passing null to parameter #1 ($a) of type int is deprecated',
+    'test_str(): Passing null to parameter #1 ($a) of type string is deprecated',
+    'test_bool(): Passing null to parameter #1 ($a) of type bool is deprecated',
+] : []));
+
+$stringable = new class {
+    public function __toString(): string
+    {
+        return 'stringable';
+    }
+};
+assert(test_str($stringable) === 'stringable');
+assert(test_string($stringable) === 'stringable');
+
+// Tests exceptions thrown while converting are not replaced
+$throwing = new class {
+    public function __toString(): string
+    {
+        throw new RuntimeException('from __toString');
+    }
+};
+try {
+    test_str($throwing);
+    assert(false, 'Exception was not thrown');
+} catch (RuntimeException $e) {
+    assert($e->getMessage() === 'from __toString');
+}
+
+// Tests invalid values throw PHP's `TypeError`
+assert_type_error(
+    fn () => test_number_signed('abc'),
+    'test_number_signed(): Argument #1 ($a) must be of type int, string given'
+);
+assert_type_error(
+    fn () => test_str([]),
+    'test_str(): Argument #1 ($a) must be of type string, array given'
+);
+assert_type_error(
+    fn () => test_bool(new stdClass()),
+    'test_bool(): Argument #1 ($a) must be of type bool, stdClass given'
+);
+assert_type_error(
+    fn () => test_nullable([]),
+    'test_nullable(): Argument #1 ($a) must be of type ?string, array given'
+);
+
+// Tests integers out of range throw PHP's `ValueError`
+function assert_value_error(callable $callback, string $message): void
+{
+    try {
+        $callback();
+    } catch (ValueError $e) {
+        assert($e->getMessage() === $message, $e->getMessage());
+        return;
+    }
+    throw new Exception("ValueError was not thrown", 255);
+}
+
+assert_value_error(
+    fn () => test_number_signed(2 ** 40),
+    'test_number_signed(): Argument #1 ($a) must be between -2147483648 and 2147483647'
+);
+assert_value_error(
+    fn () => test_number_unsigned(-1),
+    'test_number_unsigned(): Argument #1 ($a) must be between 0 and 4294967295'
+);
+
+// Tests nothing is converted when the caller uses strict types
+require('_coercion_strict.php');
+
+assert_type_error(
+    fn () => strict_number_signed('42'),
+    'test_number_signed(): Argument #1 ($a) must be of type int, string given'
+);
+assert_type_error(
+    fn () => strict_str(42),
+    'test_str(): Argument #1 ($a) must be of type string, int given'
+);
+assert_type_error(
+    fn () => strict_str($stringable),
+    'test_str(): Argument #1 ($a) must be of type string, class@anonymous given'
+);
+assert(strict_number_float(2) === 2.0);
+assert_type_error(
+    fn () => strict_number_signed(null),
+    'test_number_signed(): Argument #1 ($a) must be of type int, null given'
+);
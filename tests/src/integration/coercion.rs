@@ -0,0 +1,4 @@
+#[test]
+fn coercion_works() {
+    assert!(crate::integration::run_php("coercion.php"));
+}
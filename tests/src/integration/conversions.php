@@ -0,0 +1,42 @@
+<?php
+
+// Tests tuples and integer map keys
+assert(test_tuple_pairs([['b', 2], ['a', 1]]) === [1 => 'a', 2 => 'b']);
+assert(test_int_keys([3 => 1.5, '7' => 2.0]) === [3 => 3.0, 7 => 4.0]);
+assert(test_unsigned_keys([3 => 1, 0 => 2]) === [1 => 2, 4 => 1]);
+
+// Tests fixed size arrays
+assert(test_fixed_array([1, 2, 3]) === [3, 2, 1]);
+
+// Tests sets and deques
+assert(test_sets(['b', 'a', 'b']) === [['a', 'b'], 2, ['a', 'b', 'b']]);
+
+// Tests `Cow<str>`
+assert(test_cow('abc') === 'ABC');
+assert(test_cow('') === 'empty');
+
+// Tests `LossyString` replaces invalid UTF-8
+assert(test_lossy_string('abc') === 'ABC');
+assert(test_lossy_string("a\xffb") === "A\u{FFFD}B");
+
+// Tests values of the wrong shape throw a `ValueError`
+$pairs = 'test_tuple_pairs(): Argument #1 ($pairs) must only contain values of type array which must be a list of type [string, int]';
+$invalid = [
+    [fn () => test_tuple_pairs([['a']]), $pairs],
+    [fn () => test_tuple_pairs([[1, 'a']]), $pairs],
+    [fn () => test_tuple_pairs([['b' => 'a', 'c' => 1]]), $pairs],
+    [fn () => test_tuple_pairs([[1 => 1, 0 => 'a']]), $pairs],
+    [fn () => test_fixed_array([1, 2]), 'test_fixed_array(): Argument #1 ($a) must be a list of 3 values of type int'],
+    [fn () => test_fixed_array([5 => 1, 9 => 2, 11 => 3]), 'test_fixed_array(): Argument #1 ($a) must be a list of 3 values of type int'],
+    [fn () => test_unsigned_keys([-1 => 1]), 'test_unsigned_keys(): Argument #1 ($map) must only contain keys and values of the expected types'],
+    [fn () => test_int_keys(['a' => 1.0]), 'test_int_keys(): Argument #1 ($map) must only contain keys and values of the expected types'],
+    [fn () => test_cow("a\xffb"), 'test_cow(): Argument #1 ($a) must be a valid UTF-8 string'],
+];
+foreach ($invalid as [$call, $message]) {
+    try {
+        $call();
+        assert(false, 'Invalid value accepted');
+    } catch (ValueError $e) {
+        assert($e->getMessage() === $message, $e->getMessage());
+    }
+}
@@ -0,0 +1,4 @@
+#[test]
+fn conversions_works() {
+    assert!(crate::integration::run_php("conversions.php"));
+}
@@ -0,0 +1,44 @@
+<?php
+
+require('_utils.php');
+
+// Functions are only flagged as deprecated from PHP 8.4, earlier versions
+// emit the notices from the functions themselves.
+$flagged = PHP_VERSION_ID >= 80400;
+assert((new ReflectionFunction('test_deprecated'))->isDeprecated() === $flagged);
+assert((new ReflectionFunction('test_deprecated_silent'))->isDeprecated() === $flagged);
+assert((new ReflectionMethod('TestDeprecated', 'oldRun'))->isDeprecated() === $flagged);
+assert((new ReflectionMethod('TestDeprecated', 'oldStop'))->isDeprecated() === $flagged);
+
+$deprecations = [];
+set_error_handler(function (int $errno, string $errstr) use (&$deprecations) {
+    $deprecations[] = $errstr;
+    return true;
+}, E_DEPRECATED);
+
+assert(test_deprecated('test') === 'test');
+assert(test_deprecated_silent());
+assert(TestDeprecated::oldRun() === 20);
+assert(TestDeprecated::oldStop() === 10);
+assert($deprecations === [
+    'Function test_deprecated() is deprecated, use test_str() instead',
+    'Function test_deprecated_silent() is deprecated',
+    'Method TestDeprecated::oldRun() is deprecated, use run() instead',
+    'Method TestDeprecated::oldStop() is deprecated',
+]);
+
+$deprecations = [];
+assert(TestDeprecated::LIMIT === 20);
+assert(TestDeprecated::OLD_LIMIT === 10);
+assert($deprecations === (PHP_VERSION_ID >= 80400 ? ['Constant TestDeprecated::OLD_LIMIT is deprecated'] : []));
+
+restore_error_handler();
+
+// A deprecation turned into an exception stops the function from running.
+set_error_handler(function (int $errno, string $errstr) {
+    throw new Exception($errstr);
+}, E_DEPRECATED);
+
+assert_exception_thrown(fn () => test_deprecated('test'));
+
+restore_error_handler();
@@ -0,0 +1,4 @@
+#[test]
+fn deprecated_works() {
+    assert!(crate::integration::run_php("deprecated.php"));
+}
@@ -0,0 +1,51 @@
+<?php
+
+require('_utils.php');
+
+assert(test_exception('none') === 0);
+
+assert(is_subclass_of('ExtPhpRs\Tests\TestException', 'Exception'));
+assert(is_subclass_of('ExtPhpRs\Tests\NotFoundException', 'ExtPhpRs\Tests\TestException'));
+assert(is_subclass_of('ExtPhpRs\Tests\DeniedException', 'ExtPhpRs\Tests\TestException'));
+
+try {
+    test_exception('not_found');
+    assert(false);
+} catch (\ExtPhpRs\Tests\NotFoundException $e) {
+    assert($e->getMessage() === '/tmp/missing not found');
+    assert($e->getCode() === 404);
+    assert($e->path === '/tmp/missing');
+    assert($e->attempts === 3);
+}
+
+try {
+    test_exception('denied');
+    assert(false);
+} catch (\ExtPhpRs\Tests\TestException $e) {
+    assert($e instanceof \ExtPhpRs\Tests\DeniedException);
+    assert($e->getMessage() === 'permission denied');
+    assert($e->getCode() === 0);
+}
+
+try {
+    test_exception('invalid');
+    assert(false);
+} catch (\ValueError $e) {
+    assert($e->getMessage() === 'invalid: invalid');
+    assert($e->getCode() === 2);
+}
+
+try {
+    test_exception('io');
+    assert(false);
+} catch (\ExtPhpRs\Tests\IoException $e) {
+    assert($e->getMessage() === 'io failed');
+    assert($e->getPrevious() instanceof \Exception);
+    assert($e->getPrevious()->getMessage() === 'disk full');
+    assert($e->getPrevious()->getPrevious() === null);
+}
+
+$chain = test_exception_chain(function () {
+    throw new \RuntimeException('outer', 0, new \LogicException('inner'));
+});
+assert($chain === ['RuntimeException: outer', 'LogicException: inner']);
@@ -9,3 +9,113 @@ assert(iter_next_back([], 2) === [null, null]);
 assert(iter_next_back([1, 2 ,3], 2) === [2, 3, 0, 1, 1, 2, null, null]);
 var_dump(iter_next_back([1, 2, 3, 4, 5], 3));
 assert(iter_next_back([1, 2, 3, 4, 5], 3) === [4, 5, 0, 1, 1, 2, 3, 4, 2, 3, null, null, null]);
+
+// Iterables yield owned key/value pairs.
+$array = ['a' => 1, 2];
+$ref = &$array['a'];
+assert(iter_pairs($array) === ['a', 1, 0, 2]);
+
+function pairs_generator() {
+    yield 'first' => str_repeat('x', 3);
+    $key = new stdClass;
+    yield $key => [1, 2];
+}
+
+$pairs = iter_pairs(pairs_generator());
+assert($pairs[0] === 'first' && $pairs[1] === 'xxx');
+assert($pairs[2] instanceof stdClass && $pairs[3] === [1, 2]);
+
+class Numbers implements IteratorAggregate
+{
+    public function getIterator(): Iterator
+    {
+        return new ArrayIterator(['one' => 1, 'two' => 2]);
+    }
+}
+
+assert(iter_pairs(new Numbers) === ['one', 1, 'two', 2]);
+
+class FailingAggregate implements IteratorAggregate
+{
+    public function getIterator(): Iterator
+    {
+        throw new RuntimeException('getIterator failed');
+    }
+}
+
+// An exception thrown while creating the iterator is rethrown as is.
+try {
+    iter_pairs(new FailingAggregate);
+    assert(false, 'Exception was not rethrown');
+} catch (RuntimeException $e) {
+    assert($e->getMessage() === 'getIterator failed');
+}
+
+// Exceptions thrown while iterating are rethrown.
+function failing_generator() {
+    yield 1;
+    throw new RuntimeException('generator failed');
+}
+
+class FailingIterator implements Iterator
+{
+    private $position = 0;
+
+    public function current(): mixed
+    {
+        if ($this->position > 0) {
+            throw new RuntimeException('current failed');
+        }
+        return $this->position;
+    }
+
+    public function key(): mixed
+    {
+        return $this->position;
+    }
+
+    public function next(): void
+    {
+        $this->position++;
+    }
+
+    public function rewind(): void
+    {
+        $this->position = 0;
+    }
+
+    public function valid(): bool
+    {
+        return $this->position < 3;
+    }
+}
+
+foreach ([failing_generator(), new FailingIterator] as $iterable) {
+    try {
+        iter_pairs($iterable);
+        assert(false, 'Exception was not rethrown');
+    } catch (Exception $e) {
+        assert($e->getPrevious() instanceof RuntimeException);
+    }
+}
+
+// A generator which was already run cannot be rewound.
+$generator = pairs_generator();
+$generator->next();
+try {
+    iter_pairs($generator);
+    assert(false, 'Exception was not rethrown');
+} catch (Exception $e) {
+    assert($e->getPrevious() instanceof Exception);
+}
+
+// Generators can be sent values, and return a value.
+function send_generator() {
+    $received = yield 1;
+    yield $received;
+    yield 3;
+    return 'done';
+}
+
+// Sending a value moves the iterator forward.
+assert(iter_generator_send(send_generator()) === [1, 'sent', 1, 3, 'done']);
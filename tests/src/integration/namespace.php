@@ -0,0 +1,10 @@
+<?php
+
+require('_utils.php');
+
+assert(function_exists('ExtPhpRs\Tests\test_namespaced_function'));
+assert(!function_exists('test_namespaced_function'));
+assert(\ExtPhpRs\Tests\test_namespaced_function() === 'namespaced');
+
+assert(defined('ExtPhpRs\Tests\TEST_NAMESPACED_CONSTANT'));
+assert(\ExtPhpRs\Tests\TEST_NAMESPACED_CONSTANT === 42);
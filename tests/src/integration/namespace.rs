@@ -0,0 +1,4 @@
+#[test]
+fn namespace_works() {
+    assert!(crate::integration::run_php("namespace.php"));
+}
@@ -0,0 +1,73 @@
+<?php
+
+require('_utils.php');
+
+$pairs = [
+    [1, 1.0],
+    [1, 2],
+    ['abc', 'abd'],
+    ['10', '1e1'],
+    [0, 'a'],
+    [null, false],
+    [[1, 2], [1, 3]],
+    [['a' => 1], ['b' => 1]],
+    [NAN, 1.0],
+    [new stdClass(), new ArrayObject()],
+];
+
+// Tests `loose_eq` matches `==`
+foreach ($pairs as [$a, $b]) {
+    assert(test_zval_loose_eq($a, $b) === ($a == $b));
+    assert(test_zval_loose_eq($b, $a) === ($b == $a));
+}
+
+// Tests `compare` matches `<=>`
+foreach ($pairs as [$a, $b]) {
+    assert(test_zval_compare($a, $b) === ($a <=> $b));
+    assert(test_zval_compare($b, $a) === ($b <=> $a));
+}
+
+// Tests `add` matches `+`, returning floats on overflow and the union of arrays
+assert(test_zval_add(1, 2) === 3);
+assert(test_zval_add(PHP_INT_MAX, 1) === PHP_INT_MAX + 1);
+assert(test_zval_add([1, 2], [5 => 3]) === [1, 2, 5 => 3]);
+assert_exception_thrown(fn () => test_zval_add([], 1));
+
+// Tests `sub` matches `-`
+assert(test_zval_sub('10', 2.5) === 7.5);
+assert_exception_thrown(fn () => test_zval_sub('abc', 1));
+
+// Tests `mul` matches `*`
+assert(test_zval_mul(PHP_INT_MAX, 2) === PHP_INT_MAX * 2);
+assert_exception_thrown(fn () => test_zval_mul(new stdClass(), 2));
+
+// Tests `div` matches `/`
+assert(test_zval_div(6, 3) === 2);
+assert(test_zval_div(7, 2) === 3.5);
+assert_exception_thrown(fn () => test_zval_div(1, 0));
+
+// Tests `rem` matches `%`
+assert(test_zval_rem(-7, 3) === -1);
+assert_exception_thrown(fn () => test_zval_rem(1, 0));
+
+// Tests `pow` matches `**`
+assert(test_zval_pow(2, 10) === 1024);
+assert(test_zval_pow(2, -1) === 0.5);
+
+// Tests `concat` matches `.`
+assert(test_zval_concat('a', 1.5) === 'a1.5');
+assert_exception_thrown(fn () => test_zval_concat('a', new stdClass()));
+
+// Tests the error thrown by PHP is kept as the previous exception
+$errors = [
+    TypeError::class => fn () => test_zval_add([], 1),
+    DivisionByZeroError::class => fn () => test_zval_div(1, 0),
+];
+foreach ($errors as $class => $call) {
+    try {
+        $call();
+        assert(false, "{$class} should have been thrown");
+    } catch (Exception $e) {
+        assert($e->getPrevious() instanceof $class);
+    }
+}
@@ -0,0 +1,4 @@
+#[test]
+fn operators_works() {
+    assert!(crate::integration::run_php("operators.php"));
+}
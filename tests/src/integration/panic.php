@@ -0,0 +1,22 @@
+<?php
+
+require('_utils.php');
+
+try {
+    test_panic('something went wrong');
+    assert(false);
+} catch (\Error $e) {
+    assert($e->getMessage() === 'Rust panic: something went wrong');
+}
+
+$closure = test_panic_closure();
+
+try {
+    $closure();
+    assert(false);
+} catch (\Error $e) {
+    assert($e->getMessage() === 'Rust panic: closure panicked');
+}
+
+// The extension keeps working after a panic.
+assert(test_str('still alive') === 'still alive');
@@ -0,0 +1,4 @@
+#[test]
+fn panic_works() {
+    assert!(crate::integration::run_php("panic.php"));
+}
@@ -0,0 +1,101 @@
+<?php
+
+class TestPhpValue
+{
+    public int $uninitialized;
+    public array $list = [1, 2.5];
+    protected string $name = 'value';
+    private ?TestPhpValue $child = null;
+
+    public function setChild(TestPhpValue $child): void
+    {
+        $this->child = $child;
+    }
+}
+
+class TestPhpValueChild extends TestPhpValue
+{
+    private string $child = 'shadowed';
+}
+
+abstract class TestPhpValueAbstract
+{
+}
+
+// Tests scalars and arrays are copied deeply
+$values = [
+    null,
+    true,
+    10,
+    -2.5,
+    "bin\0ary",
+    [],
+    [5 => 'a', 'b' => ['c' => [null, false]]],
+];
+foreach ($values as $value) {
+    assert(test_php_value($value) === $value);
+}
+
+// Tests references are followed
+$inner = [1, 2];
+$ref = ['inner' => &$inner];
+assert(test_php_value($ref) === ['inner' => [1, 2]]);
+
+// Tests objects are recreated with their class and properties
+$obj = new TestPhpValue();
+$obj->setChild(new TestPhpValue());
+$copy = test_php_value($obj);
+assert($copy instanceof TestPhpValue);
+assert($copy !== $obj);
+assert($copy == $obj);
+
+// Tests private properties of parent classes are kept apart
+$child = new TestPhpValueChild();
+$child->setChild(new TestPhpValue());
+$copy = test_php_value($child);
+assert($copy instanceof TestPhpValueChild);
+assert($copy == $child);
+assert((array) $copy === (array) $child);
+
+$std = (object) ['a' => 1, 'b' => (object) ['c' => 'd']];
+$copy = test_php_value($std);
+assert($copy instanceof stdClass);
+assert($copy == $std);
+
+// Tests enum cases are converted back into the same case
+if (PHP_VERSION_ID >= 80100) {
+    eval('enum TestPhpValueSuit: string { case Hearts = "H"; case Spades = "S"; }');
+    assert(test_php_value(TestPhpValueSuit::Spades) === TestPhpValueSuit::Spades);
+}
+
+// Tests abstract classes are not instantiated
+try {
+    test_php_value_class('TestPhpValueAbstract');
+    assert(false, 'Abstract class instantiated');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Class TestPhpValueAbstract cannot be instantiated.');
+}
+
+// Tests values which contain themselves are cut off
+$self = ['a' => 1];
+$self['self'] = &$self;
+assert(test_php_value($self) === ['a' => 1, 'self' => null]);
+
+$cycle = new stdClass();
+$cycle->self = $cycle;
+assert(test_php_value($cycle)->self === null);
+
+// Tests equality and hashing
+assert(test_php_value_eq(['a' => 1, 'b' => 2], ['b' => 2, 'a' => 1]));
+assert(test_php_value_eq(0.0, -0.0));
+assert(test_php_value_eq(new TestPhpValue(), new TestPhpValue()));
+assert(!test_php_value_eq(1, '1'));
+assert(!test_php_value_eq([1], [1, 2]));
+assert(!test_php_value_eq(new TestPhpValue(), new stdClass()));
+
+// Tests keys which are not valid UTF-8 are rejected instead of panicking
+try {
+    test_php_value(["\xff" => 1]);
+    assert(false, 'Invalid key accepted');
+} catch (TypeError $e) {
+}
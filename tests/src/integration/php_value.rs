@@ -0,0 +1,4 @@
+#[test]
+fn php_value_works() {
+    assert!(crate::integration::run_php("php_value.php"));
+}
@@ -0,0 +1,12 @@
+<?php
+
+require('_utils.php');
+
+assert(test_registered_function() === 'registered');
+assert(TEST_REGISTERED_CONSTANT === 'registered');
+
+$obj = new TestRegisteredClass(21);
+assert($obj->doubled() === 42);
+assert(TestRegisteredClass::DOUBLE === 2);
+
+assert(TEST_STARTUP_AFTER_REGISTRATION === true);
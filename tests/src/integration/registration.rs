@@ -0,0 +1,4 @@
+#[test]
+fn registration_works() {
+    assert!(crate::integration::run_php("registration.php"));
+}
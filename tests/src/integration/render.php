@@ -0,0 +1,72 @@
+<?php
+
+class TestRender
+{
+    public $list = [1, 2.5, null];
+    protected $name = "bin\0ary";
+    private $flag = true;
+}
+
+class TestRenderDebugInfo
+{
+    public function __debugInfo(): array
+    {
+        return ['debug' => 'info'];
+    }
+}
+
+function var_dump_str(mixed $value): string
+{
+    ob_start();
+    var_dump($value);
+    return ob_get_clean();
+}
+
+$values = [
+    null,
+    false,
+    -10,
+    0.1,
+    1e100,
+    "quote ' and \\ and \0",
+    [],
+    [5 => 'a', 'b' => ['c' => [null, false]]],
+    new TestRender(),
+    new TestRenderDebugInfo(),
+    (object) ['a' => 1],
+];
+
+// Tests `to_php_literal` matches `var_export`
+foreach ($values as $value) {
+    assert(test_zval_export($value) === var_export($value, true));
+}
+
+// Tests exported values are valid PHP code
+$array = [5 => 'a', 'b' => ['c' => [null, 0.1, "\0"]]];
+assert(eval('return ' . test_zval_export($array) . ';') === $array);
+
+// Tests `dump` matches `var_dump`, and captures the output instead of
+// printing it
+foreach ($values as $value) {
+    assert(test_zval_dump($value) === var_dump_str($value));
+}
+ob_start();
+test_zval_dump([1, 2, 3]);
+assert(ob_get_clean() === '');
+
+// Tests `print_r` matches `print_r`
+foreach ($values as $value) {
+    assert(test_zval_print_r($value) === print_r($value, true));
+}
+
+// Tests values which contain themselves are cut off
+$self = ['a' => 1];
+$self['self'] = &$self;
+assert(@test_zval_export($self) === @var_export($self, true));
+assert(test_zval_dump($self) === var_dump_str($self));
+assert(test_zval_print_r($self) === print_r($self, true));
+
+$cycle = new stdClass();
+$cycle->self = $cycle;
+assert(str_contains(test_zval_dump($cycle), '*RECURSION*'));
+assert(str_contains(test_zval_print_r($cycle), '*RECURSION*'));
@@ -0,0 +1,4 @@
+#[test]
+fn render_works() {
+    assert!(crate::integration::run_php("render.php"));
+}
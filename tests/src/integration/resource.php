@@ -0,0 +1,31 @@
+<?php
+
+require('_utils.php');
+
+// Tests resources are created with the registered type
+$counter = test_resource_new(5);
+assert(is_resource($counter));
+assert(get_resource_type($counter) === 'test counter');
+assert(test_resource_incr($counter) === 6);
+assert(test_resource_incr($counter) === 7);
+
+// Tests resources of other types are rejected
+$stream = fopen('php://memory', 'r');
+assert_exception_thrown(fn () => test_resource_incr($stream));
+assert_exception_thrown(fn () => test_resource_incr(5));
+fclose($stream);
+
+// Tests the value is dropped when the last reference is released
+$dropped = test_resource_dropped();
+$copy = $counter;
+unset($counter);
+assert(test_resource_dropped() === $dropped);
+unset($copy);
+assert(test_resource_dropped() === $dropped + 1);
+
+// Tests closing a resource takes its value, leaving a closed resource
+$counter = test_resource_new(1);
+assert(test_resource_close($counter) === 1);
+assert(test_resource_dropped() === $dropped + 2);
+assert(get_resource_type($counter) === 'Unknown');
+assert_exception_thrown(fn () => test_resource_incr($counter));
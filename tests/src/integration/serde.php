@@ -0,0 +1,60 @@
+<?php
+
+// Tests structs, which are serialized as `stdClass` objects
+$points = test_serde_points([
+    ['x' => 1, 'y' => 2, 'label' => 'a'],
+    (object) ['x' => 3, 'y' => 4],
+]);
+assert(count($points) === 2);
+assert($points[0] instanceof stdClass);
+assert($points[0]->x === 1 && $points[0]->y === 2 && $points[0]->label === 'a');
+assert($points[1]->x === 3 && $points[1]->label === null);
+
+// Tests enums
+assert(test_serde_shape('Empty') === 'Empty');
+assert(test_serde_shape(['Circle' => 1.5]) === ['Circle' => 1.5]);
+$rect = test_serde_shape(['Rect' => ['w' => 1.0, 'h' => 2.0]]);
+assert($rect['Rect']->w === 1.0 && $rect['Rect']->h === 2.0);
+
+// Tests maps, where numeric string keys become integer keys
+assert(test_serde_map(['b' => 1, 10 => 2]) === [10 => 2, 'b' => 1]);
+
+// Tests nested values
+assert(test_serde_tree(['children' => [['children' => []], ['children' => [['children' => []]]]]]) === 3);
+
+// Tests values that cannot be deserialized throw a `ValueError` with the
+// reason given by serde
+$invalid = [
+    ['test_serde_shape(): Argument #1 ($shape)', fn () => test_serde_shape('Square')],
+    ['test_serde_shape(): Argument #1 ($shape)', fn () => test_serde_shape(['Circle' => 'big'])],
+    ['test_serde_points(): Argument #1 ($points)', fn () => test_serde_points([['x' => 'one', 'y' => 2]])],
+    ['test_serde_map(): Argument #1 ($map)', fn () => test_serde_map(["a\xff" => 1])],
+];
+foreach ($invalid as [$prefix, $call]) {
+    try {
+        $call();
+        assert(false, 'Invalid value accepted');
+    } catch (ValueError $e) {
+        assert(str_starts_with($e->getMessage(), "$prefix could not be deserialized: "), $e->getMessage());
+    }
+}
+try {
+    test_serde_points([['y' => 2]]);
+    assert(false, 'Invalid value accepted');
+} catch (ValueError $e) {
+    assert(str_contains($e->getMessage(), 'missing field `x`'), $e->getMessage());
+}
+
+// Tests recursive values are rejected instead of overflowing the stack
+$tree = ['children' => []];
+$tree['children'][] = &$tree;
+$node = new stdClass();
+$node->children = [$node];
+foreach ([$tree, $node] as $recursive) {
+    try {
+        test_serde_tree($recursive);
+        assert(false, 'Recursive value accepted');
+    } catch (ValueError $e) {
+        assert(str_contains($e->getMessage(), 'nested deeper than 128 levels'), $e->getMessage());
+    }
+}
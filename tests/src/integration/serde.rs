@@ -0,0 +1,4 @@
+#[test]
+fn serde_works() {
+    assert!(crate::integration::run_php("serde.php"));
+}
@@ -4,3 +4,28 @@ require('_utils.php');
 
 assert(test_str('abc') === 'abc');
 assert(test_string('abc') === 'abc');
+
+// Tests strings which are not valid UTF-8 are only accepted as bytes
+$invalid = "\xff\x00\xfe";
+assert_exception_thrown(fn () => test_str($invalid));
+assert(test_bytes($invalid, 'ab') === "ba\xfe\x00\xff");
+assert(test_bytes('', '') === '');
+assert(test_bytes(12, '') === '21');
+assert(test_bytes_owned($invalid) === "\xfe\x00\xff");
+assert(test_bytes_owned('') === '');
+
+// Tests OS strings and paths
+assert(test_os_str('dir', $invalid) === "dir$invalid");
+assert(test_path('/tmp', 'file.txt') === '/tmp/file.txt');
+assert(test_path('/tmp', '/etc') === '/etc');
+
+// Tests strings are built in place
+assert(test_str_builder([], 0) === '');
+assert(test_str_builder(['a', 'bc'], 2) === "a,bc,\0;a,bc,\0;");
+$built = test_str_builder([str_repeat('x', 1000)], 2000);
+assert(strlen($built) === 2000 * 1003);
+assert(str_starts_with($built, str_repeat('x', 1000) . ",\0;x"));
+
+// Tests shared strings are copied before being modified
+assert(test_str_make_mut('abc') === ['abc', '_BC', true]);
+assert(test_str_make_mut('') === ['', '', true]);
@@ -13,10 +13,14 @@ const TYPES = [
     'test_array' => [['array'], 'array'],
     'test_array_assoc' => [['array'], 'array'],
     'test_binary' => [['string'], 'string'],
+    'test_bytes' => [['string', 'string'], 'string'],
+    'test_bytes_owned' => [['string'], 'string'],
+    'test_os_str' => [['string', 'string'], 'string'],
+    'test_path' => [['string', 'string'], 'string'],
     'test_nullable' => [['?string'], '?string'],
     'test_object' => [['object'], 'object'],
-    'test_closure' => [[], 'RustClosure'],
-    'test_closure_once' => [['string'], 'RustClosure'],
+    'test_closure' => [[], 'Closure'],
+    'test_closure_once' => [['string'], 'Closure'],
     'test_callable' => [['callable', 'string'], 'mixed']
 ];
 
@@ -1,12 +1,30 @@
 #![cfg_attr(windows, feature(abi_vectorcall))]
 use ext_php_rs::{
+    args::Arg,
     binary::Binary,
     boxed::ZBox,
+    flags::DataType,
     prelude::*,
-    types::{ArrayKey, ZendHashTable, ZendObject, Zval},
-    zend::ProcessGlobals,
+    rc::PhpRc,
+    serde::Serde,
+    types::{
+        ArrayKey, Bytes, Callable, Iterable, LossyString, PackedView, PhpValue, Resource,
+        ZendHashTable, ZendIterator, ZendLong, ZendObject, ZendStr, ZendStrBuilder, Zval,
+    },
+    zend::{ClassEntry, Function, ProcessGlobals},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    ffi::{OsStr, OsString},
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
 };
-use std::collections::HashMap;
 
 #[php_function]
 pub fn test_str(a: &str) -> &str {
@@ -48,6 +66,168 @@ pub fn test_array_assoc(a: HashMap<String, String>) -> HashMap<String, String> {
     a
 }
 
+#[php_function]
+pub fn test_array_reverse(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut arr = a.to_owned();
+    arr.reverse();
+    arr
+}
+
+#[php_function]
+pub fn test_array_ksort(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut arr = a.to_owned();
+    arr.ksort();
+    arr
+}
+
+#[php_function]
+pub fn test_array_sort(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut arr = a.to_owned();
+    arr.sort_by(|a, b| a.long().cmp(&b.long()));
+    arr
+}
+
+#[php_function]
+pub fn test_array_retain_odd(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut arr = a.to_owned();
+    arr.retain(|_, val| val.long().map_or(false, |n| n % 2 == 1));
+    arr
+}
+
+#[php_function]
+pub fn test_array_splice(
+    a: &ZendHashTable,
+    offset: usize,
+    length: usize,
+    replacement: &ZendHashTable,
+) -> (ZBox<ZendHashTable>, ZBox<ZendHashTable>) {
+    let mut arr = a.to_owned();
+    let removed = arr.splice(
+        offset,
+        length,
+        replacement.values().map(Zval::shallow_clone),
+    );
+    (arr, removed)
+}
+
+#[php_function]
+pub fn test_array_merge(a: &ZendHashTable, b: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut arr = a.to_owned();
+    arr.merge(b);
+    arr
+}
+
+#[php_function]
+pub fn test_array_remove_first(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut arr = a.to_owned();
+    let first = arr.remove_index(0);
+    arr.insert("removed", first.and_then(|zv| zv.long()))
+        .unwrap();
+    arr
+}
+
+#[php_function]
+pub fn test_array_count_values(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut counts = ZendHashTable::new();
+    for val in a.values() {
+        let key = ArrayKey::String(val.string().unwrap_or_default());
+        counts
+            .entry(key)
+            .and_modify(|count| count.set_long(count.long().unwrap_or_default() + 1))
+            .or_insert(1)
+            .unwrap();
+    }
+    counts
+}
+
+#[php_function]
+pub fn test_array_separate(a: &Zval) -> Zval {
+    let mut a = a.shallow_clone();
+    if let Some(arr) = a.array_mut() {
+        arr.push("pushed").unwrap();
+    }
+    a
+}
+
+#[php_function]
+pub fn test_packed_sum(values: PackedView<ZendLong>) -> ZendLong {
+    values.iter().sum()
+}
+
+#[php_function]
+pub fn test_packed_scale(values: PackedView<f64>, factor: f64) -> ZBox<ZendHashTable> {
+    let scaled: Vec<f64> = values.iter().map(|v| v * factor).collect();
+    ZendHashTable::from_packed_slice(&scaled).unwrap()
+}
+
+#[php_function]
+pub fn test_tuple_pairs(pairs: Vec<(String, i64)>) -> BTreeMap<i64, String> {
+    pairs.into_iter().map(|(name, id)| (id, name)).collect()
+}
+
+#[php_function]
+pub fn test_fixed_array(a: [i64; 3]) -> [i64; 3] {
+    [a[2], a[1], a[0]]
+}
+
+#[php_function]
+pub fn test_sets(values: Vec<String>) -> (BTreeSet<String>, usize, VecDeque<String>) {
+    let unique: HashSet<&String> = values.iter().collect();
+    let count = unique.len();
+    let mut deque: VecDeque<String> = values.iter().cloned().collect();
+    deque.rotate_left(1);
+    (values.into_iter().collect(), count, deque)
+}
+
+#[php_function]
+pub fn test_int_keys(map: HashMap<i64, Box<f64>>) -> BTreeMap<i64, Arc<f64>> {
+    map.into_iter()
+        .map(|(k, v)| (k, Arc::new(*v * 2.0)))
+        .collect()
+}
+
+#[php_function]
+pub fn test_unsigned_keys(map: HashMap<u64, i64>) -> BTreeMap<usize, i64> {
+    map.into_iter().map(|(k, v)| (k as usize + 1, v)).collect()
+}
+
+#[php_function]
+pub fn test_cow(a: Cow<str>) -> Cow<'static, str> {
+    if a.is_empty() {
+        Cow::Borrowed("empty")
+    } else {
+        Cow::Owned(a.to_uppercase())
+    }
+}
+
+#[php_function]
+pub fn test_lossy_string(a: LossyString) -> String {
+    a.to_uppercase()
+}
+
+#[php_function]
+pub fn test_bytes(a: &[u8], b: Box<[u8]>) -> Box<[u8]> {
+    a.iter().chain(b.iter()).rev().copied().collect()
+}
+
+#[php_function]
+pub fn test_bytes_owned(mut a: Bytes) -> Bytes {
+    a.reverse();
+    a
+}
+
+#[php_function]
+pub fn test_os_str(a: OsString, b: &OsStr) -> OsString {
+    let mut joined = a;
+    joined.push(b);
+    joined
+}
+
+#[php_function]
+pub fn test_path(a: PathBuf, b: &Path) -> PathBuf {
+    a.join(b)
+}
+
 #[php_function]
 pub fn test_binary(a: Binary<u32>) -> Binary<u32> {
     a
@@ -58,6 +238,11 @@ pub fn test_nullable(a: Option<String>) -> Option<String> {
     a
 }
 
+#[php_function(defaults(b = 10))]
+pub fn test_args(a: i64, b: i64, c: Option<String>) -> String {
+    format!("{a}:{b}:{}", c.unwrap_or_default())
+}
+
 #[php_function]
 pub fn test_object(a: &mut ZendObject) -> &mut ZendObject {
     a
@@ -107,11 +292,134 @@ pub fn test_closure_once(a: String) -> Closure {
     Closure::wrap_once(Box::new(move || a) as Box<dyn FnOnce() -> String>)
 }
 
+#[php_function]
+pub fn test_closure_typed() -> Closure {
+    Closure::wrap(
+        Box::new(|a: i64, b: Option<String>| format!("{a}{}", b.unwrap_or_default()))
+            as Box<dyn Fn(i64, Option<String>) -> String>,
+    )
+}
+
+#[php_function]
+pub fn test_closure_variadic() -> Closure {
+    Closure::wrap_variadic(|args: &[&Zval]| args.len() as i64)
+}
+
+#[php_function]
+pub fn test_closure_args() -> Closure {
+    let args = ('a'..='m')
+        .map(|name| Arg::new(name, DataType::Long))
+        .collect();
+    Closure::wrap_args(args, |args: &[&Zval]| {
+        args.iter().filter_map(|arg| arg.long()).sum::<i64>()
+    })
+}
+
+pub struct TestClosureState {
+    count: i64,
+}
+
+static CLOSURES_DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+impl Drop for TestClosureState {
+    fn drop(&mut self) {
+        CLOSURES_DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[php_function]
+pub fn test_closure_counter() -> Closure {
+    let mut state = TestClosureState { count: 0 };
+    Closure::wrap(Box::new(move || {
+        state.count += 1;
+        state.count
+    }) as Box<dyn FnMut() -> i64>)
+}
+
+#[php_function]
+pub fn test_closure_dropped() -> usize {
+    CLOSURES_DROPPED.load(Ordering::SeqCst)
+}
+
 #[php_function]
 pub fn test_callable(call: ZendCallable, a: String) -> Zval {
     call.try_call(vec![&a]).expect("Failed to call function")
 }
 
+#[php_function]
+pub fn test_callable_typed(call: Callable<(i64, String), bool>, n: i64) -> PhpResult<Vec<bool>> {
+    (0..n)
+        .map(|i| Ok(call.call((i, format!("row {i}")))?))
+        .collect()
+}
+
+#[php_function]
+pub fn test_call_by_ref(call: &Zval, value: &Zval) -> PhpResult<Vec<Zval>> {
+    let mut value = value.shallow_clone();
+    let result = call.call()?.by_ref(&mut value).invoke()?;
+    Ok(vec![result, value])
+}
+
+#[php_function]
+pub fn test_call_preg_match(pattern: &str, subject: &str) -> PhpResult<Zval> {
+    let preg_match = Function::try_from_function("preg_match").ok_or("Missing preg_match")?;
+    let mut matches = Zval::new();
+    preg_match
+        .call()
+        .arg(pattern)
+        .arg(subject)
+        .by_ref(&mut matches)
+        .named("flags", 256) // PREG_OFFSET_CAPTURE
+        .invoke()?;
+    Ok(matches)
+}
+
+#[php_function]
+pub fn test_call_str_pad(input: &str) -> PhpResult<Zval> {
+    let str_pad = Function::try_from_function("str_pad").ok_or("Missing str_pad")?;
+    Ok(str_pad
+        .call()
+        .arg(input)
+        .named("pad_type", 0) // STR_PAD_LEFT
+        .named("length", 6)
+        .named("pad_string", "-")
+        .invoke()?)
+}
+
+#[php_function]
+pub fn test_call_method(obj: &mut ZendObject, name: &str) -> PhpResult<Zval> {
+    Ok(obj.call_method(name)?.arg(1).named("b", 2).invoke()?)
+}
+
+#[php_function]
+pub fn test_call_static(class: &str, name: &str) -> PhpResult<Zval> {
+    let ce = ClassEntry::try_find(class).ok_or("Missing class")?;
+    Ok(ce.call_static(name)?.arg(1).named("b", 2).invoke()?)
+}
+
+#[php_function]
+pub fn test_class_constant(class: &str, name: &str) -> PhpResult<Zval> {
+    let ce = ClassEntry::try_find(class).ok_or("Missing class")?;
+    Ok(ce.constant(name)?)
+}
+
+#[php_function]
+pub fn test_object_constant(obj: &ZendObject, name: &str) -> PhpResult<Zval> {
+    Ok(obj.get_class_entry().constant(name)?)
+}
+
+#[php_function]
+pub fn test_static_property(class: &str, name: &str) -> PhpResult<Zval> {
+    let ce = ClassEntry::try_find(class).ok_or("Missing class")?;
+    Ok(ce.static_property(name)?)
+}
+
+#[php_function]
+pub fn test_set_static_property(class: &str, name: &str, value: &Zval) -> PhpResult<()> {
+    let ce = ClassEntry::try_find(class).ok_or("Missing class")?;
+    Ok(ce.set_static_property(name, value.shallow_clone())?)
+}
+
 #[php_function]
 pub fn iter_next(ht: &ZendHashTable) -> Vec<Zval> {
     ht.iter()
@@ -119,6 +427,34 @@ pub fn iter_next(ht: &ZendHashTable) -> Vec<Zval> {
         .collect()
 }
 
+#[php_function]
+pub fn iter_pairs(mut iterable: Iterable) -> PhpResult<Vec<Zval>> {
+    let mut pairs = vec![];
+    for pair in &mut iterable {
+        let (key, value) = pair?;
+        pairs.push(key);
+        pairs.push(value);
+    }
+    Ok(pairs)
+}
+
+#[php_function]
+pub fn iter_generator_send(mut generator: ZBox<ZendIterator>) -> PhpResult<Vec<Zval>> {
+    generator.rewind()?;
+    let mut values = vec![generator.get_current_data()?];
+    values.push(generator.send("sent")?);
+    let mut index = Zval::new();
+    index.set_long(generator.index as i64);
+    values.push(index);
+    generator.move_forward()?;
+    while generator.valid()? {
+        values.push(generator.get_current_data()?);
+        generator.move_forward()?;
+    }
+    values.push(generator.get_return()?);
+    Ok(values)
+}
+
 #[php_function]
 pub fn iter_back(ht: &ZendHashTable) -> Vec<Zval> {
     ht.iter()
@@ -150,6 +486,11 @@ pub fn iter_next_back(ht: &ZendHashTable, modulus: usize) -> Vec<Option<Zval>> {
     result
 }
 
+#[php_function]
+pub fn test_array_key(key: ArrayKey) -> Zval {
+    key_to_zval(key)
+}
+
 fn key_to_zval(key: ArrayKey) -> Zval {
     match key {
         ArrayKey::String(s) => {
@@ -165,6 +506,44 @@ fn key_to_zval(key: ArrayKey) -> Zval {
     }
 }
 
+#[php_function(deprecated = "use test_str() instead")]
+pub fn test_deprecated(a: &str) -> &str {
+    a
+}
+
+#[php_function(deprecated)]
+pub fn test_deprecated_silent() -> bool {
+    true
+}
+
+#[php_class]
+pub struct TestDeprecated;
+
+#[php_impl]
+impl TestDeprecated {
+    #[deprecated = "use TestDeprecated::LIMIT instead"]
+    const OLD_LIMIT: i64 = 10;
+    const LIMIT: i64 = 20;
+
+    #[deprecated = "use run() instead"]
+    pub fn old_run() -> i64 {
+        Self::LIMIT
+    }
+
+    #[deprecated]
+    pub fn old_stop() -> i64 {
+        Self::OLD_LIMIT
+    }
+}
+
+#[php_function(namespace = "ExtPhpRs\\Tests")]
+pub fn test_namespaced_function() -> &'static str {
+    "namespaced"
+}
+
+#[php_const(namespace = "ExtPhpRs\\Tests")]
+const TEST_NAMESPACED_CONSTANT: i32 = 42;
+
 #[php_class]
 pub struct TestClass {
     string: String,
@@ -205,16 +584,483 @@ pub fn test_class(string: String, number: i32) -> TestClass {
     }
 }
 
+#[php_function]
+pub fn test_instantiate(class: &str, a: &Zval, b: &Zval) -> PhpResult<ZBox<ZendObject>> {
+    let ce = ClassEntry::try_find(class).ok_or("Missing class")?;
+    Ok(ce.instantiate((a.shallow_clone(), b.shallow_clone()))?)
+}
+
+#[php_function]
+pub fn test_new_with(value: &Zval) -> PhpResult<i64> {
+    let obj =
+        ZendObject::new_with::<registration::TestRegisteredClass, _>((value.shallow_clone(),))?;
+    Ok(obj.doubled())
+}
+
+#[derive(Debug, PhpException)]
+#[php(namespace = "ExtPhpRs\\Tests", name = "TestException")]
+pub enum TestError {
+    #[php(code = 404)]
+    NotFound {
+        path: String,
+        attempts: i64,
+        #[php(skip)]
+        kind: std::io::ErrorKind,
+    },
+    #[php(name = "DeniedException")]
+    PermissionDenied,
+    #[php(ce = "ext_php_rs::zend::ce::value_error()", code = 2)]
+    Invalid(String),
+    Io {
+        #[php(source)]
+        source: std::io::Error,
+    },
+}
+
+impl std::fmt::Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound { path, .. } => write!(f, "{path} not found"),
+            Self::PermissionDenied => write!(f, "permission denied"),
+            Self::Invalid(reason) => write!(f, "invalid: {reason}"),
+            Self::Io { .. } => write!(f, "io failed"),
+        }
+    }
+}
+
+#[php_function]
+pub fn test_exception(kind: &str) -> Result<i64, TestError> {
+    match kind {
+        "not_found" => Err(TestError::NotFound {
+            path: "/tmp/missing".into(),
+            attempts: 3,
+            kind: std::io::ErrorKind::NotFound,
+        }),
+        "denied" => Err(TestError::PermissionDenied),
+        "invalid" => Err(TestError::Invalid(kind.into())),
+        "io" => Err(TestError::Io {
+            source: std::io::Error::new(std::io::ErrorKind::Other, "disk full"),
+        }),
+        _ => Ok(0),
+    }
+}
+
+#[php_function]
+pub fn test_exception_chain(callable: ZendCallable) -> Vec<String> {
+    callable
+        .try_call(vec![])
+        .err()
+        .and_then(|err| err.exception())
+        .map(|ex| {
+            ex.chain()
+                .map(|ex| format!("{}: {}", ex.class_name, ex.message))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[php_function]
+pub fn test_panic(message: String) -> i64 {
+    panic!("{message}")
+}
+
+#[php_function]
+pub fn test_panic_closure() -> Closure {
+    Closure::wrap(Box::new(|| -> i64 { panic!("closure panicked") }) as Box<dyn Fn() -> i64>)
+}
+
+static BAILOUT_DROPPED: AtomicBool = AtomicBool::new(false);
+
+struct BailoutGuard;
+
+impl Drop for BailoutGuard {
+    fn drop(&mut self) {
+        BAILOUT_DROPPED.store(true, Ordering::SeqCst);
+    }
+}
+
+#[php_function]
+pub fn test_bailout(callable: ZendCallable) -> bool {
+    let _guard = BailoutGuard;
+    callable.try_call(vec![]).is_ok()
+}
+
+#[php_function]
+pub fn test_bailout_dropped() -> bool {
+    BAILOUT_DROPPED.load(Ordering::SeqCst)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TestPoint {
+    x: i64,
+    y: i64,
+    label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum TestShape {
+    Empty,
+    Circle(f64),
+    Rect { w: f64, h: f64 },
+}
+
+#[derive(Deserialize)]
+pub struct TestTree {
+    children: Vec<TestTree>,
+}
+
+impl TestTree {
+    fn depth(&self) -> usize {
+        1 + self.children.iter().map(TestTree::depth).max().unwrap_or(0)
+    }
+}
+
+#[php_function]
+pub fn test_serde_points(points: Serde<Vec<TestPoint>>) -> Serde<Vec<TestPoint>> {
+    points
+}
+
+#[php_function]
+pub fn test_serde_shape(shape: Serde<TestShape>) -> Serde<TestShape> {
+    shape
+}
+
+#[php_function]
+pub fn test_serde_map(map: Serde<BTreeMap<String, i64>>) -> Serde<BTreeMap<String, i64>> {
+    map
+}
+
+#[php_function]
+pub fn test_serde_tree(tree: Serde<TestTree>) -> usize {
+    tree.depth()
+}
+
+#[php_function]
+pub fn test_php_value(value: PhpValue) -> PhpValue {
+    std::thread::spawn(move || value)
+        .join()
+        .expect("Failed to send the value to another thread")
+}
+
+#[php_function]
+pub fn test_php_value_class(class: String) -> PhpValue {
+    PhpValue::Object {
+        class,
+        props: Default::default(),
+    }
+}
+
+#[php_function]
+pub fn test_php_value_eq(a: PhpValue, b: PhpValue) -> bool {
+    let hash = |value: &PhpValue| {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    };
+    a == b && hash(&a) == hash(&b)
+}
+
+#[php_function]
+pub fn test_zval_loose_eq(a: &Zval, b: &Zval) -> PhpResult<bool> {
+    Ok(a.loose_eq(b)?)
+}
+
+#[php_function]
+pub fn test_zval_compare(a: &Zval, b: &Zval) -> PhpResult<i64> {
+    Ok(a.compare(b)? as i64)
+}
+
+#[php_function]
+pub fn test_zval_add(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.add(b)?)
+}
+
+#[php_function]
+pub fn test_zval_sub(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.sub(b)?)
+}
+
+#[php_function]
+pub fn test_zval_mul(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.mul(b)?)
+}
+
+#[php_function]
+pub fn test_zval_div(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.div(b)?)
+}
+
+#[php_function]
+pub fn test_zval_rem(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.rem(b)?)
+}
+
+#[php_function]
+pub fn test_zval_pow(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.pow(b)?)
+}
+
+#[php_function]
+pub fn test_zval_concat(a: &Zval, b: &Zval) -> PhpResult<Zval> {
+    Ok(a.concat(b)?)
+}
+
+#[php_function]
+pub fn test_zval_export(value: &Zval) -> PhpResult<Zval> {
+    let mut zv = Zval::new();
+    zv.set_zend_string(value.to_php_literal()?);
+    Ok(zv)
+}
+
+#[php_function]
+pub fn test_zval_dump(value: &Zval) -> PhpResult<Zval> {
+    let mut zv = Zval::new();
+    zv.set_zend_string(value.dump()?);
+    Ok(zv)
+}
+
+#[php_function]
+pub fn test_zval_print_r(value: &Zval) -> PhpResult<Zval> {
+    let mut zv = Zval::new();
+    zv.set_zend_string(value.print_r()?);
+    Ok(zv)
+}
+
+#[php_function]
+pub fn test_str_builder(parts: Vec<String>, repeat: usize) -> PhpResult<Zval> {
+    let mut builder = ZendStrBuilder::with_capacity(16, false);
+    for _ in 0..repeat {
+        for part in &parts {
+            std::fmt::Write::write_fmt(&mut builder, format_args!("{part},"))
+                .map_err(|e| e.to_string())?;
+        }
+        std::io::Write::write_all(&mut builder, b"\0;").map_err(|e| e.to_string())?;
+    }
+    let mut zv = Zval::new();
+    zv.set_zend_string(builder.build());
+    Ok(zv)
+}
+
+#[php_function]
+pub fn test_str_make_mut(value: &str) -> PhpResult<(String, String, bool)> {
+    let mut original = ZendStr::new(value, false);
+    // Shares the string, like a second zval holding it would.
+    original.inc_count();
+    let mut shared = unsafe { ZBox::from_raw(original.as_mut_ptr()) };
+
+    ZendStr::make_mut(&mut shared).make_ascii_uppercase();
+    let separated = shared.as_ptr() != original.as_ptr();
+
+    let ptr = shared.as_ptr();
+    if let Some(first) = ZendStr::make_mut(&mut shared).first_mut() {
+        *first = b'_';
+    }
+    let in_place = shared.as_ptr() == ptr;
+
+    Ok((
+        original.as_str()?.to_string(),
+        shared.as_str()?.to_string(),
+        separated && in_place,
+    ))
+}
+
+pub struct TestCounter {
+    count: i64,
+}
+
+static COUNTERS_DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+impl Drop for TestCounter {
+    fn drop(&mut self) {
+        COUNTERS_DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[php_function]
+pub fn test_resource_new(count: i64) -> PhpResult<ZBox<Resource<TestCounter>>> {
+    Ok(Resource::new(TestCounter { count })?)
+}
+
+#[php_function]
+pub fn test_resource_incr(counter: &mut Resource<TestCounter>) -> Option<i64> {
+    let counter = counter.get_mut()?;
+    counter.count += 1;
+    Some(counter.count)
+}
+
+#[php_function]
+pub fn test_resource_close(counter: &mut Resource<TestCounter>) -> Option<i64> {
+    counter.close().map(|counter| counter.count)
+}
+
+#[php_function]
+pub fn test_resource_dropped() -> usize {
+    COUNTERS_DROPPED.load(Ordering::SeqCst)
+}
+
 #[php_module]
 pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
     module
+        .function(wrap_function!(test_str))
+        .function(wrap_function!(test_string))
+        .function(wrap_function!(test_bool))
+        .function(wrap_function!(test_number_signed))
+        .function(wrap_function!(test_number_unsigned))
+        .function(wrap_function!(test_number_float))
+        .function(wrap_function!(test_array))
+        .function(wrap_function!(test_array_assoc))
+        .function(wrap_function!(test_array_reverse))
+        .function(wrap_function!(test_array_ksort))
+        .function(wrap_function!(test_array_sort))
+        .function(wrap_function!(test_array_retain_odd))
+        .function(wrap_function!(test_array_splice))
+        .function(wrap_function!(test_array_merge))
+        .function(wrap_function!(test_array_remove_first))
+        .function(wrap_function!(test_array_count_values))
+        .function(wrap_function!(test_array_separate))
+        .function(wrap_function!(test_packed_sum))
+        .function(wrap_function!(test_packed_scale))
+        .function(wrap_function!(test_tuple_pairs))
+        .function(wrap_function!(test_fixed_array))
+        .function(wrap_function!(test_sets))
+        .function(wrap_function!(test_int_keys))
+        .function(wrap_function!(test_unsigned_keys))
+        .function(wrap_function!(test_cow))
+        .function(wrap_function!(test_lossy_string))
+        .function(wrap_function!(test_bytes))
+        .function(wrap_function!(test_bytes_owned))
+        .function(wrap_function!(test_os_str))
+        .function(wrap_function!(test_path))
+        .function(wrap_function!(test_binary))
+        .function(wrap_function!(test_nullable))
+        .function(wrap_function!(test_args))
+        .function(wrap_function!(test_object))
+        .function(wrap_function!(test_globals_http_get))
+        .function(wrap_function!(test_globals_http_post))
+        .function(wrap_function!(test_globals_http_cookie))
+        .function(wrap_function!(test_globals_http_server))
+        .function(wrap_function!(test_globals_http_request))
+        .function(wrap_function!(test_globals_http_files))
+        .function(wrap_function!(test_closure))
+        .function(wrap_function!(test_closure_once))
+        .function(wrap_function!(test_closure_typed))
+        .function(wrap_function!(test_closure_variadic))
+        .function(wrap_function!(test_closure_args))
+        .function(wrap_function!(test_closure_counter))
+        .function(wrap_function!(test_closure_dropped))
+        .function(wrap_function!(test_callable))
+        .function(wrap_function!(test_callable_typed))
+        .function(wrap_function!(test_call_by_ref))
+        .function(wrap_function!(test_call_preg_match))
+        .function(wrap_function!(test_call_str_pad))
+        .function(wrap_function!(test_call_method))
+        .function(wrap_function!(test_call_static))
+        .function(wrap_function!(test_class_constant))
+        .function(wrap_function!(test_object_constant))
+        .function(wrap_function!(test_static_property))
+        .function(wrap_function!(test_set_static_property))
+        .function(wrap_function!(iter_next))
+        .function(wrap_function!(iter_pairs))
+        .function(wrap_function!(iter_generator_send))
+        .function(wrap_function!(iter_back))
+        .function(wrap_function!(iter_next_back))
+        .function(wrap_function!(test_array_key))
+        .function(wrap_function!(test_deprecated))
+        .function(wrap_function!(test_deprecated_silent))
+        .function(wrap_function!(test_namespaced_function))
+        .function(wrap_function!(test_class))
+        .function(wrap_function!(test_instantiate))
+        .function(wrap_function!(test_new_with))
+        .function(wrap_function!(test_exception))
+        .function(wrap_function!(test_exception_chain))
+        .function(wrap_function!(test_panic))
+        .function(wrap_function!(test_panic_closure))
+        .function(wrap_function!(test_bailout))
+        .function(wrap_function!(test_bailout_dropped))
+        .function(wrap_function!(test_serde_points))
+        .function(wrap_function!(test_serde_shape))
+        .function(wrap_function!(test_serde_map))
+        .function(wrap_function!(test_serde_tree))
+        .function(wrap_function!(test_php_value))
+        .function(wrap_function!(test_php_value_class))
+        .function(wrap_function!(test_php_value_eq))
+        .function(wrap_function!(test_zval_loose_eq))
+        .function(wrap_function!(test_zval_compare))
+        .function(wrap_function!(test_zval_add))
+        .function(wrap_function!(test_zval_sub))
+        .function(wrap_function!(test_zval_mul))
+        .function(wrap_function!(test_zval_div))
+        .function(wrap_function!(test_zval_rem))
+        .function(wrap_function!(test_zval_pow))
+        .function(wrap_function!(test_zval_concat))
+        .function(wrap_function!(test_zval_export))
+        .function(wrap_function!(test_zval_dump))
+        .function(wrap_function!(test_zval_print_r))
+        .function(wrap_function!(test_str_builder))
+        .function(wrap_function!(test_str_make_mut))
+        .function(wrap_function!(test_resource_new))
+        .function(wrap_function!(test_resource_incr))
+        .function(wrap_function!(test_resource_close))
+        .function(wrap_function!(test_resource_dropped))
+        .class::<TestClass>()
+        .class::<TestDeprecated>()
+        .constant(wrap_constant!(TEST_NAMESPACED_CONSTANT))
+        .exception::<TestError>()
+        .resource::<TestCounter>("test counter")
+        .function(wrap_function!(registration::test_registered_function))
+        .class::<registration::TestRegisteredClass>()
+        .constant(wrap_constant!(registration::TEST_REGISTERED_CONSTANT))
+        .startup_function(registration::startup)
+}
+
+/// Items declared in another module, after the module function.
+mod registration {
+    use ext_php_rs::prelude::*;
+
+    #[php_function]
+    pub fn test_registered_function() -> &'static str {
+        "registered"
+    }
+
+    #[php_const]
+    pub const TEST_REGISTERED_CONSTANT: &str = "registered";
+
+    /// Fails the extension startup unless the classes of the module have been
+    /// registered before it is called.
+    #[php_startup]
+    pub fn startup() -> Result<(), String> {
+        if ext_php_rs::zend::ClassEntry::try_find("TestRegisteredClass").is_none() {
+            return Err("`TestRegisteredClass` is not registered".into());
+        }
+
+        true.register_constant("TEST_STARTUP_AFTER_REGISTRATION", module_number)
+            .map_err(|e| e.to_string())
+    }
+
+    #[php_class]
+    pub struct TestRegisteredClass {
+        value: i64,
+    }
+
+    #[php_impl]
+    impl TestRegisteredClass {
+        const DOUBLE: i64 = 2;
+
+        pub fn __construct(value: i64) -> Self {
+            Self { value }
+        }
+
+        pub fn doubled(&self) -> i64 {
+            self.value * Self::DOUBLE
+        }
+    }
 }
 
 #[cfg(test)]
 mod integration {
     use std::env;
 
-    use std::process::Command;
+    use std::process::{Command, Output};
     use std::sync::Once;
 
     static BUILD: Once = Once::new();
@@ -231,6 +1077,25 @@ mod integration {
     }
 
     pub fn run_php(file: &str) -> bool {
+        let output = php_output(file);
+        if output.status.success() {
+            true
+        } else {
+            panic!(
+                "
+                status: {}
+                stdout: {}
+                stderr: {}
+                ",
+                output.status,
+                String::from_utf8(output.stdout).unwrap(),
+                String::from_utf8(output.stderr).unwrap()
+            );
+        }
+    }
+
+    /// Runs a PHP file, returning its output whether it succeeded or not.
+    pub fn php_output(file: &str) -> Output {
         setup();
         let mut path = env::current_dir().expect("Could not get cwd");
         path.pop();
@@ -242,41 +1107,41 @@ mod integration {
             "libtests"
         });
         path.set_extension(std::env::consts::DLL_EXTENSION);
-        let output = Command::new("php")
+        Command::new("php")
             .arg(format!("-dextension={}", path.to_str().unwrap()))
             .arg("-dassert.active=1")
             .arg("-dassert.exception=1")
             .arg("-dzend.assertions=1")
             .arg(format!("src/integration/{}", file))
             .output()
-            .expect("failed to run php file");
-        if output.status.success() {
-            true
-        } else {
-            panic!(
-                "
-                status: {}
-                stdout: {}
-                stderr: {}
-                ",
-                output.status,
-                String::from_utf8(output.stdout).unwrap(),
-                String::from_utf8(output.stderr).unwrap()
-            );
-        }
+            .expect("failed to run php file")
     }
 
+    mod args;
     mod array;
+    mod bailout;
     mod binary;
     mod bool;
     mod callable;
     mod class;
     mod closure;
+    mod coercion;
+    mod conversions;
+    mod deprecated;
+    mod exception;
     mod globals;
     mod iterator;
+    mod namespace;
     mod nullable;
     mod number;
     mod object;
+    mod operators;
+    mod panic;
+    mod php_value;
+    mod registration;
+    mod render;
+    mod resource;
+    mod serde;
     mod string;
     mod types;
 }